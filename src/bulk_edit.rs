@@ -0,0 +1,84 @@
+use crate::task::{Task, TaskStatus};
+
+/// Changes to apply to every selected task. `None` leaves a field alone.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BulkEdit {
+    pub status: Option<TaskStatus>,
+    pub assignee: Option<String>,
+    /// Working days; negative moves earlier.
+    pub shift_days: Option<i32>,
+    pub percent_complete: Option<u32>,
+    pub priority: Option<u32>,
+    /// Added as a new line at the end of the description.
+    pub note: Option<String>,
+}
+
+impl BulkEdit {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Applies the changes to `task`. Summary tasks keep their dates and
+    /// progress, which come from their subtasks.
+    pub fn apply(&self, task: &mut Task, summary: bool) {
+        if let Some(status) = self.status {
+            task.status = status;
+        }
+        if let Some(assignee) = &self.assignee {
+            task.assignee = assignee.trim().to_string();
+        }
+        if let Some(priority) = self.priority {
+            task.priority = priority;
+        }
+        if let Some(note) = self.note.as_deref().map(str::trim).filter(|n| !n.is_empty()) {
+            if !task.description.is_empty() {
+                task.description.push('\n');
+            }
+            task.description.push_str(note);
+        }
+        if summary {
+            return;
+        }
+        if let Some(days) = self.shift_days {
+            task.shift(days);
+        }
+        if let Some(percent) = self.percent_complete {
+            task.percent_complete = percent.min(100);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::{at_finish, at_start};
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_bulk_edit_apply() {
+        let mut task = Task::new("Build".to_string());
+        task.description = "Phase 1".to_string();
+        task.start = at_start(NaiveDate::from_ymd_opt(2024, 1, 5).unwrap());
+        task.finish = at_finish(NaiveDate::from_ymd_opt(2024, 1, 8).unwrap());
+        let edit = BulkEdit {
+            assignee: Some(" Dana ".to_string()),
+            shift_days: Some(1),
+            percent_complete: Some(40),
+            note: Some("Moved after review".to_string()),
+            ..BulkEdit::default()
+        };
+
+        let mut summary = task.clone();
+        edit.apply(&mut task, false);
+        assert_eq!(task.assignee, "Dana");
+        assert_eq!(task.start, at_start(NaiveDate::from_ymd_opt(2024, 1, 8).unwrap()));
+        assert_eq!(task.finish, at_finish(NaiveDate::from_ymd_opt(2024, 1, 9).unwrap()));
+        assert_eq!(task.percent_complete, 40);
+        assert_eq!(task.description, "Phase 1\nMoved after review");
+
+        edit.apply(&mut summary, true);
+        assert_eq!(summary.start, at_start(NaiveDate::from_ymd_opt(2024, 1, 5).unwrap()));
+        assert_eq!(summary.percent_complete, 0);
+        assert_eq!(summary.assignee, "Dana");
+    }
+}
@@ -0,0 +1,391 @@
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use crate::task::{day_fraction, Task};
+
+/// Device-independent drawing of the schedule, rendered by the SVG, PNG and
+/// PDF exporters. Coordinates are in points with the origin top-left.
+pub struct Chart {
+    pub width: f32,
+    pub height: f32,
+    pub shapes: Vec<Shape>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    pub fn hex(self) -> String {
+        format!("#{:02x}{:02x}{:02x}", self.0, self.1, self.2)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Anchor {
+    Start,
+    Middle,
+}
+
+pub enum Shape {
+    Rect { x: f32, y: f32, w: f32, h: f32, fill: Rgb },
+    Line { points: Vec<(f32, f32)>, color: Rgb, width: f32 },
+    Polygon { points: Vec<(f32, f32)>, fill: Rgb },
+    /// `y` is the text baseline.
+    Text { x: f32, y: f32, text: String, size: f32, color: Rgb, anchor: Anchor, bold: bool },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TimeScale {
+    Day,
+    Week,
+    Month,
+}
+
+impl TimeScale {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "day" | "days" => Some(TimeScale::Day),
+            "week" | "weeks" => Some(TimeScale::Week),
+            "month" | "months" => Some(TimeScale::Month),
+            _ => None,
+        }
+    }
+
+    fn points_per_day(self) -> f32 {
+        match self {
+            TimeScale::Day => 18.0,
+            TimeScale::Week => 5.0,
+            TimeScale::Month => 1.6,
+        }
+    }
+}
+
+/// Chart settings for the SVG, PNG and PDF exporters.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChartOptions {
+    pub title: String,
+    /// `None` picks a scale from the schedule's length.
+    pub scale: Option<TimeScale>,
+    pub highlight_critical: bool,
+}
+
+impl Default for ChartOptions {
+    fn default() -> Self {
+        Self {
+            title: "Merged Project".to_string(),
+            scale: None,
+            highlight_critical: true,
+        }
+    }
+}
+
+const MARGIN: f32 = 20.0;
+const TITLE_HEIGHT: f32 = 32.0;
+const HEADER_HEIGHT: f32 = 28.0;
+const ROW_HEIGHT: f32 = 20.0;
+const LEGEND_HEIGHT: f32 = 36.0;
+const INDENT: f32 = 12.0;
+const FONT_SIZE: f32 = 10.0;
+
+const TEXT: Rgb = Rgb(34, 34, 34);
+const MUTED: Rgb = Rgb(120, 120, 120);
+const GRID: Rgb = Rgb(225, 225, 225);
+const STRIPE: Rgb = Rgb(247, 247, 250);
+const BAR: Rgb = Rgb(155, 181, 232);
+const PROGRESS: Rgb = Rgb(61, 89, 161);
+const CRITICAL: Rgb = Rgb(219, 75, 75);
+const CRITICAL_PROGRESS: Rgb = Rgb(150, 35, 35);
+const SUMMARY: Rgb = Rgb(60, 60, 60);
+const MILESTONE: Rgb = Rgb(208, 140, 31);
+const LINK: Rgb = Rgb(110, 110, 110);
+const TODAY: Rgb = Rgb(247, 118, 142);
+
+/// Rough text width for layout; renderers use their own font metrics.
+pub fn approx_text_width(text: &str, size: f32) -> f32 {
+    text.chars().count() as f32 * size * 0.52
+}
+
+pub fn build_chart(tasks: &[Task], options: &ChartOptions) -> Chart {
+    let mut shapes = Vec::new();
+
+    let today = chrono::Local::now().date_naive();
+    let first = tasks.iter().map(|t| t.start.date()).min().unwrap_or(today);
+    let last = tasks.iter().map(|t| t.finish.max(t.start).date()).max().unwrap_or(today);
+    let scale = options.scale.unwrap_or(match (last - first).num_days() {
+        0..=60 => TimeScale::Day,
+        61..=365 => TimeScale::Week,
+        _ => TimeScale::Month,
+    });
+    let ppd = scale.points_per_day();
+
+    // Start on a Monday / the 1st so the first tick has a label
+    let range_start = match scale {
+        TimeScale::Month => first.with_day(1).unwrap_or(first),
+        _ => first - Duration::days(first.weekday().num_days_from_monday() as i64),
+    };
+    let range_end = last + Duration::days(7);
+    let day_count = (range_end - range_start).num_days() + 1;
+
+    let levels: Vec<usize> = tasks.iter().map(|t| t.outline_level()).collect();
+    let summaries: HashSet<usize> = (0..tasks.len())
+        .filter(|&i| !tasks[i].wbs.is_empty() && {
+            let prefix = format!("{}.", tasks[i].wbs);
+            tasks.iter().any(|t| t.wbs.starts_with(&prefix))
+        })
+        .collect();
+    let critical = if options.highlight_critical {
+        crate::critical_path::critical_tasks(tasks)
+    } else {
+        HashSet::new()
+    };
+
+    let name_width = tasks.iter()
+        .zip(&levels)
+        .map(|(t, level)| approx_text_width(&t.name, FONT_SIZE) + (level - 1) as f32 * INDENT)
+        .fold(0.0f32, f32::max)
+        .clamp(160.0, 320.0)
+        + 10.0;
+
+    let chart_x = MARGIN + name_width;
+    let chart_y = MARGIN + TITLE_HEIGHT + HEADER_HEIGHT;
+    let chart_w = day_count as f32 * ppd;
+    let chart_h = tasks.len() as f32 * ROW_HEIGHT;
+    let legend: Vec<&str> = ["Task", "Progress", "Critical", "Summary", "Milestone", "Dependency", "Today"]
+        .into_iter()
+        .filter(|&item| item != "Critical" || options.highlight_critical)
+        .collect();
+    let legend_width: f32 = legend.iter().map(|label| 34.0 + approx_text_width(label, 9.0)).sum();
+    let width = (chart_x + chart_w).max(MARGIN + legend_width) + MARGIN;
+    let height = chart_y + chart_h + LEGEND_HEIGHT + MARGIN;
+
+    let x_of = |date: NaiveDate| chart_x + (date - range_start).num_days() as f32 * ppd;
+    let row_y = |row: usize| chart_y + row as f32 * ROW_HEIGHT;
+
+    shapes.push(Shape::Text {
+        x: MARGIN,
+        y: MARGIN + 18.0,
+        text: options.title.clone(),
+        size: 16.0,
+        color: TEXT,
+        anchor: Anchor::Start,
+        bold: true,
+    });
+
+    // Row stripes
+    for row in (1..tasks.len()).step_by(2) {
+        shapes.push(Shape::Rect { x: MARGIN, y: row_y(row), w: width - 2.0 * MARGIN, h: ROW_HEIGHT, fill: STRIPE });
+    }
+
+    // Timescale and grid
+    for offset in 0..day_count {
+        let date = range_start + Duration::days(offset);
+        let label = match scale {
+            TimeScale::Day => Some(date.format("%d").to_string()),
+            TimeScale::Week if date.weekday() == Weekday::Mon => Some(date.format("%d %b").to_string()),
+            TimeScale::Month if date.day() == 1 => Some(date.format("%b %Y").to_string()),
+            _ => None,
+        };
+        let Some(label) = label else { continue };
+        let x = x_of(date);
+        shapes.push(Shape::Line {
+            points: vec![(x, chart_y), (x, chart_y + chart_h)],
+            color: GRID,
+            width: 0.5,
+        });
+        // Day numbers sit centred over their column, coarser labels start at the tick
+        let (label_x, anchor) = match scale {
+            TimeScale::Day => (x + ppd * 0.5, Anchor::Middle),
+            _ => (x + 2.0, Anchor::Start),
+        };
+        shapes.push(Shape::Text {
+            x: label_x,
+            y: chart_y - 8.0,
+            text: label,
+            size: 8.0,
+            color: MUTED,
+            anchor,
+            bold: false,
+        });
+        // Month names above the day numbers
+        if scale == TimeScale::Day && (date.day() == 1 || offset == 0) {
+            shapes.push(Shape::Text {
+                x: x + 2.0,
+                y: chart_y - HEADER_HEIGHT + 6.0,
+                text: date.format("%b %Y").to_string(),
+                size: 8.0,
+                color: MUTED,
+                anchor: Anchor::Start,
+                bold: true,
+            });
+        }
+    }
+    shapes.push(Shape::Line {
+        points: vec![(MARGIN, chart_y), (width - MARGIN, chart_y)],
+        color: MUTED,
+        width: 0.75,
+    });
+
+    // Names and bars
+    let mut bar_rects: HashMap<i32, (f32, f32, f32, f32)> = HashMap::new();
+    for (row, task) in tasks.iter().enumerate() {
+        let top = row_y(row);
+        let mid = top + ROW_HEIGHT * 0.5;
+        let is_summary = summaries.contains(&row);
+        let is_critical = critical.contains(&row);
+
+        let indent = (levels[row] - 1) as f32 * INDENT;
+        shapes.push(Shape::Text {
+            x: MARGIN + 4.0 + indent,
+            y: mid + FONT_SIZE * 0.35,
+            text: truncate(&task.name, name_width - indent - 10.0),
+            size: FONT_SIZE,
+            color: TEXT,
+            anchor: Anchor::Start,
+            bold: is_summary,
+        });
+
+        // Bars start and end part-way through a day when the times say so
+        let start = task.start;
+        let end = task.finish.max(start);
+        let x0 = x_of(start.date()) + ppd * day_fraction(start);
+        let x1 = x_of(end.date()) + ppd * day_fraction(end);
+
+        if task.milestone {
+            let cx = x0 + ppd * 0.5;
+            let r = ROW_HEIGHT * 0.3;
+            shapes.push(Shape::Polygon {
+                points: vec![(cx, mid - r), (cx + r, mid), (cx, mid + r), (cx - r, mid)],
+                fill: if is_critical { CRITICAL } else { MILESTONE },
+            });
+            shapes.push(Shape::Text {
+                x: cx + r + 4.0,
+                y: mid + 3.0,
+                text: start.format("%d %b").to_string(),
+                size: 8.0,
+                color: MUTED,
+                anchor: Anchor::Start,
+                bold: false,
+            });
+            bar_rects.insert(task.uid, (cx - r, mid - r, 2.0 * r, 2.0 * r));
+        } else if is_summary {
+            // Bracket-style summary bar
+            let (y, h) = (mid - 3.0, 5.0);
+            shapes.push(Shape::Rect { x: x0, y, w: x1 - x0, h, fill: SUMMARY });
+            for x in [x0, x1] {
+                shapes.push(Shape::Polygon {
+                    points: vec![(x - 4.0, y), (x + 4.0, y), (x, y + h + 5.0)],
+                    fill: SUMMARY,
+                });
+            }
+            bar_rects.insert(task.uid, (x0, y, x1 - x0, h));
+        } else {
+            let (y, h) = (top + ROW_HEIGHT * 0.22, ROW_HEIGHT * 0.56);
+            let (fill, progress_fill) = if is_critical { (CRITICAL, CRITICAL_PROGRESS) } else { (BAR, PROGRESS) };
+            shapes.push(Shape::Rect { x: x0, y, w: x1 - x0, h, fill });
+            let progress = (x1 - x0) * task.percent_complete.min(100) as f32 / 100.0;
+            if progress > 0.0 {
+                shapes.push(Shape::Rect { x: x0, y: mid - 2.0, w: progress, h: 4.0, fill: progress_fill });
+            }
+            bar_rects.insert(task.uid, (x0, y, x1 - x0, h));
+        }
+    }
+
+    // Dependency lines
+    for (row, task) in tasks.iter().enumerate() {
+        let Some(&to) = bar_rects.get(&task.uid) else { continue };
+        for pred in &task.predecessors {
+            let Some(&from) = bar_rects.get(&pred.predecessor_uid) else { continue };
+            let from_row = tasks.iter().position(|t| t.uid == pred.predecessor_uid).unwrap_or(row);
+            let color = if critical.contains(&row) && critical.contains(&from_row) { CRITICAL } else { LINK };
+            // MSPDI link types: 0 = FF, 1 = FS, 2 = SF, 3 = SS
+            let (out_of_end, into_start) = match pred.link_type {
+                0 => (true, false),
+                2 => (false, false),
+                3 => (false, true),
+                _ => (true, true),
+            };
+            let from_pt = (if out_of_end { from.0 + from.2 } else { from.0 }, from.1 + from.3 * 0.5);
+            let to_pt = (if into_start { to.0 } else { to.0 + to.2 }, to.1 + to.3 * 0.5);
+            let leave_x = if out_of_end { from_pt.0 + 5.0 } else { from_pt.0 - 5.0 };
+            let approach_x = if into_start { to_pt.0 - 5.0 } else { to_pt.0 + 5.0 };
+            shapes.push(Shape::Line {
+                points: vec![from_pt, (leave_x, from_pt.1), (leave_x, to_pt.1), (approach_x, to_pt.1), to_pt],
+                color,
+                width: 0.75,
+            });
+            let dir = if into_start { 1.0 } else { -1.0 };
+            shapes.push(Shape::Polygon {
+                points: vec![to_pt, (to_pt.0 - 5.0 * dir, to_pt.1 - 3.0), (to_pt.0 - 5.0 * dir, to_pt.1 + 3.0)],
+                fill: color,
+            });
+        }
+    }
+
+    // Today line
+    if today >= range_start && today <= range_end {
+        let x = x_of(today) + ppd * 0.5;
+        shapes.push(Shape::Line { points: vec![(x, chart_y - 4.0), (x, chart_y + chart_h)], color: TODAY, width: 1.5 });
+    }
+
+    // Legend
+    let legend_y = chart_y + chart_h + 22.0;
+    let mut x = MARGIN;
+    for label in legend {
+        let (y, w) = (legend_y, 14.0);
+        shapes.push(match label {
+            "Task" => Shape::Rect { x, y: y - 5.0, w, h: 10.0, fill: BAR },
+            "Progress" => Shape::Rect { x, y: y - 2.0, w, h: 4.0, fill: PROGRESS },
+            "Critical" => Shape::Rect { x, y: y - 5.0, w, h: 10.0, fill: CRITICAL },
+            "Summary" => Shape::Rect { x, y: y - 2.5, w, h: 5.0, fill: SUMMARY },
+            "Milestone" => Shape::Polygon {
+                points: vec![(x + 7.0, y - 6.0), (x + 13.0, y), (x + 7.0, y + 6.0), (x + 1.0, y)],
+                fill: MILESTONE,
+            },
+            "Dependency" => Shape::Line { points: vec![(x, y), (x + w, y)], color: LINK, width: 0.75 },
+            _ => Shape::Line { points: vec![(x + 7.0, y - 6.0), (x + 7.0, y + 6.0)], color: TODAY, width: 1.5 },
+        });
+        shapes.push(Shape::Text {
+            x: x + 20.0,
+            y: y + 3.0,
+            text: label.to_string(),
+            size: 9.0,
+            color: TEXT,
+            anchor: Anchor::Start,
+            bold: false,
+        });
+        x += 34.0 + approx_text_width(label, 9.0);
+    }
+
+    Chart { width, height, shapes }
+}
+
+fn truncate(text: &str, max_width: f32) -> String {
+    if approx_text_width(text, FONT_SIZE) <= max_width {
+        return text.to_string();
+    }
+    let keep = ((max_width / (FONT_SIZE * 0.52)) as usize).saturating_sub(1);
+    format!("{}…", text.chars().take(keep).collect::<String>())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_chart_indents_children() {
+        let mut parent = Task::new("Phase".to_string());
+        parent.wbs = "1".to_string();
+        let mut child = Task::new("Design".to_string());
+        child.wbs = "1.1".to_string();
+
+        let chart = build_chart(&[parent, child], &ChartOptions::default());
+        let x_of = |name: &str| chart.shapes.iter().find_map(|s| match s {
+            Shape::Text { x, text, .. } if text == name => Some(*x),
+            _ => None,
+        });
+
+        assert_eq!(x_of("Design").unwrap() - x_of("Phase").unwrap(), INDENT);
+        assert!(chart.width > 0.0 && chart.height > 0.0);
+    }
+}
@@ -0,0 +1,277 @@
+use std::path::{Path, PathBuf};
+use crate::chart::TimeScale;
+use crate::dates::DateLocale;
+use crate::error::FileError;
+use crate::merge::{ActualsPolicy, LoadedFile, MergeOptions};
+use crate::registry::{ExportOptions, ImportOptions, Registry};
+use crate::task::Task;
+
+const USAGE: &str = "\
+Usage:
+  msproject-merge                                   Launch the GUI
+  msproject-merge merge <base> <overlay>... -o <output>
+                                                    Merge overlays into the base project
+  msproject-merge convert <input> -o <output>       Convert between formats
+  msproject-merge diff <old> <new> [-o <report>]    Compare two schedules; the report is
+                                                    Markdown, or CSV for a .csv file
+  msproject-merge formats                           List supported formats
+  msproject-merge help                              Show this message
+
+Input formats are detected from file content; the output format comes from
+the output file's extension.
+
+Import options:
+  --dates <order>       How Excel dates typed as text are read: auto (default;
+                        decided per sheet), mdy for US or dmy for European
+
+Merge options:
+  --actuals <policy>    overlay: overlay actual start/finish and durations
+                        replace the base's (default); base: overlays only
+                        fill in missing actuals
+
+iCalendar (.ics) options:
+  --milestones-only     Only export milestones
+  --assignee <name>     Only export tasks assigned to <name>
+  --todos               Write tasks as to-dos instead of events
+  --project <name>      Project named in event UIDs, so several projects can
+                        share a calendar (default: the base file's name)
+
+Gantt chart (.svg, .png, .pdf) options:
+  --title <text>        Chart title
+  --scale <unit>        Timescale: day, week or month (default: by project length)
+  --no-critical         Don't highlight the critical path
+
+Excel (.xlsx) options:
+  --status-date <date>  Measure earned value at YYYY-MM-DD (default: today)";
+
+/// Parsed `<inputs>... -o <output>` arguments shared by the commands.
+struct Args {
+    inputs: Vec<PathBuf>,
+    output: Option<PathBuf>,
+    options: ExportOptions,
+    merge_options: MergeOptions,
+    import_options: ImportOptions,
+}
+
+fn parse_args(args: &[String]) -> Result<Args, String> {
+    let mut inputs = Vec::new();
+    let mut output = None;
+    let mut options = ExportOptions::default();
+    let mut merge_options = MergeOptions::default();
+    let mut import_options = ImportOptions::default();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "-o" | "--output" => {
+                let value = iter.next().ok_or(format!("{} needs a file name", arg))?;
+                output = Some(PathBuf::from(value));
+            }
+            "--milestones-only" => options.calendar.milestones_only = true,
+            "--todos" => options.calendar.tasks_as_todos = true,
+            "--assignee" => {
+                let value = iter.next().ok_or("--assignee needs a name")?;
+                options.calendar.assignee = Some(value.clone());
+            }
+            "--project" => {
+                let value = iter.next().ok_or("--project needs a name")?;
+                options.calendar.project = value.clone();
+            }
+            "--title" => {
+                let value = iter.next().ok_or("--title needs a value")?;
+                options.chart.title = value.clone();
+            }
+            "--scale" => {
+                let value = iter.next().ok_or("--scale needs a unit")?;
+                options.chart.scale = Some(TimeScale::parse(value)
+                    .ok_or(format!("Unknown scale: {} (expected day, week or month)", value))?);
+            }
+            "--no-critical" => options.chart.highlight_critical = false,
+            "--status-date" => {
+                let value = iter.next().ok_or("--status-date needs a date")?;
+                options.status_date = Some(chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                    .map_err(|_| format!("Invalid status date: {} (expected YYYY-MM-DD)", value))?);
+            }
+            "--actuals" => {
+                merge_options.actuals = match iter.next().map(String::as_str) {
+                    Some("overlay") => ActualsPolicy::OverlayWins,
+                    Some("base") => ActualsPolicy::FillGaps,
+                    _ => return Err("--actuals needs overlay or base".to_string()),
+                };
+            }
+            "--dates" => {
+                import_options.date_locale = match iter.next().map(String::as_str) {
+                    Some("auto") => DateLocale::Auto,
+                    Some("mdy") => DateLocale::MonthFirst,
+                    Some("dmy") => DateLocale::DayFirst,
+                    _ => return Err("--dates needs auto, mdy or dmy".to_string()),
+                };
+            }
+            flag if flag.starts_with('-') => return Err(format!("Unknown option: {}", flag)),
+            path => inputs.push(PathBuf::from(path)),
+        }
+    }
+    Ok(Args { inputs, output, options, merge_options, import_options })
+}
+
+/// First arguments that select the command line interface. Anything else,
+/// such as a file opened with the app or macOS's `-psn_` argument, leaves
+/// the GUI to start as usual.
+const COMMANDS: [&str; 7] = ["merge", "convert", "diff", "formats", "help", "-h", "--help"];
+
+pub fn is_command(arg: &str) -> bool {
+    COMMANDS.contains(&arg)
+}
+
+/// Runs a command line invocation and returns the process exit code.
+pub fn run(args: &[String]) -> i32 {
+    let registry = Registry::with_builtin_formats();
+    let (command, rest) = match args.split_first() {
+        Some((command, rest)) => (command.as_str(), rest),
+        None => ("help", &[][..]),
+    };
+
+    let result = match command {
+        "merge" => merge(&registry, rest),
+        "convert" => convert(&registry, rest),
+        "diff" => diff(&registry, rest),
+        "formats" => {
+            print_formats(&registry);
+            Ok(())
+        }
+        "help" | "-h" | "--help" => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        other => Err(format!("Unknown command: {}\n\n{}", other, USAGE)),
+    };
+
+    match result {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            1
+        }
+    }
+}
+
+fn merge(registry: &Registry, args: &[String]) -> Result<(), String> {
+    let args = parse_args(args)?;
+    let output = args.output.ok_or("merge needs an output file (-o <output>)")?;
+    if args.inputs.len() < 2 {
+        return Err("merge needs a base file and at least one overlay".to_string());
+    }
+
+    let files = args.inputs.iter()
+        .map(|path| import(registry, path, &args.import_options))
+        .collect::<Result<Vec<_>, _>>()?;
+    let merged = crate::merge::merge_files(&files, args.merge_options);
+    for link in &merged.dropped_links {
+        eprintln!("warning: {}", link.describe());
+    }
+
+    export(registry, &merged.tasks, &output, &with_project(&args.options, &files[0]))
+}
+
+fn convert(registry: &Registry, args: &[String]) -> Result<(), String> {
+    let args = parse_args(args)?;
+    let output = args.output.ok_or("convert needs an output file (-o <output>)")?;
+    let [input] = args.inputs.as_slice() else {
+        return Err("convert takes exactly one input file".to_string());
+    };
+
+    let file = import(registry, input, &args.import_options)?;
+    export(registry, &file.tasks, &output, &with_project(&args.options, &file))
+}
+
+/// Names calendar UIDs after the base file unless a project was given.
+fn with_project(options: &ExportOptions, base: &LoadedFile) -> ExportOptions {
+    let mut options = options.clone();
+    if options.calendar.project.is_empty() {
+        options.calendar.project = base.file_name();
+    }
+    options
+}
+
+fn diff(registry: &Registry, args: &[String]) -> Result<(), String> {
+    let args = parse_args(args)?;
+    let [old_path, new_path] = args.inputs.as_slice() else {
+        return Err("diff takes exactly two input files".to_string());
+    };
+
+    let old = import(registry, old_path, &args.import_options)?;
+    let new = import(registry, new_path, &args.import_options)?;
+    let diff = crate::diff::diff_tasks(&old.tasks, &new.tasks);
+
+    let file_name = |p: &Path| p.file_name().unwrap_or_default().to_string_lossy().into_owned();
+    let is_csv = args.output.as_ref()
+        .and_then(|p| p.extension())
+        .is_some_and(|e| e.eq_ignore_ascii_case("csv"));
+    let report = if is_csv {
+        diff.to_csv()
+    } else {
+        diff.to_markdown(&file_name(old_path), &file_name(new_path))
+    };
+
+    match args.output {
+        Some(path) => {
+            std::fs::write(&path, report).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+            eprintln!("{}", diff.summary());
+            eprintln!("Wrote {}", path.display());
+        }
+        None => print!("{}", report),
+    }
+    Ok(())
+}
+
+/// Multi-line error for the terminal: what failed, then file, location and
+/// cause on their own labelled lines.
+fn report(action: &str, error: &FileError) -> String {
+    let mut out = format!("{} failed", action);
+    if let Some(path) = error.path() {
+        out.push_str(&format!("\n  file:     {}", path.display()));
+    }
+    if let Some(location) = error.location() {
+        out.push_str(&format!("\n  location: {}", location));
+    }
+    out.push_str(&format!("\n  cause:    {}", error.cause()));
+    out
+}
+
+/// Defaulted values listed per file before the rest are summarised.
+const MAX_LISTED_DIAGNOSTICS: usize = 10;
+
+fn import(registry: &Registry, path: &Path, options: &ImportOptions) -> Result<LoadedFile, String> {
+    let file = registry.import_file(path, options).map_err(|e| report("Import", &e))?;
+    eprintln!("Read {} tasks from {} ({})", file.tasks.len(), path.display(), file.format);
+    for diagnostic in file.diagnostics.iter().take(MAX_LISTED_DIAGNOSTICS) {
+        eprintln!("warning: {}: {}", file.file_name(), diagnostic.describe());
+    }
+    if file.diagnostics.len() > MAX_LISTED_DIAGNOSTICS {
+        eprintln!("warning: {}: {} more defaulted values", file.file_name(), file.diagnostics.len() - MAX_LISTED_DIAGNOSTICS);
+    }
+    Ok(file)
+}
+
+fn export(registry: &Registry, tasks: &[Task], path: &Path, options: &ExportOptions) -> Result<(), String> {
+    if let Some(exporter) = registry.exporter_for_path(path) {
+        let missing = exporter.capabilities().missing();
+        if !missing.is_empty() {
+            eprintln!("warning: {} does not keep: {}", exporter.name(), missing.join(", "));
+        }
+    }
+
+    let format = registry.export_file(tasks, path, options).map_err(|e| report("Export", &e))?;
+    eprintln!("Wrote {} ({})", path.display(), format);
+    Ok(())
+}
+
+fn print_formats(registry: &Registry) {
+    println!("Import:");
+    for importer in registry.importers() {
+        println!("  {:<32} .{:<20} {}", importer.name(), importer.extensions().join(", ."), importer.capabilities().describe());
+    }
+    println!("Export:");
+    for exporter in registry.exporters() {
+        println!("  {:<32} .{:<20} {}", exporter.name(), exporter.extension(), exporter.capabilities().describe());
+    }
+}
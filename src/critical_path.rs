@@ -0,0 +1,131 @@
+use chrono::{Datelike, NaiveDate};
+use std::collections::{HashMap, HashSet};
+use crate::task::Task;
+
+/// Indices of tasks with no total float, i.e. any slip delays the project.
+///
+/// Dates are taken as scheduled; this only runs the backward pass over the
+/// dependency links (in working days) to find each task's late finish.
+/// Returns an empty set if the links contain a cycle.
+pub fn critical_tasks(tasks: &[Task]) -> HashSet<usize> {
+    let n = tasks.len();
+    let by_uid: HashMap<i32, usize> = tasks.iter()
+        .enumerate()
+        .filter(|(_, t)| t.uid > 0)
+        .map(|(i, t)| (t.uid, i))
+        .collect();
+
+    // successors[i] = (successor index, MSPDI link type, lag in working days)
+    let mut successors: Vec<Vec<(usize, i32, i64)>> = vec![Vec::new(); n];
+    let mut indegree = vec![0usize; n];
+    for (i, task) in tasks.iter().enumerate() {
+        for pred in &task.predecessors {
+            if let Some(&p) = by_uid.get(&pred.predecessor_uid) {
+                // LinkLag is in tenths of a minute; 8h working day
+                let lag = (pred.link_lag as f64 / 4800.0).round() as i64;
+                successors[p].push((i, pred.link_type, lag));
+                indegree[i] += 1;
+            }
+        }
+    }
+
+    // Kahn's algorithm; the backward pass walks this order in reverse
+    let mut order = Vec::with_capacity(n);
+    let mut ready: Vec<usize> = (0..n).filter(|&i| indegree[i] == 0).collect();
+    while let Some(i) = ready.pop() {
+        order.push(i);
+        for &(s, _, _) in &successors[i] {
+            indegree[s] -= 1;
+            if indegree[s] == 0 {
+                ready.push(s);
+            }
+        }
+    }
+    if order.len() < n {
+        return HashSet::new();
+    }
+
+    let start: Vec<i64> = tasks.iter().map(|t| start_number(t.start.date())).collect();
+    let finish: Vec<i64> = tasks.iter()
+        .map(|t| finish_number(t.finish.max(t.start).date()).max(start_number(t.start.date())))
+        .collect();
+    let span: Vec<i64> = (0..n).map(|i| finish[i] - start[i]).collect();
+    let project_finish = finish.iter().copied().max().unwrap_or(0);
+
+    let mut late_finish = vec![project_finish; n];
+    for &i in order.iter().rev() {
+        for &(s, link_type, lag) in &successors[i] {
+            let late_start_s = late_finish[s] - span[s];
+            // MSPDI link types: 0 = FF, 1 = FS, 2 = SF, 3 = SS
+            let limit = match link_type {
+                0 => late_finish[s] - lag,
+                2 => late_finish[s] - lag + span[i],
+                3 => late_start_s - lag + span[i],
+                _ => late_start_s - 1 - lag,
+            };
+            late_finish[i] = late_finish[i].min(limit);
+        }
+    }
+
+    (0..n).filter(|&i| late_finish[i] - finish[i] <= 0).collect()
+}
+
+/// Working days since a Monday epoch. A start on a weekend counts from the
+/// following Monday, a finish on a weekend from the preceding Friday.
+fn start_number(date: NaiveDate) -> i64 {
+    let (week, day) = week_and_day(date);
+    week * 5 + day.min(5)
+}
+
+fn finish_number(date: NaiveDate) -> i64 {
+    let (week, day) = week_and_day(date);
+    week * 5 + day.min(4)
+}
+
+fn week_and_day(date: NaiveDate) -> (i64, i64) {
+    // 0001-01-01 was a Monday
+    let days = date.num_days_from_ce() as i64 - 1;
+    (days.div_euclid(7), days.rem_euclid(7))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::Predecessor;
+
+    fn task(uid: i32, start: (u32, u32), end: (u32, u32), preds: &[i32]) -> Task {
+        let mut t = Task::new(format!("T{}", uid));
+        t.uid = uid;
+        t.start = crate::task::at_start(NaiveDate::from_ymd_opt(2024, start.0, start.1).unwrap());
+        t.finish = crate::task::at_finish(NaiveDate::from_ymd_opt(2024, end.0, end.1).unwrap());
+        t.predecessors = preds.iter()
+            .map(|&p| Predecessor { predecessor_uid: p, link_type: 1, link_lag: 0, lag_format: 7 })
+            .collect();
+        t
+    }
+
+    #[test]
+    fn test_critical_chain_across_weekend() {
+        let tasks = vec![
+            // Mon-Fri, then the following Mon-Wed: no float despite the weekend
+            task(1, (1, 1), (1, 5), &[]),
+            task(2, (1, 8), (1, 10), &[1]),
+            // Runs in parallel and finishes early: has float
+            task(3, (1, 1), (1, 2), &[]),
+        ];
+
+        let critical = critical_tasks(&tasks);
+        assert!(critical.contains(&0));
+        assert!(critical.contains(&1));
+        assert!(!critical.contains(&2));
+    }
+
+    #[test]
+    fn test_cycle_has_no_critical_path() {
+        let tasks = vec![
+            task(1, (1, 1), (1, 2), &[2]),
+            task(2, (1, 3), (1, 4), &[1]),
+        ];
+        assert!(critical_tasks(&tasks).is_empty());
+    }
+}
@@ -0,0 +1,162 @@
+use chrono::{Month, NaiveDate, NaiveTime, Weekday};
+use serde::{Deserialize, Serialize};
+
+// Spreadsheet dates typed as text: "3/4/2024", "Mon 3/4/24 8:00 AM",
+// "4-Mar-2024", "March 4, 2024". Numeric dates read month or day first
+// depending on the locale; two-digit years follow Excel's window, so 00-29
+// are 20xx and 30-99 are 19xx.
+
+/// Which way round "03/04/2024" is read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum DateLocale {
+    /// Decided per sheet from values that only make sense one way, such
+    /// as 25/03/2024. Sheets without any fall back on the separator:
+    /// slashes are month first, dashes and dots day first.
+    #[default]
+    Auto,
+    MonthFirst,
+    DayFirst,
+}
+
+impl DateLocale {
+    pub const ALL: [DateLocale; 3] = [DateLocale::Auto, DateLocale::MonthFirst, DateLocale::DayFirst];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            DateLocale::Auto => "Detect from sheet",
+            DateLocale::MonthFirst => "US (month/day/year)",
+            DateLocale::DayFirst => "European (day/month/year)",
+        }
+    }
+
+    /// The locale a set of text dates should be read with. Only `Auto`
+    /// looks at the values; it stays `Auto` when they don't settle it
+    /// either way.
+    pub fn infer<'a>(self, texts: impl IntoIterator<Item = &'a str>) -> DateLocale {
+        if self != DateLocale::Auto {
+            return self;
+        }
+        let (mut month_first, mut day_first) = (false, false);
+        for text in texts {
+            if let Some([first, second, _]) = numeric_parts(text) {
+                month_first |= second > 12 && first <= 12;
+                day_first |= first > 12 && second <= 12;
+            }
+        }
+        match (month_first, day_first) {
+            (true, false) => DateLocale::MonthFirst,
+            (false, true) => DateLocale::DayFirst,
+            _ => DateLocale::Auto,
+        }
+    }
+}
+
+/// Whether `text` is a numeric date that reads as a different day month
+/// first than day first, like 03/04/2024.
+pub fn is_ambiguous(text: &str) -> bool {
+    numeric_parts(text).is_some_and(|[first, second, _]| first != second && first <= 12 && second <= 12)
+}
+
+/// A date typed as text, with its time of day if one was given.
+pub fn parse(text: &str, locale: DateLocale) -> Option<(NaiveDate, Option<NaiveTime>)> {
+    let (date, time) = split_time(text)?;
+    Some((parse_date(&date, locale)?, time))
+}
+
+/// The day and month of a numeric date such as "Mon 3/4/24", as the first
+/// two numbers. ISO dates, which always put the year first, don't count.
+fn numeric_parts(text: &str) -> Option<[u32; 3]> {
+    let (date, _) = split_time(text)?;
+    let parts: Vec<&str> = date.split(['/', '-', '.']).collect();
+    let [first, second, year] = parts.as_slice() else { return None };
+    if first.len() > 2 {
+        return None;
+    }
+    Some([first.parse().ok()?, second.parse().ok()?, year.parse().ok()?])
+}
+
+/// Drops a leading weekday and splits off a trailing time such as "8:00",
+/// "17:00:00" or "8:00 AM".
+fn split_time(text: &str) -> Option<(String, Option<NaiveTime>)> {
+    let mut words: Vec<&str> = text.split([' ', ',']).filter(|w| !w.is_empty()).collect();
+    if words.len() > 1 && words[0].chars().all(|c| c.is_ascii_alphabetic()) && words[0].parse::<Weekday>().is_ok() {
+        words.remove(0);
+    }
+    let Some(at) = words.iter().position(|w| w.contains(':')) else {
+        return Some((words.join(" "), None));
+    };
+    let time_text = words.split_off(at).concat().to_lowercase();
+    let (time_text, offset) = match (time_text.strip_suffix("am"), time_text.strip_suffix("pm")) {
+        (Some(rest), _) => (rest, Some(0)),
+        (_, Some(rest)) => (rest, Some(12)),
+        _ => (time_text.as_str(), None),
+    };
+    let mut fields = time_text.split(':').map(|f| f.parse::<u32>().ok());
+    let hour = fields.next()??;
+    let minute = fields.next()??;
+    let second = fields.next().unwrap_or(Some(0))?;
+    let hour = match offset {
+        Some(offset) if (1..=12).contains(&hour) => hour % 12 + offset,
+        Some(_) => return None,
+        None => hour,
+    };
+    Some((words.join(" "), Some(NaiveTime::from_hms_opt(hour, minute, second)?)))
+}
+
+fn parse_date(text: &str, locale: DateLocale) -> Option<NaiveDate> {
+    let parts: Vec<&str> = text.split([' ', '/', '-', '.']).filter(|p| !p.is_empty()).collect();
+    let [a, b, c] = parts.as_slice() else { return None };
+    let named = |part: &str| part.parse::<Month>().ok().map(|m| m.number_from_month());
+    let (year, month, day) = if let Some(month) = named(a) {
+        // March 4, 2024
+        (*c, month, b.parse().ok()?)
+    } else if let Some(month) = named(b) {
+        // 4-Mar-2024, or 2024-Mar-04
+        if a.len() == 4 { (*a, month, c.parse().ok()?) } else { (*c, month, a.parse().ok()?) }
+    } else if a.len() == 4 {
+        (*a, b.parse().ok()?, c.parse().ok()?)
+    } else {
+        let day_first = match locale {
+            DateLocale::MonthFirst => false,
+            DateLocale::DayFirst => true,
+            DateLocale::Auto => !text.contains('/'),
+        };
+        let (day, month) = if day_first { (a, b) } else { (b, a) };
+        (*c, month.parse().ok()?, day.parse().ok()?)
+    };
+    let year: i32 = match (year.len(), year.parse::<i32>().ok()?) {
+        (2, short) if short < 30 => 2000 + short,
+        (2, short) => 1900 + short,
+        (4, full) => full,
+        _ => return None,
+    };
+    NaiveDate::from_ymd_opt(year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_text_dates() {
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        let parsed = |text, locale| parse(text, locale).map(|(d, _)| d);
+
+        assert_eq!(parsed("03/04/2024", DateLocale::MonthFirst), Some(date(2024, 3, 4)));
+        assert_eq!(parsed("03/04/2024", DateLocale::DayFirst), Some(date(2024, 4, 3)));
+        assert_eq!(parsed("Mon 3/4/24", DateLocale::Auto), Some(date(2024, 3, 4)));
+        assert_eq!(parsed("4-Mar-2024", DateLocale::MonthFirst), Some(date(2024, 3, 4)));
+        assert_eq!(parsed("March 4, 2024", DateLocale::DayFirst), Some(date(2024, 3, 4)));
+        assert_eq!(parsed("04.03.2024", DateLocale::Auto), Some(date(2024, 3, 4)));
+        assert_eq!(parsed("1/2/95", DateLocale::MonthFirst), Some(date(1995, 1, 2)));
+        assert_eq!(parsed("13/13/2024", DateLocale::Auto), None);
+        assert_eq!(parse("Mon 3/4/24 1:30 PM", DateLocale::Auto), Some((date(2024, 3, 4), NaiveTime::from_hms_opt(13, 30, 0))));
+
+        // One unmistakable value settles all the others
+        assert_eq!(DateLocale::Auto.infer(["03/04/2024", "25/03/2024"]), DateLocale::DayFirst);
+        assert_eq!(DateLocale::Auto.infer(["03/04/2024", "3/25/2024"]), DateLocale::MonthFirst);
+        assert_eq!(DateLocale::Auto.infer(["03/04/2024"]), DateLocale::Auto);
+        assert_eq!(DateLocale::MonthFirst.infer(["25/03/2024"]), DateLocale::MonthFirst);
+        assert!(is_ambiguous("Tue 03/04/24") && !is_ambiguous("2024-03-04") && !is_ambiguous("4/4/2024"));
+    }
+}
@@ -0,0 +1,203 @@
+use std::collections::{HashMap, HashSet};
+use crate::task::{Predecessor, Task};
+
+/// MSPDI link types, by their `Type` code.
+pub const LINK_TYPES: [(i32, &str, &str); 4] = [
+    (1, "FS", "Finish-to-Start"),
+    (3, "SS", "Start-to-Start"),
+    (0, "FF", "Finish-to-Finish"),
+    (2, "SF", "Start-to-Finish"),
+];
+
+pub fn link_type_label(link_type: i32) -> &'static str {
+    LINK_TYPES.iter()
+        .find(|(code, _, _)| *code == link_type)
+        .map_or("?", |(_, short, _)| short)
+}
+
+/// Lag units from MSPDI's `LagFormat`, without the elapsed variants.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LagUnit {
+    Minutes,
+    Hours,
+    Days,
+    Weeks,
+    Months,
+    Percent,
+}
+
+pub const LAG_UNITS: [LagUnit; 6] = [
+    LagUnit::Minutes,
+    LagUnit::Hours,
+    LagUnit::Days,
+    LagUnit::Weeks,
+    LagUnit::Months,
+    LagUnit::Percent,
+];
+
+/// The `LagFormat` code for plain days, used when a file doesn't say.
+pub const LAG_FORMAT_DAYS: i32 = 7;
+
+impl LagUnit {
+    pub fn label(self) -> &'static str {
+        match self {
+            LagUnit::Minutes => "minutes",
+            LagUnit::Hours => "hours",
+            LagUnit::Days => "days",
+            LagUnit::Weeks => "weeks",
+            LagUnit::Months => "months",
+            LagUnit::Percent => "% of predecessor",
+        }
+    }
+
+    /// `LinkLag` units (tenths of a minute, or of a percent) per unit. Working
+    /// time assumes 8h days, 5-day weeks and 20-day months; elapsed time runs
+    /// around the clock.
+    fn scale(self, elapsed: bool) -> f64 {
+        let minutes = match (self, elapsed) {
+            (LagUnit::Percent, _) => 1.0,
+            (LagUnit::Minutes, _) => 1.0,
+            (LagUnit::Hours, _) => 60.0,
+            (LagUnit::Days, false) => 8.0 * 60.0,
+            (LagUnit::Days, true) => 24.0 * 60.0,
+            (LagUnit::Weeks, false) => 5.0 * 8.0 * 60.0,
+            (LagUnit::Weeks, true) => 7.0 * 24.0 * 60.0,
+            (LagUnit::Months, false) => 20.0 * 8.0 * 60.0,
+            (LagUnit::Months, true) => 30.0 * 24.0 * 60.0,
+        };
+        minutes * 10.0
+    }
+}
+
+/// A link lag as shown to the user.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Lag {
+    pub value: f64,
+    pub unit: LagUnit,
+    pub elapsed: bool,
+}
+
+impl Lag {
+    /// Reads MSPDI `LinkLag` and `LagFormat`. Estimated formats (35 and up)
+    /// are treated like their plain counterparts.
+    pub fn from_mspdi(link_lag: i32, lag_format: i32) -> Self {
+        let format = if lag_format >= 35 { lag_format - 32 } else { lag_format };
+        let (unit, elapsed) = match format {
+            3 => (LagUnit::Minutes, false),
+            4 => (LagUnit::Minutes, true),
+            5 => (LagUnit::Hours, false),
+            6 => (LagUnit::Hours, true),
+            8 => (LagUnit::Days, true),
+            9 => (LagUnit::Weeks, false),
+            10 => (LagUnit::Weeks, true),
+            11 => (LagUnit::Months, false),
+            12 => (LagUnit::Months, true),
+            19 => (LagUnit::Percent, false),
+            20 => (LagUnit::Percent, true),
+            _ => (LagUnit::Days, false),
+        };
+        Self { value: link_lag as f64 / unit.scale(elapsed), unit, elapsed }
+    }
+
+    /// `(LinkLag, LagFormat)` for MSPDI.
+    pub fn to_mspdi(self) -> (i32, i32) {
+        let format = match (self.unit, self.elapsed) {
+            (LagUnit::Minutes, false) => 3,
+            (LagUnit::Minutes, true) => 4,
+            (LagUnit::Hours, false) => 5,
+            (LagUnit::Hours, true) => 6,
+            (LagUnit::Days, false) => 7,
+            (LagUnit::Days, true) => 8,
+            (LagUnit::Weeks, false) => 9,
+            (LagUnit::Weeks, true) => 10,
+            (LagUnit::Months, false) => 11,
+            (LagUnit::Months, true) => 12,
+            (LagUnit::Percent, false) => 19,
+            (LagUnit::Percent, true) => 20,
+        };
+        ((self.value * self.unit.scale(self.elapsed)).round() as i32, format)
+    }
+}
+
+/// Checks that `task_index` can take `predecessors`: every UID exists,
+/// nothing links to itself or twice, and no dependency cycle appears.
+pub fn validate_predecessors(tasks: &[Task], task_index: usize, predecessors: &[Predecessor]) -> Result<(), String> {
+    let uid = tasks[task_index].uid;
+    let by_uid: HashMap<i32, &Task> = tasks.iter().filter(|t| t.uid > 0).map(|t| (t.uid, t)).collect();
+    let mut seen = HashSet::new();
+
+    for pred in predecessors {
+        let Some(other) = by_uid.get(&pred.predecessor_uid) else {
+            return Err(format!("No task with UID {}", pred.predecessor_uid));
+        };
+        if pred.predecessor_uid == uid {
+            return Err("A task can't depend on itself".to_string());
+        }
+        if !seen.insert(pred.predecessor_uid) {
+            return Err(format!("\"{}\" is already a predecessor", other.name));
+        }
+        if let Some(path) = cycle_path(&by_uid, pred.predecessor_uid, uid) {
+            let names: Vec<&str> = path.iter().filter_map(|u| by_uid.get(u)).map(|t| t.name.as_str()).collect();
+            return Err(format!("This would create a cycle: {} → {}", tasks[task_index].name, names.join(" → ")));
+        }
+    }
+    Ok(())
+}
+
+/// The chain of predecessor links from `from` back to `target`, if any.
+fn cycle_path(by_uid: &HashMap<i32, &Task>, from: i32, target: i32) -> Option<Vec<i32>> {
+    let mut stack = vec![vec![from]];
+    let mut visited = HashSet::new();
+    while let Some(path) = stack.pop() {
+        let current = *path.last()?;
+        if current == target {
+            return Some(path);
+        }
+        if !visited.insert(current) {
+            continue;
+        }
+        for pred in by_uid.get(&current).map(|t| t.predecessors.as_slice()).unwrap_or_default() {
+            let mut next = path.clone();
+            next.push(pred.predecessor_uid);
+            stack.push(next);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn link(uid: i32) -> Predecessor {
+        Predecessor { predecessor_uid: uid, link_type: 1, link_lag: 0, lag_format: LAG_FORMAT_DAYS }
+    }
+
+    #[test]
+    fn test_validate_predecessors_rejects_cycles() {
+        let mut tasks: Vec<Task> = (1..=3).map(|uid| {
+            let mut t = Task::new(format!("T{}", uid));
+            t.uid = uid;
+            t
+        }).collect();
+        tasks[1].predecessors = vec![link(1)];
+        tasks[2].predecessors = vec![link(2)];
+
+        assert!(validate_predecessors(&tasks, 0, &[link(4)]).is_err());
+        assert!(validate_predecessors(&tasks, 0, &[link(1)]).is_err());
+        assert_eq!(
+            validate_predecessors(&tasks, 0, &[link(3)]),
+            Err("This would create a cycle: T1 → T3 → T2 → T1".to_string())
+        );
+        assert!(validate_predecessors(&tasks, 2, &[link(2), link(1)]).is_ok());
+    }
+
+    #[test]
+    fn test_lag_round_trip() {
+        let lag = Lag::from_mspdi(4800, 7);
+        assert_eq!(lag, Lag { value: 1.0, unit: LagUnit::Days, elapsed: false });
+        assert_eq!(Lag { value: 2.0, unit: LagUnit::Days, elapsed: true }.to_mspdi(), (28800, 8));
+        assert_eq!(Lag::from_mspdi(500, 19).value, 50.0);
+        assert_eq!(Lag::from_mspdi(24000, 41).unit, LagUnit::Weeks);
+    }
+}
@@ -0,0 +1,34 @@
+use serde::{Deserialize, Serialize};
+
+/// A value an importer couldn't use as found and replaced with a default,
+/// or a record it dropped.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Diagnostic {
+    /// Where in the file: "row 5" for worksheets, "UID 7" for XML tasks.
+    pub record: String,
+    pub column: String,
+    /// As found in the file; empty when the cell was blank or missing.
+    pub raw: String,
+    /// What was used instead.
+    pub substituted: String,
+}
+
+/// What an importer substitutes when a row's task is dropped entirely.
+pub const SKIPPED: &str = "(task skipped)";
+
+impl Diagnostic {
+    pub fn new(record: impl Into<String>, column: &str, raw: &str, substituted: impl ToString) -> Self {
+        Self {
+            record: record.into(),
+            column: column.to_string(),
+            raw: raw.to_string(),
+            substituted: substituted.to_string(),
+        }
+    }
+
+    /// One line for logs and the CLI, e.g. `row 5, Start: "31/31/24" → 2024-01-01`.
+    pub fn describe(&self) -> String {
+        let raw = if self.raw.is_empty() { "blank".to_string() } else { format!("{:?}", self.raw) };
+        format!("{}, {}: {} → {}", self.record, self.column, raw, self.substituted)
+    }
+}
@@ -0,0 +1,286 @@
+use std::collections::HashMap;
+use crate::task::{default_finish_time, default_start_time, format_moment, Task};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Modified,
+}
+
+impl ChangeKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            ChangeKind::Added => "Added",
+            ChangeKind::Removed => "Removed",
+            ChangeKind::Modified => "Modified",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldChange {
+    pub field: &'static str,
+    pub before: String,
+    pub after: String,
+}
+
+/// One task that differs between the two schedules.
+#[derive(Debug, Clone)]
+pub struct TaskDiff {
+    pub kind: ChangeKind,
+    /// The matching key shown to the user, e.g. "WBS 1.2" or "UID 7".
+    pub key: String,
+    pub name: String,
+    /// Only filled for modified tasks.
+    pub changes: Vec<FieldChange>,
+}
+
+pub struct ProjectDiff {
+    pub entries: Vec<TaskDiff>,
+    pub unchanged: usize,
+    /// Keys shared by several tasks in one file, e.g. "WBS 1.2 (new)".
+    /// Such tasks are paired in file order.
+    pub duplicates: Vec<String>,
+}
+
+impl ProjectDiff {
+    pub fn count(&self, kind: ChangeKind) -> usize {
+        self.entries.iter().filter(|e| e.kind == kind).count()
+    }
+
+    pub fn summary(&self) -> String {
+        let mut summary = format!(
+            "{} added, {} removed, {} modified, {} unchanged",
+            self.count(ChangeKind::Added),
+            self.count(ChangeKind::Removed),
+            self.count(ChangeKind::Modified),
+            self.unchanged
+        );
+        if !self.duplicates.is_empty() {
+            summary.push_str(&format!("; duplicate keys: {}", self.duplicates.join(", ")));
+        }
+        summary
+    }
+
+    /// Markdown report, readable as plain text too.
+    pub fn to_markdown(&self, old_name: &str, new_name: &str) -> String {
+        let mut out = format!("# Schedule comparison\n\n`{}` → `{}`\n\n{}\n", old_name, new_name, self.summary());
+        for kind in [ChangeKind::Added, ChangeKind::Removed, ChangeKind::Modified] {
+            let entries: Vec<&TaskDiff> = self.entries.iter().filter(|e| e.kind == kind).collect();
+            if entries.is_empty() {
+                continue;
+            }
+            out.push_str(&format!("\n## {} ({})\n\n", kind.label(), entries.len()));
+            for entry in entries {
+                out.push_str(&format!("- **{}** {}\n", entry.key, entry.name));
+                for change in &entry.changes {
+                    out.push_str(&format!("  - {}: {} → {}\n", change.field, or_dash(&change.before), or_dash(&change.after)));
+                }
+            }
+        }
+        out
+    }
+
+    /// One row per changed field; added and removed tasks get a single row.
+    pub fn to_csv(&self) -> String {
+        let mut out = String::from("Change,Key,Task,Field,Before,After\n");
+        for entry in &self.entries {
+            let row = |field: &str, before: &str, after: &str| {
+                [entry.kind.label(), &entry.key, &entry.name, field, before, after]
+                    .iter()
+                    .map(|v| csv_field(v))
+                    .collect::<Vec<_>>()
+                    .join(",")
+            };
+            if entry.changes.is_empty() {
+                out.push_str(&row("", "", ""));
+                out.push('\n');
+            }
+            for change in &entry.changes {
+                out.push_str(&row(change.field, &change.before, &change.after));
+                out.push('\n');
+            }
+        }
+        out
+    }
+}
+
+fn or_dash(s: &str) -> &str {
+    if s.is_empty() { "—" } else { s }
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Matching key: WBS is the merge key, so prefer it; fall back to UID.
+fn match_key(task: &Task) -> Option<String> {
+    if !task.wbs.is_empty() {
+        Some(format!("WBS {}", task.wbs))
+    } else if task.uid > 0 {
+        Some(format!("UID {}", task.uid))
+    } else {
+        None
+    }
+}
+
+/// Keys used by more than one task, tagged with which file they're in.
+fn duplicate_keys(tasks: &[Task], file: &str) -> Vec<String> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for key in tasks.iter().filter_map(match_key) {
+        *counts.entry(key).or_default() += 1;
+    }
+    let mut keys: Vec<String> = counts.into_iter()
+        .filter(|(_, count)| *count > 1)
+        .map(|(key, _)| format!("{} ({})", key, file))
+        .collect();
+    keys.sort();
+    keys
+}
+
+/// Compares two versions of a schedule. Tasks match on WBS code, or on UID
+/// when either side has no WBS, as after a spreadsheet that dropped it.
+/// Tasks with neither can't be matched and are reported as removed/added.
+pub fn diff_tasks(old: &[Task], new: &[Task]) -> ProjectDiff {
+    let mut new_by_wbs: HashMap<&str, Vec<usize>> = HashMap::new();
+    let mut new_by_uid: HashMap<i32, Vec<usize>> = HashMap::new();
+    for (i, task) in new.iter().enumerate() {
+        if !task.wbs.is_empty() {
+            new_by_wbs.entry(&task.wbs).or_default().push(i);
+        }
+        if task.uid > 0 {
+            new_by_uid.entry(task.uid).or_default().push(i);
+        }
+    }
+    let mut matched = vec![false; new.len()];
+    let first_free = |candidates: Option<&Vec<usize>>, matched: &[bool], usable: &dyn Fn(usize) -> bool| {
+        candidates.into_iter().flatten().copied().find(|&i| !matched[i] && usable(i))
+    };
+
+    let mut entries = Vec::new();
+    let mut unchanged = 0;
+
+    for task in old {
+        let key = match_key(task);
+        let by_wbs = first_free(new_by_wbs.get(task.wbs.as_str()), &matched, &|_| true);
+        let found = by_wbs.or_else(|| {
+            let usable = |i: usize| task.wbs.is_empty() || new[i].wbs.is_empty();
+            first_free(new_by_uid.get(&task.uid).filter(|_| task.uid > 0), &matched, &usable)
+        });
+        if let Some(i) = found {
+            matched[i] = true;
+        }
+        match found.map(|i| &new[i]) {
+            Some(other) => {
+                let changes = field_changes(task, other);
+                if changes.is_empty() {
+                    unchanged += 1;
+                } else {
+                    entries.push(TaskDiff { kind: ChangeKind::Modified, key: key.unwrap_or_default(), name: other.name.clone(), changes });
+                }
+            }
+            None => entries.push(TaskDiff {
+                kind: ChangeKind::Removed,
+                key: key.unwrap_or_else(|| "—".to_string()),
+                name: task.name.clone(),
+                changes: Vec::new(),
+            }),
+        }
+    }
+
+    for (task, _) in new.iter().zip(&matched).filter(|(_, matched)| !**matched) {
+        entries.push(TaskDiff {
+            kind: ChangeKind::Added,
+            key: match_key(task).unwrap_or_else(|| "—".to_string()),
+            name: task.name.clone(),
+            changes: Vec::new(),
+        });
+    }
+
+    let mut duplicates = duplicate_keys(old, "old");
+    duplicates.extend(duplicate_keys(new, "new"));
+    ProjectDiff { entries, unchanged, duplicates }
+}
+
+fn field_changes(old: &Task, new: &Task) -> Vec<FieldChange> {
+    // Times only show when they aren't the working day's start or finish
+    let start = |m: chrono::NaiveDateTime| format_moment(m, default_start_time());
+    let finish = |m: chrono::NaiveDateTime| format_moment(m, default_finish_time());
+    let predecessors = |t: &Task| t.predecessors.iter()
+        .map(|p| p.predecessor_uid.to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let fields = [
+        ("Name", old.name.clone(), new.name.clone()),
+        ("Description", old.description.clone(), new.description.clone()),
+        ("Start", start(old.start), start(new.start)),
+        ("Finish", finish(old.finish), finish(new.finish)),
+        ("Duration", old.duration.to_string(), new.duration.to_string()),
+        ("% Complete", old.percent_complete.to_string(), new.percent_complete.to_string()),
+        ("Status", format!("{:?}", old.status), format!("{:?}", new.status)),
+        ("Priority", old.priority.to_string(), new.priority.to_string()),
+        ("Assignee", old.assignee.clone(), new.assignee.clone()),
+        ("Milestone", old.milestone.to_string(), new.milestone.to_string()),
+        ("Actual Start", old.actual_start.map(start).unwrap_or_default(), new.actual_start.map(start).unwrap_or_default()),
+        ("Actual Finish", old.actual_finish.map(finish).unwrap_or_default(), new.actual_finish.map(finish).unwrap_or_default()),
+        ("Remaining", old.remaining_duration.to_string(), new.remaining_duration.to_string()),
+        ("Predecessors", predecessors(old), predecessors(new)),
+    ];
+
+    fields.into_iter()
+        .filter(|(_, before, after)| before != after)
+        .map(|(field, before, after)| FieldChange { field, before, after })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(wbs: &str, name: &str, percent: u32) -> Task {
+        let mut t = Task::new(name.to_string());
+        t.wbs = wbs.to_string();
+        t.percent_complete = percent;
+        t
+    }
+
+    #[test]
+    fn test_diff_tasks() {
+        let old = vec![task("1", "Design", 0), task("2", "Build", 0), task("3", "Test", 0)];
+        let new = vec![task("1", "Design", 0), task("2", "Build", 40), task("4", "Deploy", 0)];
+
+        let diff = diff_tasks(&old, &new);
+        assert_eq!(diff.unchanged, 1);
+        assert_eq!(diff.summary(), "1 added, 1 removed, 1 modified, 1 unchanged");
+
+        let modified = diff.entries.iter().find(|e| e.kind == ChangeKind::Modified).unwrap();
+        assert_eq!(modified.key, "WBS 2");
+        assert_eq!(modified.changes, vec![FieldChange { field: "% Complete", before: "0".to_string(), after: "40".to_string() }]);
+
+        let csv = diff.to_csv();
+        assert!(csv.contains("Modified,WBS 2,Build,% Complete,0,40\n"));
+        assert!(csv.contains("Removed,WBS 3,Test,,,\n"));
+    }
+
+    #[test]
+    fn test_diff_falls_back_to_uid() {
+        let mut old = vec![task("1", "Design", 0), task("2", "Build", 0)];
+        let mut new = vec![task("", "Design", 0), task("", "Build", 40), task("", "Build", 0)];
+        for (i, t) in old.iter_mut().enumerate() {
+            t.uid = i as i32 + 1;
+        }
+        for (i, t) in new.iter_mut().enumerate() {
+            t.uid = (i as i32).min(1) + 1;
+        }
+
+        let diff = diff_tasks(&old, &new);
+        assert_eq!(diff.summary(), "1 added, 0 removed, 1 modified, 1 unchanged; duplicate keys: UID 2 (new)");
+        assert_eq!(diff.entries[0].key, "WBS 2");
+    }
+}
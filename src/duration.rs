@@ -0,0 +1,280 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+// Durations are kept in minutes with the unit they were given in, so "4h"
+// stays four hours and "2.5d" comes back out as 2.5 days. Working time uses
+// MS Project's defaults of 8h days, 5-day weeks and 20-day months; elapsed
+// ("e") durations run around the clock.
+
+pub const MINUTES_PER_DAY: u32 = 8 * 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum DurationUnit {
+    Minutes,
+    Hours,
+    #[default]
+    Days,
+    Weeks,
+    Months,
+}
+
+impl DurationUnit {
+    fn minutes(self, elapsed: bool) -> u32 {
+        match (self, elapsed) {
+            (DurationUnit::Minutes, _) => 1,
+            (DurationUnit::Hours, _) => 60,
+            (DurationUnit::Days, false) => MINUTES_PER_DAY,
+            (DurationUnit::Days, true) => 24 * 60,
+            (DurationUnit::Weeks, false) => 5 * MINUTES_PER_DAY,
+            (DurationUnit::Weeks, true) => 7 * 24 * 60,
+            (DurationUnit::Months, false) => 20 * MINUTES_PER_DAY,
+            (DurationUnit::Months, true) => 30 * 24 * 60,
+        }
+    }
+
+    /// MS Project's abbreviation, singular or plural.
+    fn label(self, plural: bool) -> &'static str {
+        match (self, plural) {
+            (DurationUnit::Minutes, false) => "min",
+            (DurationUnit::Minutes, true) => "mins",
+            (DurationUnit::Hours, false) => "hr",
+            (DurationUnit::Hours, true) => "hrs",
+            (DurationUnit::Days, false) => "day",
+            (DurationUnit::Days, true) => "days",
+            (DurationUnit::Weeks, false) => "wk",
+            (DurationUnit::Weeks, true) => "wks",
+            (DurationUnit::Months, false) => "mon",
+            (DurationUnit::Months, true) => "mons",
+        }
+    }
+
+    fn parse(text: &str) -> Option<Self> {
+        match text {
+            "" | "d" | "dy" | "day" | "days" => Some(DurationUnit::Days),
+            "m" | "min" | "mins" | "minute" | "minutes" => Some(DurationUnit::Minutes),
+            "h" | "hr" | "hrs" | "hour" | "hours" => Some(DurationUnit::Hours),
+            "w" | "wk" | "wks" | "week" | "weeks" => Some(DurationUnit::Weeks),
+            "mo" | "mon" | "mons" | "month" | "months" => Some(DurationUnit::Months),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(from = "Stored")]
+pub struct Duration {
+    pub minutes: u32,
+    /// The unit it is shown in.
+    pub unit: DurationUnit,
+    /// Calendar time rather than working time.
+    pub elapsed: bool,
+    /// Marked with "?" as a guess.
+    pub estimated: bool,
+}
+
+/// Sessions from before durations had units stored whole days.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Stored {
+    Days(u32),
+    Full {
+        minutes: u32,
+        unit: DurationUnit,
+        #[serde(default)]
+        elapsed: bool,
+        #[serde(default)]
+        estimated: bool,
+    },
+}
+
+impl From<Stored> for Duration {
+    fn from(stored: Stored) -> Self {
+        match stored {
+            Stored::Days(days) => Duration::from_days(days),
+            Stored::Full { minutes, unit, elapsed, estimated } => Duration { minutes, unit, elapsed, estimated },
+        }
+    }
+}
+
+impl Duration {
+    /// Working days.
+    pub fn from_days(days: u32) -> Self {
+        Self::from_minutes(days * MINUTES_PER_DAY, DurationUnit::Days)
+    }
+
+    pub fn from_minutes(minutes: u32, unit: DurationUnit) -> Self {
+        Self { minutes, unit, elapsed: false, estimated: false }
+    }
+
+    /// The amount in its own unit, e.g. 2.5 for "2.5 days".
+    pub fn value(&self) -> f64 {
+        self.minutes as f64 / self.unit.minutes(self.elapsed) as f64
+    }
+
+    /// Length in days of the duration's kind (working or elapsed).
+    pub fn days(&self) -> f64 {
+        self.minutes as f64 / DurationUnit::Days.minutes(self.elapsed) as f64
+    }
+
+    /// Same kind and unit, different length.
+    pub fn with_minutes(self, minutes: u32) -> Self {
+        Self { minutes, ..self }
+    }
+
+    /// Text such as "2.5d", "3 wks", "4h?", "1 eday", or an ISO 8601
+    /// duration. A bare number is days.
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text.trim().to_lowercase();
+        let (text, estimated) = match text.strip_suffix('?') {
+            Some(rest) => (rest.trim_end(), true),
+            None => (text.as_str(), false),
+        };
+        if text.starts_with('p') {
+            let mut duration = Self::from_iso(text, false)?;
+            duration.estimated = estimated;
+            return Some(duration);
+        }
+        let split = text.find(|c: char| !(c.is_ascii_digit() || c == '.' || c == ',')).unwrap_or(text.len());
+        let value: f64 = text[..split].replace(',', ".").parse().ok()?;
+        let suffix = text[split..].trim();
+        let (unit, elapsed) = match DurationUnit::parse(suffix) {
+            Some(unit) => (unit, false),
+            None => (DurationUnit::parse(suffix.strip_prefix('e')?.trim_start())?, true),
+        };
+        if !value.is_finite() || value < 0.0 {
+            return None;
+        }
+        let minutes = (value * unit.minutes(elapsed) as f64).round() as u32;
+        Some(Self { minutes, unit, elapsed, estimated })
+    }
+
+    /// ISO 8601 like "PT36H30M0S" or "P1DT4H". Date parts count as working
+    /// or elapsed time depending on `elapsed`.
+    fn from_iso(text: &str, elapsed: bool) -> Option<Self> {
+        let text = text.strip_prefix(['P', 'p'])?;
+        let (date, time) = match text.split_once(['T', 't']) {
+            Some((date, time)) => (date, time),
+            None => (text, ""),
+        };
+        let mut minutes = 0.0;
+        for (part, is_time) in [(date, false), (time, true)] {
+            let mut number = String::new();
+            for c in part.chars() {
+                if c.is_ascii_digit() || c == '.' || c == ',' {
+                    number.push(if c == ',' { '.' } else { c });
+                    continue;
+                }
+                let value: f64 = number.parse().ok()?;
+                number.clear();
+                let scale = match (c.to_ascii_uppercase(), is_time) {
+                    ('H', true) => 60.0,
+                    ('M', true) => 1.0,
+                    ('S', true) => 1.0 / 60.0,
+                    ('D', false) => DurationUnit::Days.minutes(elapsed) as f64,
+                    ('W', false) => DurationUnit::Weeks.minutes(elapsed) as f64,
+                    ('M', false) => DurationUnit::Months.minutes(elapsed) as f64,
+                    ('Y', false) => 12.0 * DurationUnit::Months.minutes(elapsed) as f64,
+                    _ => return None,
+                };
+                minutes += value * scale;
+            }
+            if !number.is_empty() {
+                return None;
+            }
+        }
+        Some(Self { minutes: minutes.round() as u32, unit: DurationUnit::Days, elapsed, estimated: false })
+    }
+
+    /// Reads an MSPDI duration and its `DurationFormat` code. Without a
+    /// code the unit is days.
+    pub fn from_mspdi(text: &str, format: i32) -> Option<Self> {
+        let estimated = format >= 35;
+        let format = if estimated { format - 32 } else { format };
+        let (unit, elapsed) = match format {
+            3 => (DurationUnit::Minutes, false),
+            4 => (DurationUnit::Minutes, true),
+            5 => (DurationUnit::Hours, false),
+            6 => (DurationUnit::Hours, true),
+            8 => (DurationUnit::Days, true),
+            9 => (DurationUnit::Weeks, false),
+            10 => (DurationUnit::Weeks, true),
+            11 => (DurationUnit::Months, false),
+            12 => (DurationUnit::Months, true),
+            _ => (DurationUnit::Days, false),
+        };
+        let parsed = Self::from_iso(text.trim(), elapsed)?;
+        Some(Self { unit, estimated, ..parsed })
+    }
+
+    /// `(Duration, DurationFormat)` for MSPDI.
+    pub fn to_mspdi(self) -> (String, i32) {
+        let text = format!("PT{}H{}M0S", self.minutes / 60, self.minutes % 60);
+        let format = match (self.unit, self.elapsed) {
+            (DurationUnit::Minutes, false) => 3,
+            (DurationUnit::Minutes, true) => 4,
+            (DurationUnit::Hours, false) => 5,
+            (DurationUnit::Hours, true) => 6,
+            (DurationUnit::Days, false) => 7,
+            (DurationUnit::Days, true) => 8,
+            (DurationUnit::Weeks, false) => 9,
+            (DurationUnit::Weeks, true) => 10,
+            (DurationUnit::Months, false) => 11,
+            (DurationUnit::Months, true) => 12,
+        };
+        (text, if self.estimated { format + 32 } else { format })
+    }
+}
+
+/// MS Project's own style: "2.5 days", "4 hrs?", "1 ewk". Two decimals
+/// unless more are needed to read back the same minutes, as for 270 mins
+/// shown as "0.563 days".
+impl fmt::Display for Duration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let unit_minutes = self.unit.minutes(self.elapsed) as f64;
+        let value = (2..=6)
+            .map(|places| {
+                let scale = 10f64.powi(places);
+                (self.value() * scale).round() / scale
+            })
+            .find(|value| (value * unit_minutes).round() as u32 == self.minutes)
+            .unwrap_or_else(|| self.value());
+        let elapsed = if self.elapsed { "e" } else { "" };
+        let estimated = if self.estimated { "?" } else { "" };
+        write!(f, "{} {}{}{}", value, elapsed, self.unit.label(value != 1.0), estimated)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_format_durations() {
+        assert_eq!(Duration::parse("4h"), Some(Duration::from_minutes(240, DurationUnit::Hours)));
+        assert_eq!(Duration::parse("2.5d").map(|d| d.minutes), Some(1200));
+        assert_eq!(Duration::parse("3w").map(|d| d.days()), Some(15.0));
+        assert_eq!(Duration::parse("5"), Some(Duration::from_days(5)));
+        assert_eq!(Duration::parse("1 month").map(|d| d.unit), Some(DurationUnit::Months));
+        assert_eq!(Duration::parse("soon"), None);
+
+        let elapsed = Duration::parse("2 edays?").unwrap();
+        assert_eq!((elapsed.minutes, elapsed.elapsed, elapsed.estimated), (2880, true, true));
+        assert_eq!(elapsed.to_string(), "2 edays?");
+        assert_eq!(Duration::parse(&elapsed.to_string()), Some(elapsed));
+        assert_eq!(Duration::from_minutes(240, DurationUnit::Hours).to_string(), "4 hrs");
+        assert_eq!(Duration::parse("P1DT4H").map(|d| d.minutes), Some(720));
+
+        // MSPDI round trip keeps minutes, unit and flags
+        let (text, format) = elapsed.to_mspdi();
+        assert_eq!((text.as_str(), format), ("PT48H0M0S", 40));
+        assert_eq!(Duration::from_mspdi(&text, format), Some(elapsed));
+        let half_day = Duration::from_mspdi("PT4H30M0S", 7).unwrap();
+        assert_eq!((half_day.minutes, half_day.unit), (270, DurationUnit::Days));
+        assert_eq!(half_day.to_string(), "0.563 days");
+        assert_eq!(Duration::parse(&half_day.to_string()), Some(half_day));
+        assert_eq!(Duration::from_minutes(600, DurationUnit::Days).to_string(), "1.25 days");
+
+        let old: Duration = serde_json::from_str("3").unwrap();
+        assert_eq!(old, Duration::from_days(3));
+    }
+}
@@ -0,0 +1,182 @@
+use chrono::NaiveDate;
+use std::ops::Add;
+use crate::outline;
+use crate::task::{working_days_between, Task};
+
+// Earned value compares three amounts at the status date: the work that
+// should be done (BCWS, planned value), the work that is done (BCWP,
+// earned value) and what it took (ACWP, actual cost). Plans with costs are
+// measured in money. Plans without any are measured in working days, with
+// a task's actual duration standing in for its actual cost, or the days
+// since it started when no actual duration was reported.
+
+/// What the amounts are measured in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Basis {
+    Cost,
+    Days,
+}
+
+impl Basis {
+    /// Cost if any task has a budget, otherwise days.
+    pub fn for_tasks(tasks: &[Task]) -> Self {
+        let has_costs = tasks.iter().any(|t| t.cost > 0.0 || t.baseline.is_some_and(|b| b.cost > 0.0));
+        if has_costs { Basis::Cost } else { Basis::Days }
+    }
+
+    pub fn unit(self) -> &'static str {
+        match self {
+            Basis::Cost => "cost",
+            Basis::Days => "days",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Metrics {
+    /// Budgeted cost of work scheduled (planned value).
+    pub bcws: f64,
+    /// Budgeted cost of work performed (earned value).
+    pub bcwp: f64,
+    /// Actual cost of work performed.
+    pub acwp: f64,
+}
+
+impl Metrics {
+    /// Schedule variance: earned minus planned.
+    pub fn sv(&self) -> f64 {
+        self.bcwp - self.bcws
+    }
+
+    /// Cost variance: earned minus actual.
+    pub fn cv(&self) -> f64 {
+        self.bcwp - self.acwp
+    }
+
+    /// Schedule performance index; `None` before anything was planned.
+    pub fn spi(&self) -> Option<f64> {
+        (self.bcws > 0.0).then(|| self.bcwp / self.bcws)
+    }
+
+    /// Cost performance index; `None` before anything was spent.
+    pub fn cpi(&self) -> Option<f64> {
+        (self.acwp > 0.0).then(|| self.bcwp / self.acwp)
+    }
+}
+
+impl Add for Metrics {
+    type Output = Metrics;
+
+    fn add(self, other: Metrics) -> Metrics {
+        Metrics {
+            bcws: self.bcws + other.bcws,
+            bcwp: self.bcwp + other.bcwp,
+            acwp: self.acwp + other.acwp,
+        }
+    }
+}
+
+/// Share of the span from `start` to `end` elapsed by `date`, in working days.
+fn elapsed_fraction(start: NaiveDate, end: NaiveDate, date: NaiveDate) -> f64 {
+    if date < start {
+        return 0.0;
+    }
+    if date >= end {
+        return 1.0;
+    }
+    let total = working_days_between(start, end).max(1);
+    working_days_between(start, date) as f64 / total as f64
+}
+
+/// Metrics for a single task. The baseline is the plan when there is one,
+/// the current dates otherwise.
+pub fn task_metrics(task: &Task, status_date: NaiveDate, basis: Basis) -> Metrics {
+    let (start, end, duration, baseline_cost) = match task.baseline {
+        Some(b) => (b.start.date(), b.finish.date(), b.duration, b.cost),
+        None => (task.start.date(), task.finish.date(), task.duration, 0.0),
+    };
+    let budget = match basis {
+        Basis::Cost if baseline_cost > 0.0 => baseline_cost,
+        Basis::Cost => task.cost,
+        Basis::Days => duration.days(),
+    };
+    let acwp = match basis {
+        Basis::Cost => task.actual_cost,
+        Basis::Days if task.actual_duration.minutes > 0 => task.actual_duration.days(),
+        Basis::Days if task.percent_complete == 0 || status_date < task.start.date() => 0.0,
+        Basis::Days => working_days_between(task.start.date(), status_date.min(task.finish.date())) as f64,
+    };
+    Metrics {
+        bcws: budget * elapsed_fraction(start, end, status_date),
+        bcwp: budget * task.percent_complete.min(100) as f64 / 100.0,
+        acwp,
+    }
+}
+
+/// Metrics for the task at `index`, summed over its leaf tasks if it's a
+/// summary.
+pub fn rollup(tasks: &[Task], index: usize, status_date: NaiveDate, basis: Basis) -> Metrics {
+    if !outline::is_summary(tasks, index) {
+        return task_metrics(&tasks[index], status_date, basis);
+    }
+    (index + 1..outline::subtree_end(tasks, index))
+        .filter(|&i| !outline::is_summary(tasks, i))
+        .map(|i| task_metrics(&tasks[i], status_date, basis))
+        .fold(Metrics::default(), Add::add)
+}
+
+/// Metrics for the whole plan, from its leaf tasks.
+pub fn totals(tasks: &[Task], status_date: NaiveDate, basis: Basis) -> Metrics {
+    (0..tasks.len())
+        .filter(|&i| !outline::is_summary(tasks, i))
+        .map(|i| task_metrics(&tasks[i], status_date, basis))
+        .fold(Metrics::default(), Add::add)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::duration::Duration;
+    use crate::task::{at_finish, at_start, Baseline};
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 1, day).unwrap()
+    }
+
+    #[test]
+    fn test_earned_value_rollup() {
+        // Mon 1st to Fri 5th, baselined at 1000, half done by Wednesday
+        let mut design = Task::new("Design".to_string());
+        design.wbs = "1.1".to_string();
+        design.start = at_start(date(1));
+        design.finish = at_finish(date(5));
+        design.percent_complete = 50;
+        design.actual_cost = 800.0;
+        design.baseline = Some(Baseline { start: at_start(date(1)), finish: at_finish(date(5)), duration: Duration::from_days(5), cost: 1000.0 });
+
+        let mut build = Task::new("Build".to_string());
+        build.wbs = "1.2".to_string();
+        build.start = at_start(date(8));
+        build.finish = at_finish(date(12));
+        build.cost = 500.0;
+
+        let mut phase = Task::new("Phase".to_string());
+        phase.wbs = "1".to_string();
+        let tasks = vec![phase, design, build];
+
+        let basis = Basis::for_tasks(&tasks);
+        assert_eq!(basis, Basis::Cost);
+        let metrics = rollup(&tasks, 0, date(3), basis);
+        assert_eq!(metrics, Metrics { bcws: 600.0, bcwp: 500.0, acwp: 800.0 });
+        assert_eq!(metrics.sv(), -100.0);
+        assert_eq!(metrics.cpi(), Some(0.625));
+        assert_eq!(totals(&tasks, date(3), basis), metrics);
+
+        let days = task_metrics(&tasks[1], date(3), Basis::Days);
+        assert_eq!(days, Metrics { bcws: 3.0, bcwp: 2.5, acwp: 3.0 });
+        // Paused after a day and a half: only the reported time counts
+        let mut paused = tasks[1].clone();
+        paused.actual_duration = Duration::from_minutes(720, crate::duration::DurationUnit::Days);
+        assert_eq!(task_metrics(&paused, date(3), Basis::Days).acwp, 1.5);
+    }
+}
@@ -0,0 +1,133 @@
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Why reading or writing a project file failed, with as much context
+/// (file, worksheet row, XML element) as the format can give.
+#[derive(Debug)]
+pub enum FileError {
+    /// The file couldn't be opened, read or written.
+    Io { path: PathBuf, source: io::Error },
+    /// No importer recognised the content.
+    UnknownFormat { path: PathBuf, reason: String },
+    /// No exporter for the output file's extension.
+    NoExporter { path: PathBuf, supported: Vec<&'static str> },
+    /// The workbook container itself is broken or couldn't be written.
+    Workbook(String),
+    /// A single worksheet cell. `row` is 1-based, as Excel shows it.
+    Cell { row: u32, column: &'static str, message: String },
+    /// XML that isn't well formed or doesn't match the schema.
+    Xml { element: Option<String>, message: String },
+    /// Errors from formats that don't report anything more specific.
+    Other(String),
+    /// Any of the above, in a known file.
+    InFile { path: PathBuf, source: Box<FileError> },
+}
+
+impl FileError {
+    /// Attaches `path` unless the error already names a file.
+    pub fn in_file(self, path: &Path) -> Self {
+        if self.path().is_some() {
+            self
+        } else {
+            FileError::InFile { path: path.to_path_buf(), source: Box::new(self) }
+        }
+    }
+
+    /// Keeps our own errors typed; anything else becomes `Other`.
+    pub fn from_boxed(error: Box<dyn std::error::Error>) -> Self {
+        match error.downcast::<FileError>() {
+            Ok(error) => *error,
+            Err(error) => FileError::Other(error.to_string()),
+        }
+    }
+
+    pub fn path(&self) -> Option<&Path> {
+        match self {
+            FileError::Io { path, .. }
+            | FileError::UnknownFormat { path, .. }
+            | FileError::NoExporter { path, .. }
+            | FileError::InFile { path, .. } => Some(path),
+            _ => None,
+        }
+    }
+
+    /// Where in the file things went wrong, e.g. "row 4, column Start".
+    pub fn location(&self) -> Option<String> {
+        match self {
+            FileError::Cell { row, column, .. } => Some(format!("row {}, column {}", row, column)),
+            FileError::Xml { element: Some(element), .. } => Some(element.clone()),
+            FileError::InFile { source, .. } => source.location(),
+            _ => None,
+        }
+    }
+
+    /// The underlying problem without file or location context.
+    pub fn cause(&self) -> String {
+        match self {
+            FileError::Io { source, .. } => source.to_string(),
+            FileError::UnknownFormat { reason, .. } => reason.clone(),
+            FileError::NoExporter { supported, .. } => format!("No exporter for this file type. Supported: {}", supported.join(", ")),
+            FileError::Workbook(message)
+            | FileError::Cell { message, .. }
+            | FileError::Xml { message, .. }
+            | FileError::Other(message) => message.clone(),
+            FileError::InFile { source, .. } => source.cause(),
+        }
+    }
+}
+
+impl fmt::Display for FileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(path) = self.path() {
+            write!(f, "{}: ", path.display())?;
+        }
+        if let Some(location) = self.location() {
+            write!(f, "{}: ", location)?;
+        }
+        write!(f, "{}", self.cause())
+    }
+}
+
+impl std::error::Error for FileError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            FileError::Io { source, .. } => Some(source),
+            FileError::InFile { source, .. } => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl From<calamine::XlsxError> for FileError {
+    fn from(error: calamine::XlsxError) -> Self {
+        FileError::Workbook(error.to_string())
+    }
+}
+
+impl From<rust_xlsxwriter::XlsxError> for FileError {
+    fn from(error: rust_xlsxwriter::XlsxError) -> Self {
+        FileError::Workbook(error.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_error_context() {
+        let error = FileError::Cell { row: 4, column: "Start Date", message: "String too long".to_string() }
+            .in_file(Path::new("plan.xlsx"));
+        assert_eq!(error.location().as_deref(), Some("row 4, column Start Date"));
+        assert_eq!(error.to_string(), "plan.xlsx: row 4, column Start Date: String too long");
+
+        // A path that's already known isn't wrapped twice
+        let io = FileError::Io { path: PathBuf::from("a.xml"), source: io::Error::from(io::ErrorKind::NotFound) };
+        assert!(matches!(io.in_file(Path::new("b.xml")), FileError::Io { .. }));
+
+        let boxed: Box<dyn std::error::Error> = Box::new(FileError::Workbook("corrupt".to_string()));
+        assert!(matches!(FileError::from_boxed(boxed), FileError::Workbook(_)));
+        assert!(matches!(FileError::from_boxed("plain".into()), FileError::Other(_)));
+    }
+}
@@ -1,7 +1,69 @@
-use rust_xlsxwriter::{Workbook, Format, FormatAlign};
+use chrono::{NaiveDate, NaiveDateTime};
+use rust_xlsxwriter::{Workbook, Format, FormatAlign, Worksheet, XlsxError};
+use std::path::Path;
+use crate::earned_value::{self, Basis, Metrics};
+use crate::error::FileError;
+use crate::registry::{Capabilities, ExportOptions, Exporter};
 use crate::task::Task;
 
-pub fn export_to_xlsx(tasks: &[Task], path: &str) -> Result<(), Box<dyn std::error::Error>> {
+pub struct XlsxExporter;
+
+impl Exporter for XlsxExporter {
+    fn name(&self) -> &'static str {
+        "Excel workbook"
+    }
+
+    fn extension(&self) -> &'static str {
+        "xlsx"
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        // Assignee column only; no WBS or predecessor columns
+        Capabilities::new(false, false, true)
+    }
+
+    fn export(&self, tasks: &[Task], path: &Path, options: &ExportOptions) -> Result<(), Box<dyn std::error::Error>> {
+        let status_date = options.status_date.unwrap_or_else(|| chrono::Local::now().date_naive());
+        Ok(export_to_xlsx(tasks, path, status_date)?)
+    }
+}
+
+/// Column headers, in sheet order.
+const HEADERS: [&str; 15] = [
+    "Task Name",
+    "Description",
+    "Start Date",
+    "End Date",
+    "Status",
+    crate::priority::COLUMN,
+    "Assignee",
+    "Duration",
+    "% Complete",
+    "Cost",
+    "Actual Cost",
+    "Actual Start",
+    "Actual Finish",
+    "Actual Duration",
+    "Remaining Duration",
+];
+
+/// Earned value sheet headers, in sheet order.
+const EV_HEADERS: [&str; 11] = [
+    "WBS",
+    "Task Name",
+    "BAC",
+    "BCWS (PV)",
+    "BCWP (EV)",
+    "ACWP (AC)",
+    "SV",
+    "CV",
+    "SPI",
+    "CPI",
+    "Level",
+];
+
+/// Writes the task sheet and an "Earned Value" sheet measured at `status_date`.
+pub fn export_to_xlsx(tasks: &[Task], path: &Path, status_date: NaiveDate) -> Result<(), FileError> {
     let mut workbook = Workbook::new();
     let worksheet = workbook.add_worksheet();
     
@@ -11,31 +73,39 @@ pub fn export_to_xlsx(tasks: &[Task], path: &str) -> Result<(), Box<dyn std::err
         .set_align(FormatAlign::Center);
     
     // Write headers
-    worksheet.write_string_with_format(0, 0, "Task Name", &header_format)?;
-    worksheet.write_string_with_format(0, 1, "Description", &header_format)?;
-    worksheet.write_string_with_format(0, 2, "Start Date", &header_format)?;
-    worksheet.write_string_with_format(0, 3, "End Date", &header_format)?;
-    worksheet.write_string_with_format(0, 4, "Status", &header_format)?;
-    worksheet.write_string_with_format(0, 5, "Priority", &header_format)?;
-    worksheet.write_string_with_format(0, 6, "Assignee", &header_format)?;
-    worksheet.write_string_with_format(0, 7, "Duration (Days)", &header_format)?;
-    worksheet.write_string_with_format(0, 8, "% Complete", &header_format)?;
+    for (col, header) in HEADERS.iter().enumerate() {
+        worksheet.write_string_with_format(0, col as u16, *header, &header_format)?;
+    }
     
-    // Write task data
+    // Write task data. Cell errors (e.g. text over Excel's length limit)
+    // name the row and column so the task can be found.
     for (row, task) in tasks.iter().enumerate() {
         let row_num = (row + 1) as u32;
+        let cell = |col: u16| move |e: XlsxError| FileError::Cell {
+            row: row_num + 1,
+            column: HEADERS[col as usize],
+            message: e.to_string(),
+        };
         
-        worksheet.write_string(row_num, 0, &task.name)?;
-        worksheet.write_string(row_num, 1, &task.description)?;
+        worksheet.write_string(row_num, 0, &task.name).map_err(cell(0))?;
+        worksheet.write_string(row_num, 1, &task.description).map_err(cell(1))?;
         
-        // Write dates as formatted strings
-        worksheet.write_string(row_num, 2, &task.start_date.format("%Y-%m-%d").to_string())?;
-        worksheet.write_string(row_num, 3, &task.end_date.format("%Y-%m-%d").to_string())?;
-        worksheet.write_string(row_num, 4, format!("{:?}", task.status))?;
-        worksheet.write_number(row_num, 5, task.priority as f64)?;
-        worksheet.write_string(row_num, 6, &task.assignee)?;
-        worksheet.write_number(row_num, 7, task.duration_days as f64)?;
-        worksheet.write_number(row_num, 8, task.percent_complete as f64)?;
+        // Write dates as formatted strings, with the time of day
+        let moment = |m: NaiveDateTime| m.format("%Y-%m-%d %H:%M").to_string();
+        worksheet.write_string(row_num, 2, moment(task.start)).map_err(cell(2))?;
+        worksheet.write_string(row_num, 3, moment(task.finish)).map_err(cell(3))?;
+        worksheet.write_string(row_num, 4, format!("{:?}", task.status)).map_err(cell(4))?;
+        worksheet.write_number(row_num, 5, task.priority as f64).map_err(cell(5))?;
+        worksheet.write_string(row_num, 6, &task.assignee).map_err(cell(6))?;
+        // Text like "4 hrs" keeps the unit and flags; it reads back as-is
+        worksheet.write_string(row_num, 7, task.duration.to_string()).map_err(cell(7))?;
+        worksheet.write_number(row_num, 8, task.percent_complete as f64).map_err(cell(8))?;
+        worksheet.write_number(row_num, 9, task.cost).map_err(cell(9))?;
+        worksheet.write_number(row_num, 10, task.actual_cost).map_err(cell(10))?;
+        worksheet.write_string(row_num, 11, task.actual_start.map(moment).unwrap_or_default()).map_err(cell(11))?;
+        worksheet.write_string(row_num, 12, task.actual_finish.map(moment).unwrap_or_default()).map_err(cell(12))?;
+        worksheet.write_string(row_num, 13, task.actual_duration.to_string()).map_err(cell(13))?;
+        worksheet.write_string(row_num, 14, task.remaining_duration.to_string()).map_err(cell(14))?;
     }
     
     // Auto-fit columns
@@ -48,8 +118,99 @@ pub fn export_to_xlsx(tasks: &[Task], path: &str) -> Result<(), Box<dyn std::err
     worksheet.set_column_width(6, 20.0)?;
     worksheet.set_column_width(7, 15.0)?;
     worksheet.set_column_width(8, 12.0)?;
+    worksheet.set_column_width(9, 12.0)?;
+    worksheet.set_column_width(10, 12.0)?;
+
+    write_earned_value(workbook.add_worksheet(), tasks, status_date, &header_format)?;
     
-    workbook.save(path)?;
+    workbook.save(path).map_err(|e| match e {
+        XlsxError::IoError(source) => FileError::Io { path: path.to_path_buf(), source },
+        e => e.into(),
+    })?;
+    Ok(())
+}
+
+
+/// One row per task with its (rolled up) metrics, and a total row from the
+/// leaf tasks.
+fn write_earned_value(sheet: &mut Worksheet, tasks: &[Task], status_date: NaiveDate, header_format: &Format) -> Result<(), FileError> {
+    let basis = Basis::for_tasks(tasks);
+    sheet.set_name("Earned Value")?;
+    sheet.write_string(0, 0, format!("Status date: {}", status_date.format("%Y-%m-%d")))?;
+    sheet.write_string(0, 2, format!("Measured in {}", basis.unit()))?;
+    for (col, header) in EV_HEADERS.iter().enumerate() {
+        sheet.write_string_with_format(2, col as u16, *header, header_format)?;
+    }
+
+    let write_row = |sheet: &mut Worksheet, row: u32, metrics: Metrics, budget: f64| -> Result<(), XlsxError> {
+        sheet.write_number(row, 2, budget)?;
+        sheet.write_number(row, 3, metrics.bcws)?;
+        sheet.write_number(row, 4, metrics.bcwp)?;
+        sheet.write_number(row, 5, metrics.acwp)?;
+        sheet.write_number(row, 6, metrics.sv())?;
+        sheet.write_number(row, 7, metrics.cv())?;
+        if let Some(spi) = metrics.spi() {
+            sheet.write_number(row, 8, spi)?;
+        }
+        if let Some(cpi) = metrics.cpi() {
+            sheet.write_number(row, 9, cpi)?;
+        }
+        Ok(())
+    };
+    // Budget at completion is everything planned by the end of time
+    let far_future = NaiveDate::MAX;
+
+    for (i, task) in tasks.iter().enumerate() {
+        let row = i as u32 + 3;
+        let metrics = earned_value::rollup(tasks, i, status_date, basis);
+        let budget = earned_value::rollup(tasks, i, far_future, basis).bcws;
+        sheet.write_string(row, 0, &task.wbs)?;
+        sheet.write_string(row, 1, &task.name)?;
+        write_row(sheet, row, metrics, budget)?;
+        sheet.write_number(row, 10, task.outline_level() as f64)?;
+    }
+
+    let total_row = tasks.len() as u32 + 3;
+    let bold = Format::new().set_bold();
+    sheet.write_string_with_format(total_row, 1, "Total", &bold)?;
+    write_row(
+        sheet,
+        total_row,
+        earned_value::totals(tasks, status_date, basis),
+        earned_value::totals(tasks, far_future, basis).bcws,
+    )?;
+
+    sheet.set_column_width(0, 10.0)?;
+    sheet.set_column_width(1, 30.0)?;
+    for col in 2..=9 {
+        sheet.set_column_width(col, 12.0)?;
+    }
     Ok(())
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::duration::{Duration, DurationUnit};
+    use crate::registry::{ImportOptions, Registry};
+
+    #[test]
+    fn test_xlsx_keeps_odd_durations() {
+        let day = NaiveDate::from_ymd_opt(2024, 1, 9).unwrap();
+        let mut task = Task::new("Review".to_string());
+        task.reschedule(crate::task::at_start(day), day.and_hms_opt(13, 30, 0).unwrap());
+        task.actual_duration = Duration::from_minutes(100, DurationUnit::Days);
+        task.remaining_duration = Duration::from_minutes(170, DurationUnit::Weeks);
+
+        let registry = Registry::with_builtin_formats();
+        let path = std::env::temp_dir().join(format!("odd_durations_{}.xlsx", std::process::id()));
+        registry.export_file(std::slice::from_ref(&task), &path, &ExportOptions::default()).unwrap();
+        let imported = registry.import_file(&path, &ImportOptions::default()).unwrap().tasks;
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(task.duration.minutes, 270);
+        assert_eq!(imported[0].duration, task.duration);
+        assert_eq!(imported[0].actual_duration, task.actual_duration);
+        assert_eq!(imported[0].remaining_duration, task.remaining_duration);
+    }
+}
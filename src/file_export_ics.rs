@@ -0,0 +1,288 @@
+use chrono::{Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use crate::registry::{Capabilities, ExportOptions, Exporter};
+use crate::task::{default_finish_time, default_start_time, Task, TaskStatus};
+
+/// iCalendar settings for `IcsExporter`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IcsOptions {
+    pub milestones_only: bool,
+    /// Only tasks assigned to this person (case-insensitive); `None` exports everyone's.
+    pub assignee: Option<String>,
+    /// Write tasks as VTODO (with percent complete) instead of VEVENT windows.
+    /// Milestones are always events.
+    pub tasks_as_todos: bool,
+    /// Goes into every UID so two projects can share a calendar. The GUI
+    /// and CLI fall back on the base file's name, the exporter on the
+    /// output file's.
+    pub project: String,
+}
+
+pub struct IcsExporter;
+
+impl Exporter for IcsExporter {
+    fn name(&self) -> &'static str {
+        "iCalendar"
+    }
+
+    fn extension(&self) -> &'static str {
+        "ics"
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::new(false, false, true)
+    }
+
+    fn export(&self, tasks: &[Task], path: &Path, options: &ExportOptions) -> Result<(), Box<dyn std::error::Error>> {
+        let mut calendar = options.calendar.clone();
+        if calendar.project.is_empty() {
+            calendar.project = path.file_stem().unwrap_or_default().to_string_lossy().into_owned();
+        }
+        let mut file = File::create(path)?;
+        file.write_all(tasks_to_ics(tasks, &calendar).as_bytes())?;
+        Ok(())
+    }
+}
+
+pub fn tasks_to_ics(tasks: &[Task], options: &IcsOptions) -> String {
+    let stamp = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//msproject-merge//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+    ];
+
+    let project = fnv1a(options.project.as_bytes()) as u32;
+    let mut used_uids = HashSet::new();
+    for task in tasks.iter().filter(|t| include_task(t, options)) {
+        let todo = options.tasks_as_todos && !task.milestone;
+        let component = if todo { "VTODO" } else { "VEVENT" };
+
+        lines.push(format!("BEGIN:{}", component));
+        // Later tasks with the same key get a counter so no two events clash
+        let key = stable_uid(task);
+        let uid = (1..).map(|n| if n == 1 { key.clone() } else { format!("{}-{}", key, n) })
+            .find(|uid| used_uids.insert(uid.clone()))
+            .unwrap_or(key);
+        lines.push(format!("UID:{}.{:08x}@msproject-merge", uid, project));
+        lines.push(format!("DTSTAMP:{}", stamp));
+        lines.push(format!("SUMMARY:{}", escape_text(&task.name)));
+        if !task.description.is_empty() {
+            lines.push(format!("DESCRIPTION:{}", escape_text(&task.description)));
+        }
+
+        // All-day dates for full working days, otherwise local times.
+        // All-day DTEND/DUE are exclusive so the last day needs +1
+        let end = if task.milestone { task.start } else { task.finish.max(task.start) };
+        let end_key = if todo { "DUE" } else { "DTEND" };
+        let all_day = task.start.time() == default_start_time()
+            && (task.milestone || end.time() == default_finish_time());
+        if all_day {
+            lines.push(format!("DTSTART;VALUE=DATE:{}", ics_date(task.start.date())));
+            lines.push(format!("{};VALUE=DATE:{}", end_key, ics_date(end.date() + Duration::days(1))));
+        } else {
+            lines.push(format!("DTSTART:{}", task.start.format("%Y%m%dT%H%M%S")));
+            lines.push(format!("{}:{}", end_key, end.format("%Y%m%dT%H%M%S")));
+        }
+
+        if todo {
+            lines.push(format!("PERCENT-COMPLETE:{}", task.percent_complete.min(100)));
+            lines.push(format!("STATUS:{}", match task.status {
+                TaskStatus::Completed => "COMPLETED",
+                TaskStatus::Cancelled => "CANCELLED",
+                TaskStatus::InProgress => "IN-PROCESS",
+                TaskStatus::NotStarted | TaskStatus::OnHold => "NEEDS-ACTION",
+            }));
+        } else if !task.milestone {
+            // PERCENT-COMPLETE isn't valid on events; keep it visible anyway
+            lines.push(format!("X-PERCENT-COMPLETE:{}", task.percent_complete.min(100)));
+            lines.push("TRANSP:TRANSPARENT".to_string());
+        }
+        if task.status == TaskStatus::Cancelled && !todo {
+            lines.push("STATUS:CANCELLED".to_string());
+        }
+
+        for name in assignees(task) {
+            lines.push(attendee(name));
+        }
+
+        lines.push(format!("END:{}", component));
+    }
+
+    lines.push("END:VCALENDAR".to_string());
+
+    let mut out = String::new();
+    for line in lines {
+        out.push_str(&fold_line(&line));
+        out.push_str("\r\n");
+    }
+    out
+}
+
+fn include_task(task: &Task, options: &IcsOptions) -> bool {
+    if options.milestones_only && !task.milestone {
+        return false;
+    }
+    match &options.assignee {
+        Some(wanted) => assignees(task).any(|a| a.eq_ignore_ascii_case(wanted.trim())),
+        None => true,
+    }
+}
+
+/// Individual names from a free-text assignee field like "Alice, Bob".
+pub fn assignees(task: &Task) -> impl Iterator<Item = &str> {
+    task.assignee.split([',', ';']).map(str::trim).filter(|s| !s.is_empty())
+}
+
+/// The same task exported twice must keep its UID so calendars update it
+/// rather than adding a duplicate. The task's own UID is the most specific
+/// key, then WBS, then its name and start; the project is added on top.
+fn stable_uid(task: &Task) -> String {
+    if task.uid > 0 {
+        format!("uid-{}", task.uid)
+    } else if !task.wbs.is_empty() {
+        format!("wbs-{}", task.wbs)
+    } else {
+        let key = format!("{}\n{}", task.name, task.start.format("%Y-%m-%dT%H:%M"));
+        format!("name-{:016x}", fnv1a(key.as_bytes()))
+    }
+}
+
+// std's hasher isn't guaranteed stable between releases; FNV-1a is
+fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for b in bytes {
+        hash ^= *b as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+fn attendee(name: &str) -> String {
+    let address = if name.contains('@') {
+        format!("mailto:{}", name)
+    } else {
+        // No address known; any URI is a valid cal-address
+        format!("urn:x-resource:{}", name.replace(' ', "%20"))
+    };
+    format!("ATTENDEE;CN=\"{}\";ROLE=REQ-PARTICIPANT:{}", name.replace('"', "'"), address)
+}
+
+fn ics_date(date: NaiveDate) -> String {
+    date.format("%Y%m%d").to_string()
+}
+
+fn escape_text(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// RFC 5545 lines are limited to 75 octets; continuation lines start with a space.
+fn fold_line(line: &str) -> String {
+    let mut out = String::new();
+    let mut width = 0;
+    for c in line.chars() {
+        let len = c.len_utf8();
+        if width + len > 75 {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(c);
+        width += len;
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::{at_finish, at_start};
+
+    fn sample_tasks() -> Vec<Task> {
+        let mut design = Task::new("Design, phase 1".to_string());
+        design.wbs = "1.1".to_string();
+        design.start = at_start(NaiveDate::from_ymd_opt(2024, 3, 4).unwrap());
+        design.finish = at_finish(NaiveDate::from_ymd_opt(2024, 3, 8).unwrap());
+        design.percent_complete = 40;
+        design.assignee = "Alice, Bob".to_string();
+
+        let mut signoff = Task::new("Sign-off".to_string());
+        signoff.wbs = "1.2".to_string();
+        signoff.milestone = true;
+        signoff.start = at_start(NaiveDate::from_ymd_opt(2024, 3, 11).unwrap());
+        signoff.finish = signoff.start;
+        signoff.assignee = "Carol".to_string();
+
+        vec![design, signoff]
+    }
+
+    #[test]
+    fn test_tasks_to_ics() {
+        let options = IcsOptions { tasks_as_todos: true, ..Default::default() };
+        let ics = tasks_to_ics(&sample_tasks(), &options);
+
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(ics.contains("BEGIN:VTODO\r\nUID:wbs-1.1."));
+        assert!(ics.contains("SUMMARY:Design\\, phase 1\r\n"));
+        assert!(ics.contains("DUE;VALUE=DATE:20240309\r\n"));
+        assert!(ics.contains("PERCENT-COMPLETE:40\r\n"));
+        assert!(ics.contains("ATTENDEE;CN=\"Bob\";ROLE=REQ-PARTICIPANT:urn:x-resource:Bob\r\n"));
+        // Milestones stay events even when tasks are todos
+        assert!(ics.contains("BEGIN:VEVENT\r\nUID:wbs-1.2."));
+        assert!(ics.contains("DTEND;VALUE=DATE:20240312\r\n"));
+
+        // A half-day task keeps its times
+        let mut tasks = sample_tasks();
+        tasks[0].finish = tasks[0].start.date().and_hms_opt(12, 0, 0).unwrap();
+        let ics = tasks_to_ics(&tasks, &IcsOptions::default());
+        assert!(ics.contains("DTSTART:20240304T080000\r\nDTEND:20240304T120000\r\n"));
+    }
+
+    #[test]
+    fn test_ics_filters() {
+        let tasks = sample_tasks();
+
+        let milestones = tasks_to_ics(&tasks, &IcsOptions { milestones_only: true, ..Default::default() });
+        assert!(!milestones.contains("wbs-1.1."));
+        assert!(milestones.contains("wbs-1.2."));
+
+        let bobs = tasks_to_ics(&tasks, &IcsOptions { assignee: Some("bob".to_string()), ..Default::default() });
+        assert!(bobs.contains("wbs-1.1."));
+        assert!(!bobs.contains("wbs-1.2."));
+    }
+
+    #[test]
+    fn test_ics_uids_are_unique() {
+        let mut tasks = sample_tasks();
+        tasks[0].uid = 7;
+        let mut review = Task::new("Review".to_string());
+        review.start = tasks[1].start;
+        tasks.extend([review.clone(), review]);
+
+        let ics = tasks_to_ics(&tasks, &IcsOptions::default());
+        let uids: Vec<&str> = ics.lines().filter_map(|l| l.strip_prefix("UID:")).collect();
+        assert!(uids[0].starts_with("uid-7.") && uids[0].ends_with("@msproject-merge"));
+        assert!(uids[1].starts_with("wbs-1.2."));
+        assert_eq!(uids.iter().collect::<HashSet<_>>().len(), 4);
+        assert!(uids[3].contains("-2."));
+
+        // Another project's tasks don't overwrite these in a shared calendar
+        let other = tasks_to_ics(&tasks, &IcsOptions { project: "other.xml".to_string(), ..Default::default() });
+        assert!(!other.lines().any(|l| l.strip_prefix("UID:").is_some_and(|uid| uids.contains(&uid))));
+    }
+
+    #[test]
+    fn test_fold_line() {
+        let folded = fold_line(&"x".repeat(100));
+        assert_eq!(folded, format!("{}\r\n {}", "x".repeat(75), "x".repeat(25)));
+    }
+}
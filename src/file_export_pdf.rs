@@ -0,0 +1,162 @@
+use std::io::Write;
+use std::path::Path;
+use crate::chart::{approx_text_width, build_chart, Anchor, Chart, Rgb, Shape};
+use crate::registry::{Capabilities, ExportOptions, Exporter};
+use crate::task::Task;
+
+pub struct PdfExporter;
+
+impl Exporter for PdfExporter {
+    fn name(&self) -> &'static str {
+        "Gantt chart (PDF)"
+    }
+
+    fn extension(&self) -> &'static str {
+        "pdf"
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::new(true, true, false)
+    }
+
+    fn export(&self, tasks: &[Task], path: &Path, options: &ExportOptions) -> Result<(), Box<dyn std::error::Error>> {
+        std::fs::write(path, chart_to_pdf(&build_chart(tasks, &options.chart)))?;
+        Ok(())
+    }
+}
+
+/// A single-page PDF sized to the chart, using the standard Helvetica fonts
+/// so nothing has to be embedded.
+pub fn chart_to_pdf(chart: &Chart) -> Vec<u8> {
+    let content = content_stream(chart);
+
+    let mut objects: Vec<Vec<u8>> = vec![
+        b"<< /Type /Catalog /Pages 2 0 R >>".to_vec(),
+        b"<< /Type /Pages /Kids [3 0 R] /Count 1 >>".to_vec(),
+        format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {:.1} {:.1}] /Resources << /Font << /F1 4 0 R /F2 5 0 R >> >> /Contents 6 0 R >>",
+            chart.width, chart.height
+        ).into_bytes(),
+        b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>".to_vec(),
+        b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica-Bold /Encoding /WinAnsiEncoding >>".to_vec(),
+    ];
+    let mut stream = format!("<< /Length {} >>\nstream\n", content.len()).into_bytes();
+    stream.extend_from_slice(&content);
+    stream.extend_from_slice(b"\nendstream");
+    objects.push(stream);
+
+    let mut out = b"%PDF-1.4\n%\xE2\xE3\xCF\xD3\n".to_vec();
+    let mut offsets = Vec::with_capacity(objects.len());
+    for (i, object) in objects.iter().enumerate() {
+        offsets.push(out.len());
+        let _ = writeln!(out, "{} 0 obj", i + 1);
+        out.extend_from_slice(object);
+        out.extend_from_slice(b"\nendobj\n");
+    }
+
+    let xref = out.len();
+    let _ = write!(out, "xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+    for offset in offsets {
+        let _ = writeln!(out, "{:010} 00000 n ", offset);
+    }
+    let _ = write!(out, "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n", objects.len() + 1, xref);
+    out
+}
+
+fn content_stream(chart: &Chart) -> Vec<u8> {
+    let mut out = Vec::new();
+    // PDF's origin is bottom-left
+    let flip = |y: f32| chart.height - y;
+
+    for shape in &chart.shapes {
+        let _ = match shape {
+            Shape::Rect { x, y, w, h, fill } => writeln!(
+                out,
+                "{} {:.2} {:.2} {:.2} {:.2} re f",
+                color(*fill, "rg"), x, flip(y + h), w, h
+            ),
+            Shape::Line { points, color: c, width } => writeln!(
+                out,
+                "{} {:.2} w {} S",
+                color(*c, "RG"), width, path(points, flip)
+            ),
+            Shape::Polygon { points, fill } => writeln!(
+                out,
+                "{} {} h f",
+                color(*fill, "rg"), path(points, flip)
+            ),
+            Shape::Text { x, y, text, size, color: c, anchor, bold } => {
+                let width = approx_text_width(text, *size);
+                let x = match anchor {
+                    Anchor::Start => *x,
+                    Anchor::Middle => x - width / 2.0,
+                };
+                let _ = write!(
+                    out,
+                    "{} BT /{} {} Tf {:.2} {:.2} Td (",
+                    color(*c, "rg"), if *bold { "F2" } else { "F1" }, size, x, flip(*y)
+                );
+                out.extend(encode_text(text));
+                writeln!(out, ") Tj ET")
+            }
+        };
+    }
+    out
+}
+
+fn color(c: Rgb, operator: &str) -> String {
+    format!("{:.3} {:.3} {:.3} {}", c.0 as f32 / 255.0, c.1 as f32 / 255.0, c.2 as f32 / 255.0, operator)
+}
+
+fn path(points: &[(f32, f32)], flip: impl Fn(f32) -> f32) -> String {
+    points.iter()
+        .enumerate()
+        .map(|(i, (x, y))| format!("{:.2} {:.2} {}", x, flip(*y), if i == 0 { "m" } else { "l" }))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// WinAnsi bytes for a PDF string literal. Characters outside Latin-1 become '?'.
+fn encode_text(text: &str) -> Vec<u8> {
+    let mut out = Vec::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '(' | ')' | '\\' => {
+                out.push(b'\\');
+                out.push(c as u8);
+            }
+            '…' => out.push(0x85),
+            '–' => out.push(0x96),
+            '—' => out.push(0x97),
+            ' '..='~' | '\u{A0}'..='\u{FF}' => out.push(c as u32 as u8),
+            _ => out.push(b'?'),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chart::ChartOptions;
+
+    #[test]
+    fn test_chart_to_pdf_xref_offsets() {
+        let tasks = vec![Task::new("Design (draft)".to_string())];
+        let pdf = chart_to_pdf(&build_chart(&tasks, &ChartOptions::default()));
+        // Latin-1 keeps byte offsets intact, unlike a lossy UTF-8 conversion
+        let text: String = pdf.iter().map(|&b| b as char).collect();
+
+        assert!(text.starts_with("%PDF-1.4"));
+        assert!(text.contains("(Design \\(draft\\)) Tj"));
+
+        // Every xref entry must point at the start of its object
+        let startxref: usize = text.rsplit("startxref\n").next().unwrap().lines().next().unwrap().parse().unwrap();
+        let xref = &pdf[startxref..];
+        assert!(xref.starts_with(b"xref\n"));
+        for (i, line) in xref.split(|&b| b == b'\n').skip(3).take(6).enumerate() {
+            let offset: usize = std::str::from_utf8(&line[..10]).unwrap().parse().unwrap();
+            assert!(pdf[offset..].starts_with(format!("{} 0 obj", i + 1).as_bytes()));
+        }
+    }
+}
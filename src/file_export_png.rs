@@ -0,0 +1,145 @@
+use ab_glyph::{Font, FontRef, PxScale, ScaleFont};
+use std::path::Path;
+use tiny_skia::{Color, FillRule, Paint, PathBuilder, Pixmap, Rect, Stroke, Transform};
+use crate::chart::{build_chart, Anchor, Chart, Rgb, Shape};
+use crate::registry::{Capabilities, ExportOptions, Exporter};
+use crate::task::Task;
+
+/// Pixels per chart point, so the image stays sharp when zoomed or printed.
+const SCALE: f32 = 2.0;
+
+pub struct PngExporter;
+
+impl Exporter for PngExporter {
+    fn name(&self) -> &'static str {
+        "Gantt chart (PNG)"
+    }
+
+    fn extension(&self) -> &'static str {
+        "png"
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::new(true, true, false)
+    }
+
+    fn export(&self, tasks: &[Task], path: &Path, options: &ExportOptions) -> Result<(), Box<dyn std::error::Error>> {
+        let pixmap = render_chart(&build_chart(tasks, &options.chart))?;
+        std::fs::write(path, pixmap.encode_png()?)?;
+        Ok(())
+    }
+}
+
+pub fn render_chart(chart: &Chart) -> Result<Pixmap, Box<dyn std::error::Error>> {
+    let mut pixmap = Pixmap::new((chart.width * SCALE).ceil() as u32, (chart.height * SCALE).ceil() as u32)
+        .ok_or("Chart is too large to render")?;
+    pixmap.fill(Color::WHITE);
+
+    // Reuse the GUI's bundled font so the export needs no system fonts
+    let fonts = egui::FontDefinitions::default();
+    let font_data = fonts.font_data.get("Ubuntu-Light").ok_or("Bundled font not found")?;
+    let font = FontRef::try_from_slice(&font_data.font)?;
+
+    let transform = Transform::from_scale(SCALE, SCALE);
+    for shape in &chart.shapes {
+        match shape {
+            Shape::Rect { x, y, w, h, fill } => {
+                if let Some(rect) = Rect::from_xywh(*x, *y, *w, *h) {
+                    pixmap.fill_rect(rect, &paint(*fill), transform, None);
+                }
+            }
+            Shape::Line { points, color, width } => {
+                if let Some(path) = polyline(points, false) {
+                    let stroke = Stroke { width: *width, ..Stroke::default() };
+                    pixmap.stroke_path(&path, &paint(*color), &stroke, transform, None);
+                }
+            }
+            Shape::Polygon { points, fill } => {
+                if let Some(path) = polyline(points, true) {
+                    pixmap.fill_path(&path, &paint(*fill), FillRule::Winding, transform, None);
+                }
+            }
+            Shape::Text { x, y, text, size, color, anchor, bold } => {
+                draw_text(&mut pixmap, &font, (*x, *y), text, *size, *color, *anchor, *bold);
+            }
+        }
+    }
+    Ok(pixmap)
+}
+
+fn paint(color: Rgb) -> Paint<'static> {
+    let mut paint = Paint::default();
+    paint.set_color_rgba8(color.0, color.1, color.2, 255);
+    paint.anti_alias = true;
+    paint
+}
+
+fn polyline(points: &[(f32, f32)], close: bool) -> Option<tiny_skia::Path> {
+    let (first, rest) = points.split_first()?;
+    let mut builder = PathBuilder::new();
+    builder.move_to(first.0, first.1);
+    for (x, y) in rest {
+        builder.line_to(*x, *y);
+    }
+    if close {
+        builder.close();
+    }
+    builder.finish()
+}
+
+#[allow(clippy::too_many_arguments)]
+fn draw_text(
+    pixmap: &mut Pixmap,
+    font: &FontRef,
+    (x, y): (f32, f32),
+    text: &str,
+    size: f32,
+    color: Rgb,
+    anchor: Anchor,
+    bold: bool,
+) {
+    let scaled = font.as_scaled(PxScale::from(size * SCALE));
+    let width: f32 = text.chars().map(|c| scaled.h_advance(scaled.glyph_id(c))).sum();
+    let mut pen_x = x * SCALE - match anchor {
+        Anchor::Start => 0.0,
+        Anchor::Middle => width / 2.0,
+    };
+    let baseline = y * SCALE;
+
+    let (pixel_width, pixel_height) = (pixmap.width() as i32, pixmap.height() as i32);
+    let data = pixmap.data_mut();
+    let mut blend = |px: i32, py: i32, coverage: f32| {
+        if px < 0 || py < 0 || px >= pixel_width || py >= pixel_height {
+            return;
+        }
+        let offset = ((py * pixel_width + px) * 4) as usize;
+        let alpha = coverage.clamp(0.0, 1.0);
+        // The background is opaque, so blending premultiplied values is plain lerp
+        for (channel, value) in [color.0, color.1, color.2].into_iter().enumerate() {
+            let dst = data[offset + channel] as f32;
+            data[offset + channel] = (dst + (value as f32 - dst) * alpha).round() as u8;
+        }
+    };
+
+    let mut previous = None;
+    for c in text.chars() {
+        let id = scaled.glyph_id(c);
+        if let Some(prev) = previous {
+            pen_x += scaled.kern(prev, id);
+        }
+        previous = Some(id);
+
+        // The bundled font has no bold face; overstrike instead
+        let passes: &[f32] = if bold { &[0.0, 0.6 * SCALE] } else { &[0.0] };
+        for dx in passes {
+            let glyph = id.with_scale_and_position(size * SCALE, ab_glyph::point(pen_x + dx, baseline));
+            if let Some(outlined) = font.outline_glyph(glyph) {
+                let bounds = outlined.px_bounds();
+                outlined.draw(|gx, gy, coverage| {
+                    blend(bounds.min.x as i32 + gx as i32, bounds.min.y as i32 + gy as i32, coverage);
+                });
+            }
+        }
+        pen_x += scaled.h_advance(id);
+    }
+}
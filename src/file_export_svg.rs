@@ -0,0 +1,100 @@
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+use crate::chart::{build_chart, Anchor, Chart, Shape};
+use crate::registry::{Capabilities, ExportOptions, Exporter};
+use crate::task::Task;
+
+pub struct SvgExporter;
+
+impl Exporter for SvgExporter {
+    fn name(&self) -> &'static str {
+        "Gantt chart (SVG)"
+    }
+
+    fn extension(&self) -> &'static str {
+        "svg"
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::new(true, true, false)
+    }
+
+    fn export(&self, tasks: &[Task], path: &Path, options: &ExportOptions) -> Result<(), Box<dyn std::error::Error>> {
+        fs::write(path, chart_to_svg(&build_chart(tasks, &options.chart)))?;
+        Ok(())
+    }
+}
+
+pub fn chart_to_svg(chart: &Chart) -> String {
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w:.0}" height="{h:.0}" viewBox="0 0 {w:.1} {h:.1}" font-family="Helvetica, Arial, sans-serif">"#,
+        w = chart.width,
+        h = chart.height
+    );
+    let _ = writeln!(out, r##"<rect width="100%" height="100%" fill="#ffffff"/>"##);
+
+    for shape in &chart.shapes {
+        let _ = match shape {
+            Shape::Rect { x, y, w, h, fill } => writeln!(
+                out,
+                r#"<rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" fill="{}"/>"#,
+                x, y, w, h, fill.hex()
+            ),
+            Shape::Line { points, color, width } => writeln!(
+                out,
+                r#"<polyline points="{}" fill="none" stroke="{}" stroke-width="{}"/>"#,
+                points_attr(points), color.hex(), width
+            ),
+            Shape::Polygon { points, fill } => writeln!(
+                out,
+                r#"<polygon points="{}" fill="{}"/>"#,
+                points_attr(points), fill.hex()
+            ),
+            Shape::Text { x, y, text, size, color, anchor, bold } => writeln!(
+                out,
+                r#"<text x="{:.1}" y="{:.1}" font-size="{}" fill="{}" text-anchor="{}"{}>{}</text>"#,
+                x,
+                y,
+                size,
+                color.hex(),
+                match anchor {
+                    Anchor::Start => "start",
+                    Anchor::Middle => "middle",
+                },
+                if *bold { r#" font-weight="bold""# } else { "" },
+                escape_xml(text)
+            ),
+        };
+    }
+
+    out.push_str("</svg>\n");
+    out
+}
+
+fn points_attr(points: &[(f32, f32)]) -> String {
+    points.iter().map(|(x, y)| format!("{:.1},{:.1}", x, y)).collect::<Vec<_>>().join(" ")
+}
+
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chart::ChartOptions;
+
+    #[test]
+    fn test_chart_to_svg() {
+        let tasks = vec![Task::new("Design & build".to_string())];
+        let svg = chart_to_svg(&build_chart(&tasks, &ChartOptions::default()));
+
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert!(svg.contains(">Design &amp; build</text>"));
+        assert!(svg.contains(">Critical</text>"));
+        assert!(svg.trim_end().ends_with("</svg>"));
+    }
+}
@@ -1,34 +1,99 @@
 use quick_xml::se::to_string;
-use crate::task::Task;
-use crate::mspdi::{Project, Tasks, MspdiTask};
+use chrono::NaiveDateTime;
+use crate::task::{default_finish_time, default_start_time, Task, TaskStatus};
+use crate::mspdi::{MspdiBaseline, MspdiExtendedAttribute, MspdiTask, Project, Tasks};
+use std::collections::HashSet;
 use std::fs::File;
 use std::io::Write;
+use std::path::Path;
+use crate::error::FileError;
+use crate::registry::{Capabilities, ExportOptions, Exporter};
 
-pub fn export_to_xml(tasks: &[Task], path: &str) -> Result<(), Box<dyn std::error::Error>> {
+pub struct MspdiExporter;
+
+impl Exporter for MspdiExporter {
+    fn name(&self) -> &'static str {
+        "Microsoft Project XML (MSPDI)"
+    }
+
+    fn extension(&self) -> &'static str {
+        "xml"
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        // Resources and assignments aren't written yet
+        Capabilities::new(true, true, false)
+    }
+
+    fn export(&self, tasks: &[Task], path: &Path, _options: &ExportOptions) -> Result<(), Box<dyn std::error::Error>> {
+        Ok(export_to_xml(tasks, path)?)
+    }
+}
+
+pub fn export_to_xml(tasks: &[Task], path: &Path) -> Result<(), FileError> {
     let mut mspdi_tasks = Vec::new();
     
+    // Keep imported UIDs so predecessor links stay valid; merging already
+    // made them unique, so only tasks without one get a fresh UID
+    let mut used_uids = HashSet::new();
+    let mut next_uid = tasks.iter().map(|t| t.uid).max().unwrap_or(0).max(0) + 1;
+    
     for (idx, task) in tasks.iter().enumerate() {
+        let uid = if task.uid > 0 && used_uids.insert(task.uid) {
+            task.uid
+        } else {
+            next_uid += 1;
+            next_uid - 1
+        };
+        used_uids.insert(uid);
+        let (duration, duration_format) = task.duration.to_mspdi();
         let mspdi_task = MspdiTask {
-            uid: (idx + 1) as i32,
+            uid,
             id: (idx + 1) as i32,
             name: task.name.clone(),
-            start: task.start_date.format("%Y-%m-%dT08:00:00").to_string(),
-            finish: task.end_date.format("%Y-%m-%dT17:00:00").to_string(),
-            duration: format!("PT{}H0M0S", task.duration_days * 8),
+            start: mspdi_moment(task.start),
+            finish: mspdi_moment(task.finish),
+            duration,
+            duration_format,
             percent_complete: task.percent_complete as i32,
-            active: 1,
+            active: (task.status != TaskStatus::Cancelled) as i32,
             manual: 0,
+            milestone: task.milestone as i32,
+            summary: crate::outline::is_summary(tasks, idx) as i32,
+            estimated: task.duration.estimated as i32,
             outline_number: task.wbs.clone(), // Use WBS as outline number for now
-            outline_level: 1,
-            priority: task.priority as i32,
+            outline_level: task.outline_level() as i32,
+            priority: task.priority.min(crate::priority::MAX) as i32,
             notes: task.description.clone(),
+            actual_start: task.actual_start.map(mspdi_moment).unwrap_or_default(),
+            actual_finish: task.actual_finish.map(mspdi_moment).unwrap_or_default(),
+            // Actual and remaining share the task's DurationFormat
+            actual_duration: task.actual_duration.to_mspdi().0,
+            remaining_duration: task.remaining_duration.to_mspdi().0,
+            cost: (task.cost * 100.0).round(),
+            actual_cost: (task.actual_cost * 100.0).round(),
             wbs: task.wbs.clone(),
             predecessor_link: task.predecessors.iter().map(|p| crate::mspdi::MspdiPredecessorLink {
                 predecessor_uid: p.predecessor_uid,
                 link_type: p.link_type,
                 cross_project: 0,
                 link_lag: p.link_lag,
-                lag_format: 7, // Default format
+                lag_format: p.lag_format,
+            }).collect(),
+            // Read back by the importer; MS Project shows it as Text1
+            extended_attribute: match task.status {
+                TaskStatus::OnHold => vec![MspdiExtendedAttribute {
+                    field_id: crate::mspdi::TEXT1_FIELD_ID.to_string(),
+                    value: "On Hold".to_string(),
+                }],
+                _ => Vec::new(),
+            },
+            baseline: task.baseline.iter().map(|b| MspdiBaseline {
+                number: 0,
+                start: mspdi_moment(b.start),
+                finish: mspdi_moment(b.finish),
+                duration: b.duration.to_mspdi().0,
+                cost: (b.cost * 100.0).round(),
             }).collect(),
         };
         mspdi_tasks.push(mspdi_task);
@@ -36,16 +101,80 @@ pub fn export_to_xml(tasks: &[Task], path: &str) -> Result<(), Box<dyn std::erro
     
     let project = Project {
         title: "Merged Project".to_string(),
+        default_start_time: default_start_time().format("%H:%M:%S").to_string(),
+        default_finish_time: default_finish_time().format("%H:%M:%S").to_string(),
         tasks: Tasks { task: mspdi_tasks },
     };
     
-    let xml_string = to_string(&project)?;
+    let xml_string = to_string(&project).map_err(|e| FileError::Xml {
+        element: Some("<Project>".to_string()),
+        message: e.to_string(),
+    })?;
     
     // Add XML declaration
     let final_xml = format!("<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n{}", xml_string);
     
-    let mut file = File::create(path)?;
-    file.write_all(final_xml.as_bytes())?;
+    let io_error = |source| FileError::Io { path: path.to_path_buf(), source };
+    let mut file = File::create(path).map_err(io_error)?;
+    file.write_all(final_xml.as_bytes()).map_err(io_error)?;
     
     Ok(())
 }
+
+fn mspdi_moment(moment: NaiveDateTime) -> String {
+    moment.format("%Y-%m-%dT%H:%M:%S").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::{ExportOptions, ImportOptions, Registry};
+
+    #[test]
+    fn test_export_keeps_outline() {
+        let task = |name: &str, wbs: &str| {
+            let mut task = Task::new(name.to_string());
+            task.wbs = wbs.to_string();
+            task
+        };
+        let tasks = vec![task("Phase", "1"), task("Design", "1.1"), task("Build", "1.2"), task("Launch", "2")];
+        let path = std::env::temp_dir().join(format!("outline_round_trip_{}.xml", std::process::id()));
+        export_to_xml(&tasks, &path).unwrap();
+        let xml = std::fs::read_to_string(&path).unwrap();
+        let imported = Registry::with_builtin_formats().import_file(&path, &ImportOptions::default()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(xml.contains("<Name>Phase</Name>") && xml.contains("<Summary>1</Summary>"));
+        assert!(xml.contains("<OutlineLevel>2</OutlineLevel>"));
+        let outline: Vec<(&str, usize)> = imported.tasks.iter().map(|t| (t.wbs.as_str(), t.outline_level())).collect();
+        assert_eq!(outline, vec![("1", 1), ("1.1", 2), ("1.2", 2), ("2", 1)]);
+        assert!(crate::outline::is_summary(&imported.tasks, 0) && !crate::outline::is_summary(&imported.tasks, 3));
+    }
+
+    #[test]
+    fn test_half_days_survive_round_trips() {
+        let day = chrono::NaiveDate::from_ymd_opt(2024, 1, 9).unwrap();
+        let mut morning = Task::new("Morning".to_string());
+        morning.reschedule(crate::task::at_start(day), day.and_hms_opt(12, 0, 0).unwrap());
+        // Low enough to be mistaken for a 1-10 rating
+        morning.priority = 5;
+        let mut afternoon = Task::new("Afternoon".to_string());
+        afternoon.reschedule(day.and_hms_opt(13, 0, 0).unwrap(), crate::task::at_finish(day));
+        let tasks = vec![morning, afternoon];
+
+        let registry = Registry::with_builtin_formats();
+        for extension in ["xml", "xlsx"] {
+            let path = std::env::temp_dir().join(format!("half_days_{}.{}", std::process::id(), extension));
+            registry.export_file(&tasks, &path, &ExportOptions::default()).unwrap();
+            let imported = registry.import_file(&path, &ImportOptions::default()).unwrap().tasks;
+            std::fs::remove_file(&path).unwrap();
+
+            assert_eq!(imported[0].finish, day.and_hms_opt(12, 0, 0).unwrap(), "{}", extension);
+            assert_eq!((imported[1].start, imported[1].finish), (tasks[1].start, tasks[1].finish), "{}", extension);
+            // Same day, one after the other
+            assert!(imported[0].finish <= imported[1].start, "{}", extension);
+            assert_eq!(imported[0].duration.minutes + imported[1].duration.minutes, 480, "{}", extension);
+            assert_eq!(imported[0].priority, 5, "{}", extension);
+        }
+    }
+}
@@ -1,13 +1,90 @@
-use calamine::{open_workbook, Reader, Xlsx};
-use std::path::Path;
-use crate::task::{Task, TaskStatus};
+use calamine::{Reader, Xlsx};
+use std::io::Cursor;
+use crate::diagnostics::{Diagnostic, SKIPPED};
+use crate::dates::{self, DateLocale};
+use crate::duration::Duration;
+use crate::error::FileError;
+use crate::registry::{Capabilities, Confidence, ImportOptions, Importer, Probe};
+use crate::task::{at_start, default_finish_time, default_start_time, format_moment, Task, TaskStatus};
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
 
-pub fn import_xlsx(path: &Path) -> Result<Vec<Task>, Box<dyn std::error::Error>> {
-    let mut workbook: Xlsx<_> = open_workbook(path)?;
+pub struct XlsxImporter;
+pub struct MspdiImporter;
+
+impl Importer for XlsxImporter {
+    fn name(&self) -> &'static str {
+        "Excel workbook"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["xlsx"]
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::new(true, false, true)
+    }
+
+    fn detect(&self, probe: &Probe) -> Confidence {
+        // Zip container
+        if probe.content.starts_with(b"PK\x03\x04") {
+            Confidence::Content
+        } else if probe.has_extension(self.extensions()) {
+            Confidence::Extension
+        } else {
+            Confidence::None
+        }
+    }
+
+    fn import(&self, content: &[u8], options: &ImportOptions) -> Result<Vec<Task>, Box<dyn std::error::Error>> {
+        Ok(import_xlsx(content, options)?.0)
+    }
+
+    fn import_with_diagnostics(&self, content: &[u8], options: &ImportOptions) -> Result<(Vec<Task>, Vec<Diagnostic>), Box<dyn std::error::Error>> {
+        Ok(import_xlsx(content, options)?)
+    }
+}
+
+impl Importer for MspdiImporter {
+    fn name(&self) -> &'static str {
+        "Microsoft Project XML (MSPDI)"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["xml"]
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::new(true, true, false)
+    }
+
+    fn detect(&self, probe: &Probe) -> Confidence {
+        if probe.xml_root_is("Project") {
+            Confidence::Content
+        } else if probe.has_extension(self.extensions()) {
+            Confidence::Extension
+        } else {
+            Confidence::None
+        }
+    }
+
+    fn import(&self, content: &[u8], options: &ImportOptions) -> Result<Vec<Task>, Box<dyn std::error::Error>> {
+        Ok(import_mpp_xml(content, options)?.0)
+    }
+
+    fn import_with_diagnostics(&self, content: &[u8], options: &ImportOptions) -> Result<(Vec<Task>, Vec<Diagnostic>), Box<dyn std::error::Error>> {
+        Ok(import_mpp_xml(content, options)?)
+    }
+}
+
+fn import_xlsx(content: &[u8], options: &ImportOptions) -> Result<(Vec<Task>, Vec<Diagnostic>), FileError> {
+    let mut workbook = Xlsx::new(Cursor::new(content))?;
     let mut tasks = Vec::new();
+    let mut diagnostics = Vec::new();
 
     // Try to read from the first sheet
-    if let Some(Ok(range)) = workbook.worksheet_range_at(0) {
+    let sheet = workbook.worksheet_range_at(0).transpose()
+        .map_err(|e| FileError::Workbook(format!("First worksheet can't be read: {}", e)))?;
+    if let Some(range) = sheet {
         let mut rows = range.rows();
         
         // Skip header row if present
@@ -19,16 +96,32 @@ pub fn import_xlsx(path: &Path) -> Result<Vec<Task>, Box<dyn std::error::Error>>
             let mut end_col = None;
             let mut status_col = None;
             let mut priority_col = None;
+            let mut priority_exact = false;
             let mut assignee_col = None;
             let mut duration_col = None;
             let mut percent_col = None;
 
             let mut wbs_col = None;
+            let mut milestone_col = None;
+            let mut cost_col = None;
+            let mut actual_cost_col = None;
+            let mut actual_start_col = None;
+            let mut actual_finish_col = None;
+            let mut actual_duration_col = None;
+            let mut remaining_col = None;
 
             for (idx, cell) in header.iter().enumerate() {
                 let cell_str = cell.to_string().to_lowercase();
                 if cell_str.contains("task") && cell_str.contains("name") || cell_str == "name" {
                     name_col = Some(idx);
+                } else if cell_str.contains("actual") && cell_str.contains("start") {
+                    actual_start_col = Some(idx);
+                } else if cell_str.contains("actual") && (cell_str.contains("finish") || cell_str.contains("end")) {
+                    actual_finish_col = Some(idx);
+                } else if cell_str.contains("actual") && cell_str.contains("duration") {
+                    actual_duration_col = Some(idx);
+                } else if cell_str.contains("remaining") {
+                    remaining_col = Some(idx);
                 } else if cell_str.contains("description") || cell_str == "description" {
                     desc_col = Some(idx);
                 } else if cell_str.contains("start") && cell_str.contains("date") || cell_str == "start" {
@@ -39,6 +132,7 @@ pub fn import_xlsx(path: &Path) -> Result<Vec<Task>, Box<dyn std::error::Error>>
                     status_col = Some(idx);
                 } else if cell_str.contains("priority") {
                     priority_col = Some(idx);
+                    priority_exact = cell_str.contains("1000");
                 } else if cell_str.contains("assignee") || cell_str.contains("resource") {
                     assignee_col = Some(idx);
                 } else if cell_str.contains("duration") {
@@ -47,142 +141,254 @@ pub fn import_xlsx(path: &Path) -> Result<Vec<Task>, Box<dyn std::error::Error>>
                     percent_col = Some(idx);
                 } else if cell_str == "wbs" {
                     wbs_col = Some(idx);
+                } else if cell_str.contains("milestone") {
+                    milestone_col = Some(idx);
+                } else if cell_str.contains("actual") && cell_str.contains("cost") {
+                    actual_cost_col = Some(idx);
+                } else if cell_str.contains("cost") || cell_str.contains("budget") {
+                    cost_col = Some(idx);
                 }
             }
 
-            // Read data rows
-            for row in rows {
+            // Text dates are read the same way throughout the sheet
+            let date_cols = [start_col, end_col, actual_start_col, actual_finish_col];
+            let locale = sheet_locale(&range, header, &date_cols, options.date_locale, &mut diagnostics);
+
+            // Read data rows. Every value that has to be defaulted is
+            // recorded so it can be reviewed before merging.
+            for (row_idx, row) in rows.enumerate() {
+                if row.iter().all(|c| c.to_string().is_empty()) {
+                    continue; // Skip empty rows
+                }
+                // Header is row 1 in Excel
+                let mut cells = RowReader {
+                    row,
+                    header,
+                    record: format!("row {}", row_idx + 2),
+                    diagnostics: &mut diagnostics,
+                };
                 let get_cell = |col: Option<usize>| {
                     col.and_then(|c| row.get(c)).map(|c| c.to_string())
                 };
 
-                let name = get_cell(name_col)
-                    .filter(|s| !s.is_empty())
-                    .unwrap_or_else(|| String::from("Unnamed Task"));
-
-                if name == "Unnamed Task" && row.iter().all(|c| c.to_string().is_empty()) {
-                    continue; // Skip empty rows
-                }
+                let name = match get_cell(name_col).filter(|s| !s.trim().is_empty()) {
+                    Some(name) => name,
+                    None => {
+                        let column = name_col.and_then(|c| header.get(c)).map_or("Name".to_string(), |c| c.to_string());
+                        cells.note(&column, "", "Unnamed Task");
+                        String::from("Unnamed Task")
+                    }
+                };
 
                 let description = get_cell(desc_col).unwrap_or_default();
                 let assignee = get_cell(assignee_col).unwrap_or_default();
                 let wbs = get_cell(wbs_col).unwrap_or_default();
                 
                 // Parse dates
-                let today = chrono::Local::now().date_naive();
-                let start_date = get_cell(start_col)
-                    .and_then(|s| parse_date(&s))
-                    .unwrap_or(today);
-                let end_date = get_cell(end_col)
-                    .and_then(|s| parse_date(&s))
-                    .unwrap_or(start_date);
+                let today = at_start(chrono::Local::now().date_naive());
+                let start = cells.read_moment(start_col, default_start_time(), locale, today);
+                let finish = cells.read_moment(end_col, default_finish_time(), locale, start.date().and_time(default_finish_time()));
 
                 // Parse status
-                let status = get_cell(status_col)
-                    .and_then(|s| parse_status(&s))
-                    .unwrap_or(TaskStatus::NotStarted);
+                let status = cells.read(status_col, false, parse_status, TaskStatus::NotStarted);
 
                 // Parse priority
-                let priority = get_cell(priority_col)
-                    .and_then(|s| s.parse::<u32>().ok())
-                    .unwrap_or(0);
+                let parse_priority = if priority_exact { crate::priority::parse_exact } else { crate::priority::parse };
+                let priority = cells.read(priority_col, false, parse_priority, crate::priority::DEFAULT);
 
                 // Parse duration
-                let duration_days = get_cell(duration_col)
-                    .and_then(|s| parse_duration(&s))
-                    .unwrap_or(0);
+                let duration = cells.read(duration_col, false, Duration::parse, Duration::default());
 
                 // Parse percent complete
-                let percent_complete = get_cell(percent_col)
-                    .and_then(|s| {
-                        s.replace('%', "").trim().parse::<u32>().ok()
-                    })
-                    .unwrap_or(0);
+                let percent_complete = cells.read(percent_col, false, |s| s.replace('%', "").trim().parse::<u32>().ok(), 0);
+
+                let cost = cells.read(cost_col, false, parse_money, 0.0);
+                let actual_cost = cells.read(actual_cost_col, false, parse_money, 0.0);
+                let actual_start = cells.read_actual(actual_start_col, default_start_time(), locale);
+                let actual_finish = cells.read_actual(actual_finish_col, default_finish_time(), locale);
+                let actual_duration = cells.read(actual_duration_col, false, Duration::parse, Duration::default());
+                let remaining_duration = cells.read(remaining_col, false, Duration::parse, Duration::default());
+
+                let milestone = get_cell(milestone_col)
+                    .map(|s| matches!(s.trim().to_lowercase().as_str(), "yes" | "y" | "true" | "1" | "x"))
+                    .unwrap_or(false);
 
                 tasks.push(Task {
+                    uid: (row_idx + 1) as i32,
                     name,
                     description,
-                    start_date,
-                    end_date,
+                    start,
+                    finish,
                     status,
                     priority,
                     assignee,
-                    duration_days,
+                    duration,
                     percent_complete,
+                    milestone,
                     source_file: 0, // Will be set by caller
                     wbs,
                     predecessors: Vec::new(),
+                    provenance: Default::default(),
+                    cost,
+                    actual_cost,
+                    baseline: None,
+                    actual_start,
+                    actual_finish,
+                    actual_duration,
+                    remaining_duration,
                 });
             }
         }
     }
 
-    Ok(tasks)
+    Ok((tasks, diagnostics))
 }
 
-pub fn import_mpp(path: &Path) -> Result<Vec<Task>, Box<dyn std::error::Error>> {
-    // Note: Direct MPP file reading in Rust is limited.
-    // This is a placeholder that attempts to handle MPP files.
-    // For production use, you may need to:
-    // 1. Convert MPP to XLSX/XML first using external tools
-    // 2. Use COM interop on Windows (not cross-platform)
-    // 3. Use a Python bridge with python-pptx or similar
-    
-    // For now, we'll try to read it as if it might be XML-based
-    // or return an error suggesting conversion
-    
-    let content = std::fs::read(path)?;
-    
-    // Try to detect if it's actually an XML file (some MPP exports are XML)
-    if content.starts_with(b"<?xml") || content.starts_with(b"<") {
-        // Try parsing as XML
-        return import_mpp_xml(&content);
+/// Reads the cells of one worksheet row, recording every value that has to
+/// be replaced by a default.
+struct RowReader<'a> {
+    row: &'a [calamine::Data],
+    header: &'a [calamine::Data],
+    record: String,
+    diagnostics: &'a mut Vec<Diagnostic>,
+}
+
+impl RowReader<'_> {
+    /// Parses column `col`. Values that don't parse, and blanks when
+    /// `required`, become `default`. Missing columns silently give `default`.
+    fn read<T: std::fmt::Debug>(
+        &mut self,
+        col: Option<usize>,
+        required: bool,
+        parse: impl Fn(&str) -> Option<T>,
+        default: T,
+    ) -> T {
+        self.read_cell(col, required, |cell| parse(cell.to_string().trim()), default, |value| format!("{:?}", value))
+    }
+
+    /// Required date-time column, with substitutions noted the way the
+    /// task table shows them.
+    fn read_moment(&mut self, col: Option<usize>, default_time: NaiveTime, locale: DateLocale, default: NaiveDateTime) -> NaiveDateTime {
+        self.read_cell(col, true, |cell| cell_moment(cell, default_time, locale), default, |value| format_moment(*value, default_time))
+    }
+
+    /// Optional date-time column such as an actual start.
+    fn read_actual(&mut self, col: Option<usize>, default_time: NaiveTime, locale: DateLocale) -> Option<NaiveDateTime> {
+        self.read_cell(col, false, |cell| cell_moment(cell, default_time, locale).map(Some), None, |_| "blank".to_string())
+    }
+
+    fn read_cell<T>(
+        &mut self,
+        col: Option<usize>,
+        required: bool,
+        parse: impl Fn(&calamine::Data) -> Option<T>,
+        default: T,
+        show: impl Fn(&T) -> String,
+    ) -> T {
+        let Some(col) = col else { return default };
+        let cell = self.row.get(col).unwrap_or(&calamine::Data::Empty);
+        let raw = cell.to_string();
+        if raw.trim().is_empty() && !required {
+            return default;
+        }
+        match parse(cell) {
+            Some(value) => value,
+            None => {
+                let column = self.header.get(col).map(|c| c.to_string()).unwrap_or_default();
+                self.note(&column, &raw, show(&default));
+                default
+            }
+        }
+    }
+
+    fn note(&mut self, column: &str, raw: &str, substituted: impl ToString) {
+        self.diagnostics.push(Diagnostic::new(self.record.clone(), column, raw, substituted));
     }
-    
-    // If it's a binary MPP file, we can't easily read it
-    Err(format!(
-        "Binary MPP files are not directly supported. Please export your MPP file to XLSX format first.\n\
-         File: {}",
-        path.display()
-    ).into())
 }
 
 use quick_xml::de::from_str;
-use crate::mspdi::Project;
+use quick_xml::events::Event;
+use crate::mspdi::{MspdiTask, Project};
 
-fn import_mpp_xml(content: &[u8]) -> Result<Vec<Task>, Box<dyn std::error::Error>> {
+fn import_mpp_xml(content: &[u8], options: &ImportOptions) -> Result<(Vec<Task>, Vec<Diagnostic>), FileError> {
     let xml_str = String::from_utf8_lossy(content);
     
     // Parse XML into MSPDI struct
-    let project: Project = from_str(&xml_str)?;
+    let project: Project = from_str(&xml_str).map_err(|e| FileError::Xml {
+        element: locate_xml_error(&xml_str),
+        message: e.to_string(),
+    })?;
     
     let mut tasks = Vec::new();
-    
+    let mut diagnostics = Vec::new();
+
+    // The project's working day, for dates written without a time
+    let project_time = |text: &str, default: NaiveTime| NaiveTime::parse_from_str(text.trim(), "%H:%M:%S").unwrap_or(default);
+    let start_time = project_time(&project.default_start_time, default_start_time());
+    let finish_time = project_time(&project.default_finish_time, default_finish_time());
+    let start_of = |text: &str| parse_mspdi_moment(text, start_time);
+    let finish_of = |text: &str| parse_mspdi_moment(text, finish_time);
+
     for mspdi_task in project.tasks.task {
-        // Skip summary tasks or empty tasks if needed, or handle them
-        // For now, we import everything that has a name
+        let record = format!("UID {}", mspdi_task.uid);
+
+        // Nameless tasks are placeholders (e.g. blank rows in Project); drop them
         if mspdi_task.name.is_empty() {
+            diagnostics.push(Diagnostic::new(record, "Name", "", SKIPPED));
             continue;
         }
 
         let mut task = Task::new(mspdi_task.name);
+        task.uid = mspdi_task.uid;
         
-        // Parse dates
-        if let Some(date) = parse_mspdi_date(&mspdi_task.start) {
-            task.start_date = date;
+        // Parse dates; Task::new already holds today's date as the fallback
+        match start_of(&mspdi_task.start) {
+            Some(moment) => task.start = moment,
+            None => diagnostics.push(Diagnostic::new(record.clone(), "Start", &mspdi_task.start, format_moment(task.start, start_time))),
         }
-        if let Some(date) = parse_mspdi_date(&mspdi_task.finish) {
-            task.end_date = date;
+        match finish_of(&mspdi_task.finish) {
+            Some(moment) => task.finish = moment,
+            None => {
+                task.finish = task.start.date().and_time(finish_time);
+                diagnostics.push(Diagnostic::new(record.clone(), "Finish", &mspdi_task.finish, format_moment(task.finish, finish_time)));
+            }
         }
         
-        // Parse duration (MSPDI format is usually PT8H0M0S or similar ISO8601 duration)
-        // But MS Project XML often uses "PT8H0M0S"
-        task.duration_days = parse_mspdi_duration(&mspdi_task.duration);
+        // ISO 8601 like "PT36H0M0S"; DurationFormat gives the unit it's shown in
+        let format = mspdi_task.duration_format;
+        let parse_duration = |text: &str| Duration::from_mspdi(text, format).unwrap_or_default();
+        task.duration = parse_duration(&mspdi_task.duration);
+        task.duration.estimated |= mspdi_task.estimated == 1;
         
         task.percent_complete = mspdi_task.percent_complete as u32;
-        task.priority = mspdi_task.priority as u32;
+        task.priority = crate::priority::from_mspdi(mspdi_task.priority);
         task.description = mspdi_task.notes;
-        task.wbs = mspdi_task.wbs;
+        task.actual_start = start_of(&mspdi_task.actual_start);
+        task.actual_finish = finish_of(&mspdi_task.actual_finish);
+        // Without durations from the file, progress splits the planned one
+        task.actual_duration = if mspdi_task.actual_duration.is_empty() {
+            task.duration.with_minutes((task.duration.minutes * task.percent_complete.min(100)).div_ceil(100))
+        } else {
+            parse_duration(&mspdi_task.actual_duration)
+        };
+        task.remaining_duration = if mspdi_task.remaining_duration.is_empty() {
+            task.duration.with_minutes(task.duration.minutes.saturating_sub(task.actual_duration.minutes))
+        } else {
+            parse_duration(&mspdi_task.remaining_duration)
+        };
+        task.cost = mspdi_task.cost / 100.0;
+        task.actual_cost = mspdi_task.actual_cost / 100.0;
+        task.baseline = mspdi_task.baseline.iter()
+            .find(|b| b.number == 0)
+            .and_then(|b| Some(crate::task::Baseline {
+                start: start_of(&b.start)?,
+                finish: finish_of(&b.finish)?,
+                duration: parse_duration(&b.duration),
+                cost: b.cost / 100.0,
+            }));
+        // MS Project always writes the outline number; WBS codes can be custom
+        task.wbs = if mspdi_task.wbs.is_empty() { mspdi_task.outline_number } else { mspdi_task.wbs };
+        task.milestone = mspdi_task.milestone == 1;
         
         // Import predecessors
         for pred in mspdi_task.predecessor_link {
@@ -190,82 +396,143 @@ fn import_mpp_xml(content: &[u8]) -> Result<Vec<Task>, Box<dyn std::error::Error
                 predecessor_uid: pred.predecessor_uid,
                 link_type: pred.link_type,
                 link_lag: pred.link_lag,
+                lag_format: if pred.lag_format == 0 { crate::dependency::LAG_FORMAT_DAYS } else { pred.lag_format },
             });
         }
         
-        // Status logic based on percent complete
-        if task.percent_complete == 100 {
-            task.status = TaskStatus::Completed;
-        } else if task.percent_complete > 0 {
-            task.status = TaskStatus::InProgress;
+        // Inactive tasks are cancelled; the status field can say On Hold
+        let status_field = options.status_field.unwrap_or(crate::mspdi::TEXT1_FIELD_ID);
+        let stated = if mspdi_task.active == 0 {
+            Some(TaskStatus::Cancelled)
         } else {
-            task.status = TaskStatus::NotStarted;
-        }
+            mspdi_task.extended_attribute.iter()
+                .filter(|a| a.field_id.trim().parse() == Ok(status_field))
+                .find_map(|a| crate::status::parse_explicit(&a.value))
+        };
+        task.status = stated.unwrap_or_else(|| crate::status::from_percent(task.percent_complete));
 
         tasks.push(task);
     }
     
-    Ok(tasks)
+    Ok((tasks, diagnostics))
 }
 
-fn parse_mspdi_date(s: &str) -> Option<chrono::NaiveDate> {
-    // MSPDI dates are usually ISO8601: YYYY-MM-DDTHH:MM:SS
-    if let Ok(dt) = chrono::NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S") {
-        return Some(dt.date());
-    }
-    // Fallback to date only
-    if let Ok(d) = chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d") {
-        return Some(d);
+/// Narrows a failed MSPDI parse down to where it happened: the line of a
+/// syntax error, or the first `<Task>` that doesn't deserialize on its own.
+fn locate_xml_error(xml: &str) -> Option<String> {
+    let mut reader = quick_xml::Reader::from_str(xml);
+    let mut index = 0;
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(e)) if e.local_name().as_ref() == b"Task" => {
+                index += 1;
+                let span = match reader.read_to_end(e.name()) {
+                    Ok(span) => span,
+                    Err(_) => return Some(format!("<Task> #{}", index)),
+                };
+                let inner = &xml[span];
+                if from_str::<MspdiTask>(&format!("<Task>{}</Task>", inner)).is_err() {
+                    let uid = inner.split_once("<UID>")
+                        .and_then(|(_, rest)| rest.split_once("</UID>"))
+                        .map(|(uid, _)| uid.trim());
+                    return Some(match uid {
+                        Some(uid) => format!("<Task> #{} (UID {})", index, uid),
+                        None => format!("<Task> #{}", index),
+                    });
+                }
+            }
+            Ok(Event::Eof) => return None,
+            Err(_) => {
+                let position = reader.buffer_position().min(xml.len());
+                let line = xml.as_bytes()[..position].iter().filter(|&&b| b == b'\n').count() + 1;
+                return Some(format!("line {}", line));
+            }
+            _ => {}
+        }
     }
-    None
 }
 
-fn parse_mspdi_duration(s: &str) -> u32 {
-    // Format is usually PT<hours>H<minutes>M<seconds>S
-    // We want days. Standard day is 8 hours.
-    // This is a simplified parser.
-    let s = s.trim_start_matches("PT");
-    let mut hours = 0;
-    
-    if let Some(h_idx) = s.find('H') {
-        if let Ok(h) = s[..h_idx].parse::<u32>() {
-            hours = h;
-        }
+/// MSPDI date-times are ISO 8601, YYYY-MM-DDTHH:MM:SS. A date on its own
+/// gets `default_time`.
+pub(crate) fn parse_mspdi_moment(s: &str, default_time: NaiveTime) -> Option<NaiveDateTime> {
+    let s = s.trim();
+    if let Ok(moment) = NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S%.f") {
+        return Some(moment);
     }
-    
-    // Rough conversion: 8 hours = 1 day
-    if hours > 0 {
-        return (hours + 7) / 8; // Ceiling division
+    if let Ok(moment) = NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M") {
+        return Some(moment);
     }
-    
-    0
+    NaiveDate::parse_from_str(s, "%Y-%m-%d").ok().map(|d| d.and_time(default_time))
 }
 
-// Keep existing helper functions if needed, or remove if unused
-fn parse_date(s: &str) -> Option<chrono::NaiveDate> {
-    // ... (keep existing implementation if needed for XLSX)
-    // Try various date formats
-    let formats = [
-        "%Y-%m-%d",
-        "%m/%d/%Y",
-        "%d/%m/%Y",
-        "%Y/%m/%d",
-        "%d-%m-%Y",
-    ];
-    
-    for format in &formats {
-        if let Ok(date) = chrono::NaiveDate::parse_from_str(s.trim(), format) {
-            return Some(date);
+/// Amounts like "1,250.00" or "$300"; currency symbols are ignored.
+fn parse_money(s: &str) -> Option<f64> {
+    let s: String = s.chars().filter(|c| c.is_ascii_digit() || matches!(c, '.' | '-')).collect();
+    s.parse::<f64>().ok().filter(|v| v.is_finite())
+}
+
+/// A worksheet cell holding a date. Native Excel dates are used as they
+/// are and text is read in `locale`. Dates without a time of day get
+/// `default_time`.
+fn cell_moment(cell: &calamine::Data, default_time: NaiveTime, locale: DateLocale) -> Option<NaiveDateTime> {
+    let moment = match cell {
+        calamine::Data::DateTime(value) if value.is_datetime() => value.as_datetime()?,
+        calamine::Data::Float(serial) => serial_moment(*serial)?,
+        calamine::Data::Int(serial) => serial_moment(*serial as f64)?,
+        calamine::Data::String(text) | calamine::Data::DateTimeIso(text) => return parse_moment(text, default_time, locale),
+        _ => return None,
+    };
+    Some(if moment.time() == NaiveTime::MIN { moment.date().and_time(default_time) } else { moment })
+}
+
+/// Excel serial date-times count days since 1900 and carry the time as
+/// the fraction of a day.
+fn serial_moment(serial: f64) -> Option<NaiveDateTime> {
+    calamine::ExcelDateTime::new(serial, calamine::ExcelDateTimeType::DateTime, false).as_datetime()
+}
+
+/// A date typed as text, optionally with a time ("2024-03-04 12:30",
+/// "Mon 3/4/24 1:30 PM"). Dates on their own get `default_time`. Numbers
+/// typed as text, such as "2024", are not dates; only numeric cells are
+/// read as Excel serials.
+fn parse_moment(s: &str, default_time: NaiveTime, locale: DateLocale) -> Option<NaiveDateTime> {
+    let s = s.trim();
+    for format in ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%dT%H:%M"] {
+        if let Ok(moment) = NaiveDateTime::parse_from_str(s, format) {
+            return Some(moment);
         }
     }
-    
-    // Try Excel serial date (days since 1900-01-01)
-    if let Ok(days) = s.parse::<f64>() {
-        let base = chrono::NaiveDate::from_ymd_opt(1899, 12, 30)?;
-        return base.checked_add_signed(chrono::Duration::days(days as i64));
+    let (date, time) = dates::parse(s, locale)?;
+    Some(date.and_time(time.unwrap_or(default_time)))
+}
+
+/// How the text dates in the columns `cols` should be read: one value that
+/// only makes sense one way settles all of them. If the sheet still reads
+/// either way, each column is noted at its first ambiguous date so the
+/// locale can be set on the file.
+fn sheet_locale(
+    range: &calamine::Range<calamine::Data>,
+    header: &[calamine::Data],
+    cols: &[Option<usize>],
+    locale: DateLocale,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> DateLocale {
+    let texts = |col: usize| range.rows().skip(1).map(move |row| match row.get(col) {
+        Some(calamine::Data::String(text)) => text.as_str(),
+        _ => "",
+    });
+    let cols: Vec<usize> = cols.iter().flatten().copied().collect();
+    let inferred = locale.infer(cols.iter().flat_map(|&col| texts(col)));
+    if inferred == DateLocale::Auto {
+        for &col in &cols {
+            if let Some((row_idx, text)) = texts(col).enumerate().find(|(_, text)| dates::is_ambiguous(text)) {
+                let column = header.get(col).map(|c| c.to_string()).unwrap_or_default();
+                let read_as = dates::parse(text, inferred).map_or(String::new(), |(date, _)| date.to_string());
+                diagnostics.push(Diagnostic::new(format!("row {}", row_idx + 2), &column, text, format!("{} (ambiguous date)", read_as)));
+            }
+        }
     }
-    
-    None
+    inferred
 }
 
 fn parse_status(s: &str) -> Option<TaskStatus> {
@@ -285,17 +552,6 @@ fn parse_status(s: &str) -> Option<TaskStatus> {
     }
 }
 
-fn parse_duration(s: &str) -> Option<u32> {
-    // Try to parse duration strings like "5d", "5 days", "5", etc.
-    let s = s.trim().to_lowercase();
-    if let Some(num_str) = s.split_whitespace().next() {
-        if let Ok(num) = num_str.parse::<u32>() {
-            return Some(num);
-        }
-    }
-    s.parse::<u32>().ok()
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -324,14 +580,16 @@ mod tests {
         </Project>
         "#;
 
-        let tasks = import_mpp_xml(xml.as_bytes()).expect("Failed to parse XML");
+        let (tasks, diagnostics) = import_mpp_xml(xml.as_bytes(), &ImportOptions::default()).expect("Failed to parse XML");
+        assert!(diagnostics.is_empty());
         
         assert_eq!(tasks.len(), 1);
         let task = &tasks[0];
         assert_eq!(task.name, "Task 1");
-        assert_eq!(task.start_date, chrono::NaiveDate::from_ymd_opt(2023, 1, 1).unwrap());
-        assert_eq!(task.end_date, chrono::NaiveDate::from_ymd_opt(2023, 1, 5).unwrap());
-        assert_eq!(task.duration_days, 4); // 32 hours / 8 hours per day
+        assert_eq!(task.start, chrono::NaiveDate::from_ymd_opt(2023, 1, 1).unwrap().and_hms_opt(8, 0, 0).unwrap());
+        assert_eq!(task.finish, chrono::NaiveDate::from_ymd_opt(2023, 1, 5).unwrap().and_hms_opt(17, 0, 0).unwrap());
+        assert_eq!(task.duration.days(), 4.0); // 32 hours / 8 hours per day
+        assert_eq!(task.duration.minutes, 32 * 60);
         assert_eq!(task.percent_complete, 50);
         assert_eq!(task.status, TaskStatus::InProgress);
         assert_eq!(task.description, "Test Note");
@@ -365,12 +623,133 @@ mod tests {
         </Project>
         "#;
 
-        let tasks = import_mpp_xml(xml.as_bytes()).unwrap();
+        let (tasks, _) = import_mpp_xml(xml.as_bytes(), &ImportOptions::default()).unwrap();
         assert_eq!(tasks.len(), 2);
         assert_eq!(tasks[1].predecessors.len(), 1);
         assert_eq!(tasks[1].predecessors[0].predecessor_uid, 1);
         assert_eq!(tasks[1].predecessors[0].link_type, 1);
     }
+
+    #[test]
+    fn test_import_xlsx_records_defaults() {
+        let mut workbook = rust_xlsxwriter::Workbook::new();
+        let sheet = workbook.add_worksheet();
+        for (col, header) in ["Task Name", "Start Date", "Priority", "% Complete"].iter().enumerate() {
+            sheet.write_string(0, col as u16, *header).unwrap();
+        }
+        sheet.write_string(1, 0, "Design").unwrap();
+        sheet.write_string(1, 1, "2024-01-08").unwrap();
+        sheet.write_string(1, 3, "lots").unwrap();
+        sheet.write_string(2, 1, "31/31/2024").unwrap();
+        sheet.write_string(2, 2, "5").unwrap();
+        let content = workbook.save_to_buffer().unwrap();
+
+        let (tasks, diagnostics) = import_xlsx(&content, &ImportOptions::default()).unwrap();
+        assert_eq!(tasks.len(), 2);
+        assert_eq!(tasks[1].name, "Unnamed Task");
+        let today = chrono::Local::now().date_naive();
+        assert_eq!(diagnostics, vec![
+            Diagnostic::new("row 2", "% Complete", "lots", "0"),
+            Diagnostic::new("row 3", "Task Name", "", "Unnamed Task"),
+            Diagnostic::new("row 3", "Start Date", "31/31/2024", today),
+        ]);
+    }
+
+    #[test]
+    fn test_import_xlsx_dates_by_sheet() {
+        let mut workbook = rust_xlsxwriter::Workbook::new();
+        let sheet = workbook.add_worksheet();
+        for (col, header) in ["Task Name", "Start Date", "Finish"].iter().enumerate() {
+            sheet.write_string(0, col as u16, *header).unwrap();
+        }
+        let date_format = rust_xlsxwriter::Format::new().set_num_format("yyyy-mm-dd hh:mm");
+        let noon = rust_xlsxwriter::ExcelDateTime::parse_from_str("2024-04-05T12:00:00").unwrap();
+        sheet.write_string(1, 0, "Design").unwrap();
+        sheet.write_string(1, 1, "03/04/2024").unwrap();
+        sheet.write_datetime_with_format(1, 2, &noon, &date_format).unwrap();
+        sheet.write_string(2, 0, "Build").unwrap();
+        sheet.write_string(2, 1, "25/03/2024").unwrap();
+        sheet.write_string(2, 2, "Mon 4/8/24").unwrap();
+        // A number typed as text is not a date; a numeric cell is a serial
+        sheet.write_string(3, 0, "Pour").unwrap();
+        sheet.write_string(3, 1, "2024").unwrap();
+        sheet.write_number(3, 2, 45390.5).unwrap();
+        let content = workbook.save_to_buffer().unwrap();
+
+        let at = |m, d, h| NaiveDate::from_ymd_opt(2024, m, d).unwrap().and_hms_opt(h, 0, 0).unwrap();
+        let (tasks, diagnostics) = import_xlsx(&content, &ImportOptions::default()).unwrap();
+        // 25/03 in the start column settles the whole sheet as day first
+        assert_eq!((tasks[0].start, tasks[0].finish), (at(4, 3, 8), at(4, 5, 12)));
+        assert_eq!((tasks[1].start, tasks[1].finish), (at(3, 25, 8), at(8, 4, 17)));
+        let today = chrono::Local::now().date_naive();
+        assert_eq!((tasks[2].start, tasks[2].finish), (at_start(today), at(4, 8, 12)));
+        assert_eq!(diagnostics, vec![Diagnostic::new("row 4", "Start Date", "2024", today)]);
+
+        // A chosen locale wins over the values
+        let us = ImportOptions { date_locale: DateLocale::MonthFirst, ..ImportOptions::default() };
+        let (tasks, _) = import_xlsx(&content, &us).unwrap();
+        assert_eq!(tasks[1].finish, at(4, 8, 17));
+
+        // Without a settling value, every date column is flagged
+        let mut workbook = rust_xlsxwriter::Workbook::new();
+        let sheet = workbook.add_worksheet();
+        for (col, text) in ["Task Name", "Start Date", "Finish", "Design", "03/04/2024", "Mon 4/8/24"].iter().enumerate() {
+            sheet.write_string(col as u32 / 3, col as u16 % 3, *text).unwrap();
+        }
+        let (tasks, diagnostics) = import_xlsx(&workbook.save_to_buffer().unwrap(), &ImportOptions::default()).unwrap();
+        assert_eq!((tasks[0].start, tasks[0].finish), (at(3, 4, 8), at(4, 8, 17)));
+        assert_eq!(diagnostics, vec![
+            Diagnostic::new("row 2", "Start Date", "03/04/2024", "2024-03-04 (ambiguous date)"),
+            Diagnostic::new("row 2", "Finish", "Mon 4/8/24", "2024-04-08 (ambiguous date)"),
+        ]);
+    }
+
+    #[test]
+    fn test_import_mpp_xml_records_skipped_tasks() {
+        let xml = r#"<Project><Tasks>
+            <Task><UID>1</UID><ID>1</ID><Name>Kept</Name><Start>2024-01-01T08:00:00</Start><Finish>2024-01-02T17:00:00</Finish></Task>
+            <Task><UID>2</UID><ID>2</ID></Task>
+        </Tasks></Project>"#;
+
+        let (tasks, diagnostics) = import_mpp_xml(xml.as_bytes(), &ImportOptions::default()).unwrap();
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(diagnostics, vec![Diagnostic::new("UID 2", "Name", "", SKIPPED)]);
+    }
+
+    #[test]
+    fn test_import_mpp_xml_status_field() {
+        let xml = r#"<Project><Tasks>
+            <Task><UID>1</UID><ID>1</ID><Name>Paused</Name><Start>2024-01-01T08:00:00</Start><Finish>2024-01-02T17:00:00</Finish>
+                <ExtendedAttribute><FieldID>188743731</FieldID><Value>On Hold</Value></ExtendedAttribute></Task>
+            <Task><UID>2</UID><ID>2</ID><Name>Noted</Name><Start>2024-01-01T08:00:00</Start><Finish>2024-01-02T17:00:00</Finish>
+                <ExtendedAttribute><FieldID>188743734</FieldID><Value>Cancelled</Value></ExtendedAttribute></Task>
+        </Tasks></Project>"#;
+
+        let (tasks, _) = import_mpp_xml(xml.as_bytes(), &ImportOptions::default()).unwrap();
+        assert_eq!(tasks[0].status, TaskStatus::OnHold);
+        assert_eq!(tasks[1].status, TaskStatus::NotStarted);
+
+        let text2 = ImportOptions { status_field: Some(188743734), ..ImportOptions::default() };
+        let (tasks, _) = import_mpp_xml(xml.as_bytes(), &text2).unwrap();
+        assert_eq!(tasks[0].status, TaskStatus::NotStarted);
+        assert_eq!(tasks[1].status, TaskStatus::Cancelled);
+    }
+
+    #[test]
+    fn test_import_mpp_xml_error_location() {
+        let xml = r#"<Project>
+            <Tasks>
+                <Task><UID>1</UID><ID>1</ID><Name>Fine</Name></Task>
+                <Task><UID>2</UID><ID>2</ID><Name>Broken</Name><PercentComplete>half</PercentComplete></Task>
+            </Tasks>
+        </Project>"#;
+
+        match import_mpp_xml(xml.as_bytes(), &ImportOptions::default()) {
+            Err(FileError::Xml { element, .. }) => assert_eq!(element.as_deref(), Some("<Task> #2 (UID 2)")),
+            other => panic!("expected an XML error, got {:?}", other.map(|(t, _)| t.len())),
+        }
+    }
+
 }
 
 
@@ -0,0 +1,242 @@
+use chrono::NaiveDate;
+use quick_xml::de::from_str;
+use serde::Deserialize;
+use std::collections::HashMap;
+use crate::duration::Duration;
+use crate::registry::{Capabilities, Confidence, ImportOptions, Importer, Probe};
+use crate::task::{add_working_days, at_finish, at_start, Predecessor, Task};
+
+/// GanttProject `.gan` files (lowercase `<project>` root without Planner's version attribute).
+pub struct GanImporter;
+
+impl Importer for GanImporter {
+    fn name(&self) -> &'static str {
+        "GanttProject"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["gan"]
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::new(true, true, true)
+    }
+
+    fn detect(&self, probe: &Probe) -> Confidence {
+        // Planner shares the lowercase <project> root but tags it with its version
+        match &probe.xml_root {
+            Some(root) if root.name == "project" && !root.has_attribute("mrproject-version") => Confidence::Content,
+            _ if probe.has_extension(self.extensions()) => Confidence::Extension,
+            _ => Confidence::None,
+        }
+    }
+
+    fn import(&self, content: &[u8], _options: &ImportOptions) -> Result<Vec<Task>, Box<dyn std::error::Error>> {
+        import_gan_xml(content)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct GanProject {
+    #[serde(rename = "tasks", default)]
+    tasks: Option<GanTasks>,
+    #[serde(rename = "resources", default)]
+    resources: Option<GanResources>,
+    #[serde(rename = "allocations", default)]
+    allocations: Option<GanAllocations>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GanTasks {
+    #[serde(rename = "task", default)]
+    task: Vec<GanTask>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GanTask {
+    #[serde(rename = "@id")]
+    id: i32,
+    #[serde(rename = "@name", default)]
+    name: String,
+    #[serde(rename = "@start", default)]
+    start: String,
+    // Working days
+    #[serde(rename = "@duration", default)]
+    duration: u32,
+    #[serde(rename = "@complete", default)]
+    complete: u32,
+    #[serde(rename = "@meeting", default)]
+    meeting: bool,
+    #[serde(rename = "notes", default)]
+    notes: Option<String>,
+    #[serde(rename = "depend", default)]
+    depend: Vec<GanDepend>,
+    #[serde(rename = "task", default)]
+    task: Vec<GanTask>,
+}
+
+/// A `<depend>` inside task A pointing at task B means A is B's predecessor.
+#[derive(Debug, Deserialize)]
+struct GanDepend {
+    #[serde(rename = "@id")]
+    id: i32,
+    #[serde(rename = "@type", default)]
+    link_type: i32,
+    // Days
+    #[serde(rename = "@difference", default)]
+    difference: i32,
+}
+
+#[derive(Debug, Deserialize)]
+struct GanResources {
+    #[serde(rename = "resource", default)]
+    resource: Vec<GanResource>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GanResource {
+    #[serde(rename = "@id")]
+    id: i32,
+    #[serde(rename = "@name", default)]
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GanAllocations {
+    #[serde(rename = "allocation", default)]
+    allocation: Vec<GanAllocation>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GanAllocation {
+    #[serde(rename = "@task-id")]
+    task_id: i32,
+    #[serde(rename = "@resource-id")]
+    resource_id: i32,
+}
+
+fn import_gan_xml(content: &[u8]) -> Result<Vec<Task>, Box<dyn std::error::Error>> {
+    let xml_str = String::from_utf8_lossy(content);
+    let project: GanProject = from_str(&xml_str)?;
+
+    let resources: HashMap<i32, String> = project.resources
+        .map(|r| r.resource.into_iter().map(|r| (r.id, r.name)).collect())
+        .unwrap_or_default();
+    let mut assignees: HashMap<i32, Vec<String>> = HashMap::new();
+    for allocation in project.allocations.map(|a| a.allocation).unwrap_or_default() {
+        if let Some(name) = resources.get(&allocation.resource_id) {
+            assignees.entry(allocation.task_id).or_default().push(name.clone());
+        }
+    }
+
+    let mut tasks = Vec::new();
+    let mut links = Vec::new();
+    let roots = project.tasks.map(|t| t.task).unwrap_or_default();
+    for (i, gan_task) in roots.iter().enumerate() {
+        add_gan_task(gan_task, (i + 1).to_string(), &assignees, &mut tasks, &mut links);
+    }
+
+    for (successor, predecessor) in links {
+        if let Some(task) = tasks.iter_mut().find(|t| t.uid == successor) {
+            task.predecessors.push(predecessor);
+        }
+    }
+
+    Ok(tasks)
+}
+
+fn add_gan_task(
+    gan_task: &GanTask,
+    wbs: String,
+    assignees: &HashMap<i32, Vec<String>>,
+    tasks: &mut Vec<Task>,
+    links: &mut Vec<(i32, Predecessor)>,
+) {
+    let mut task = Task::new(gan_task.name.clone());
+    // GanttProject ids start at 0, which MSPDI reserves for the project summary
+    task.uid = gan_task.id + 1;
+    task.wbs = wbs.clone();
+    task.description = gan_task.notes.clone().unwrap_or_default().trim().to_string();
+    task.assignee = assignees.get(&gan_task.id).map(|a| a.join(", ")).unwrap_or_default();
+
+    if let Ok(start) = NaiveDate::parse_from_str(&gan_task.start, "%Y-%m-%d") {
+        task.start = at_start(start);
+        task.finish = task.start;
+    }
+    task.milestone = gan_task.meeting;
+    if !gan_task.meeting {
+        task.duration = Duration::from_days(gan_task.duration);
+        task.finish = at_finish(add_working_days(task.start.date(), gan_task.duration.saturating_sub(1)));
+    }
+
+    task.percent_complete = gan_task.complete.min(100);
+    task.status = crate::status::from_percent(task.percent_complete);
+
+    for depend in &gan_task.depend {
+        links.push((depend.id + 1, Predecessor {
+            predecessor_uid: gan_task.id + 1,
+            link_type: gan_link_type(depend.link_type),
+            // MSPDI lag is in tenths of a minute, 8h working day
+            link_lag: depend.difference * 8 * 60 * 10,
+            lag_format: crate::dependency::LAG_FORMAT_DAYS,
+        }));
+    }
+
+    tasks.push(task);
+
+    for (i, child) in gan_task.task.iter().enumerate() {
+        add_gan_task(child, format!("{}.{}", wbs, i + 1), assignees, tasks, links);
+    }
+}
+
+fn gan_link_type(t: i32) -> i32 {
+    // GanttProject: 1 = SS, 2 = FS, 3 = FF, 4 = SF
+    // MSPDI:        0 = FF, 1 = FS, 2 = SF, 3 = SS
+    match t {
+        1 => 3,
+        3 => 0,
+        4 => 2,
+        _ => 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::TaskStatus;
+
+    #[test]
+    fn test_import_gan_xml() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+        <project name="Demo" company="" webLink="" view-date="2024-01-01" view-index="0" version="3.2" locale="en_US">
+            <tasks empty-milestones="true">
+                <task id="0" name="Phase 1" meeting="false" start="2024-01-01" duration="10" complete="0" expand="true">
+                    <task id="1" name="Design" meeting="false" start="2024-01-05" duration="3" complete="40" expand="true">
+                        <notes><![CDATA[Initial design]]></notes>
+                        <depend id="2" type="2" difference="1" hardness="Strong"/>
+                    </task>
+                    <task id="2" name="Review" meeting="true" start="2024-01-11" duration="0" complete="0" expand="true"/>
+                </task>
+            </tasks>
+            <resources>
+                <resource id="0" name="Alice" function="Default:0" contacts="" phone=""/>
+            </resources>
+            <allocations>
+                <allocation task-id="1" resource-id="0" function="Default:0" responsible="true" load="100.0"/>
+            </allocations>
+        </project>
+        "#;
+
+        let tasks = import_gan_xml(xml.as_bytes()).unwrap();
+        assert_eq!(tasks.len(), 3);
+        assert_eq!(tasks[1].wbs, "1.1");
+        assert_eq!(tasks[1].description, "Initial design");
+        assert_eq!(tasks[1].assignee, "Alice");
+        assert_eq!(tasks[1].status, TaskStatus::InProgress);
+        // Fri 5th + 2 working days skips the weekend
+        assert_eq!(tasks[1].finish, at_finish(NaiveDate::from_ymd_opt(2024, 1, 9).unwrap()));
+        assert!(tasks[2].milestone);
+        assert_eq!(tasks[2].finish, tasks[2].start);
+        assert_eq!(tasks[2].predecessors, vec![Predecessor { predecessor_uid: 2, link_type: 1, link_lag: 4800, lag_format: 7 }]);
+    }
+}
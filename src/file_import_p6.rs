@@ -0,0 +1,381 @@
+use quick_xml::de::from_str;
+use serde::Deserialize;
+use std::collections::HashMap;
+use crate::file_import::parse_mspdi_moment;
+use crate::duration::{Duration, DurationUnit};
+use crate::registry::{Capabilities, Confidence, ImportOptions, Importer, Probe};
+use crate::task::{default_finish_time, default_start_time, Predecessor, Task, TaskStatus};
+
+/// Primavera P6 PMXML export (`<APIBusinessObjects>` root).
+pub struct P6Importer;
+
+impl Importer for P6Importer {
+    fn name(&self) -> &'static str {
+        "Primavera P6 (PMXML)"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["xml"]
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::new(true, true, false)
+    }
+
+    fn detect(&self, probe: &Probe) -> Confidence {
+        if probe.xml_root_is("APIBusinessObjects") {
+            Confidence::Content
+        } else {
+            // .xml alone says nothing: leave that to MSPDI
+            Confidence::None
+        }
+    }
+
+    fn import(&self, content: &[u8], _options: &ImportOptions) -> Result<Vec<Task>, Box<dyn std::error::Error>> {
+        import_p6_xml(content)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiBusinessObjects {
+    #[serde(rename = "Project", default)]
+    project: Vec<P6Project>,
+    // Older exports put relationships next to the project rather than inside it
+    #[serde(rename = "Relationship", default)]
+    relationship: Vec<P6Relationship>,
+}
+
+#[derive(Debug, Deserialize)]
+struct P6Project {
+    #[serde(rename = "WBS", default)]
+    wbs: Vec<P6Wbs>,
+    #[serde(rename = "Activity", default)]
+    activity: Vec<P6Activity>,
+    #[serde(rename = "Relationship", default)]
+    relationship: Vec<P6Relationship>,
+}
+
+#[derive(Debug, Deserialize)]
+struct P6Wbs {
+    #[serde(rename = "ObjectId")]
+    object_id: i32,
+    #[serde(rename = "ParentObjectId", default)]
+    parent_object_id: Option<i32>,
+    #[serde(rename = "Code", default)]
+    code: String,
+    #[serde(rename = "Name", default)]
+    name: String,
+    #[serde(rename = "SequenceNumber", default)]
+    sequence_number: i32,
+}
+
+#[derive(Debug, Deserialize)]
+struct P6Activity {
+    #[serde(rename = "ObjectId")]
+    object_id: i32,
+    #[serde(rename = "Id", default)]
+    id: String,
+    #[serde(rename = "Name", default)]
+    name: String,
+    #[serde(rename = "WBSObjectId", default)]
+    wbs_object_id: Option<i32>,
+    #[serde(rename = "StartDate", default)]
+    start_date: String,
+    #[serde(rename = "FinishDate", default)]
+    finish_date: String,
+    #[serde(rename = "PlannedStartDate", default)]
+    planned_start_date: String,
+    #[serde(rename = "PlannedFinishDate", default)]
+    planned_finish_date: String,
+    // Hours
+    #[serde(rename = "PlannedDuration", default)]
+    planned_duration: f64,
+    #[serde(rename = "PercentComplete", default)]
+    percent_complete: f64,
+    #[serde(rename = "Status", default)]
+    status: String,
+    #[serde(rename = "Type", default)]
+    activity_type: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct P6Relationship {
+    #[serde(rename = "PredecessorActivityObjectId")]
+    predecessor: i32,
+    #[serde(rename = "SuccessorActivityObjectId")]
+    successor: i32,
+    #[serde(rename = "Type", default)]
+    link_type: String,
+    // Hours
+    #[serde(rename = "Lag", default)]
+    lag: f64,
+}
+
+fn import_p6_xml(content: &[u8]) -> Result<Vec<Task>, Box<dyn std::error::Error>> {
+    let xml_str = String::from_utf8_lossy(content);
+    let doc: ApiBusinessObjects = from_str(&xml_str)?;
+
+    let mut relationships = doc.relationship;
+    let mut tasks = Vec::new();
+
+    // WBS nodes have no ObjectId overlap with activities in P6, but keep
+    // the generated summary UIDs clear of both, across every project
+    let max_id = doc.project.iter()
+        .flat_map(|p| p.activity.iter().map(|a| a.object_id).chain(p.wbs.iter().map(|w| w.object_id)))
+        .max()
+        .unwrap_or(0);
+    let mut next_summary_uid = max_id + 1;
+
+    // PMXML stores percentages as fractions, but some tools write 0-100.
+    // Decided for the whole file so 1% isn't read as 100%.
+    let fractions = doc.project.iter()
+        .flat_map(|p| &p.activity)
+        .all(|a| a.percent_complete <= 1.0);
+    let percent_scale = if fractions { 100.0 } else { 1.0 };
+
+    // Projects follow each other at the top level, so codes stay unique
+    let mut position = 0;
+    for project in doc.project {
+        relationships.extend(project.relationship);
+
+        let mut children: HashMap<Option<i32>, Vec<&P6Wbs>> = HashMap::new();
+        for node in &project.wbs {
+            children.entry(node.parent_object_id).or_default().push(node);
+        }
+        for nodes in children.values_mut() {
+            nodes.sort_by_key(|n| n.sequence_number);
+        }
+
+        let mut activities: HashMap<Option<i32>, Vec<&P6Activity>> = HashMap::new();
+        for activity in &project.activity {
+            // Activities pointing at an unknown WBS node go to the top level
+            let key = activity.wbs_object_id
+                .filter(|id| project.wbs.iter().any(|w| w.object_id == *id));
+            activities.entry(key).or_default().push(activity);
+        }
+
+        // Root WBS nodes are the ones whose parent isn't another WBS node
+        // (P6 points them at the project's ObjectId instead)
+        let wbs_ids: Vec<i32> = project.wbs.iter().map(|w| w.object_id).collect();
+        let mut roots: Vec<&P6Wbs> = project.wbs.iter()
+            .filter(|w| w.parent_object_id.is_none_or(|p| !wbs_ids.contains(&p)))
+            .collect();
+        roots.sort_by_key(|n| n.sequence_number);
+
+        let tree = WbsTree { children: &children, activities: &activities, percent_scale };
+        for activity in activities.get(&None).into_iter().flatten() {
+            position += 1;
+            tasks.push(activity_to_task(activity, position.to_string(), percent_scale));
+        }
+        for node in roots.into_iter().filter(|n| tree.has_activities(n)) {
+            position += 1;
+            tree.add_node(node, position.to_string(), &mut next_summary_uid, &mut tasks);
+        }
+    }
+
+    // P6 WBS nodes carry no dates of their own
+    crate::outline::apply_rollups(&mut tasks);
+
+    for rel in relationships {
+        if let Some(task) = tasks.iter_mut().find(|t| t.uid == rel.successor) {
+            task.predecessors.push(Predecessor {
+                predecessor_uid: rel.predecessor,
+                link_type: p6_link_type(&rel.link_type),
+                // MSPDI lag is in tenths of a minute
+                link_lag: (rel.lag * 600.0).round() as i32,
+                lag_format: crate::dependency::LAG_FORMAT_DAYS,
+            });
+        }
+    }
+
+    Ok(tasks)
+}
+
+/// One project's WBS nodes and activities, keyed by parent node.
+struct WbsTree<'a> {
+    children: &'a HashMap<Option<i32>, Vec<&'a P6Wbs>>,
+    activities: &'a HashMap<Option<i32>, Vec<&'a P6Activity>>,
+    percent_scale: f64,
+}
+
+impl WbsTree<'_> {
+    /// Nodes without an activity anywhere below them have no dates to roll
+    /// up, so they are left out rather than shown on today.
+    fn has_activities(&self, node: &P6Wbs) -> bool {
+        self.activities.contains_key(&Some(node.object_id))
+            || self.children.get(&Some(node.object_id)).into_iter().flatten().any(|c| self.has_activities(c))
+    }
+
+    fn add_node(&self, node: &P6Wbs, outline: String, next_summary_uid: &mut i32, tasks: &mut Vec<Task>) {
+        let name = if node.name.is_empty() { node.code.clone() } else { node.name.clone() };
+        let mut summary = Task::new(name);
+        summary.uid = *next_summary_uid;
+        *next_summary_uid += 1;
+        summary.wbs = outline.clone();
+        tasks.push(summary);
+
+        let mut position = 0;
+        for activity in self.activities.get(&Some(node.object_id)).into_iter().flatten() {
+            position += 1;
+            tasks.push(activity_to_task(activity, format!("{}.{}", outline, position), self.percent_scale));
+        }
+        for child in self.children.get(&Some(node.object_id)).into_iter().flatten() {
+            if self.has_activities(child) {
+                position += 1;
+                self.add_node(child, format!("{}.{}", outline, position), next_summary_uid, tasks);
+            }
+        }
+    }
+}
+
+/// `percent_scale` turns the file's percent complete into 0-100.
+fn activity_to_task(activity: &P6Activity, wbs: String, percent_scale: f64) -> Task {
+    let name = if activity.name.is_empty() { activity.id.clone() } else { activity.name.clone() };
+    let mut task = Task::new(name);
+    task.uid = activity.object_id;
+    task.wbs = wbs;
+
+    let start_of = |text: &str| parse_mspdi_moment(text, default_start_time());
+    let finish_of = |text: &str| parse_mspdi_moment(text, default_finish_time());
+    let start = start_of(&activity.start_date).or_else(|| start_of(&activity.planned_start_date));
+    let finish = finish_of(&activity.finish_date).or_else(|| finish_of(&activity.planned_finish_date));
+    if let Some(moment) = start {
+        task.start = moment;
+        task.finish = moment;
+    }
+    if let Some(moment) = finish {
+        task.finish = moment;
+    }
+
+    task.duration = Duration::from_minutes((activity.planned_duration * 60.0).round() as u32, DurationUnit::Days);
+
+    task.percent_complete = (activity.percent_complete * percent_scale).round().clamp(0.0, 100.0) as u32;
+
+    task.status = match activity.status.as_str() {
+        "Completed" => TaskStatus::Completed,
+        "In Progress" => TaskStatus::InProgress,
+        _ => TaskStatus::NotStarted,
+    };
+
+    if activity.activity_type.ends_with("Milestone") {
+        task.duration = Duration::default();
+        task.milestone = true;
+    }
+
+    task
+}
+
+fn p6_link_type(s: &str) -> i32 {
+    // MSPDI: 0 = FF, 1 = FS, 2 = SF, 3 = SS
+    match s {
+        "Finish to Finish" => 0,
+        "Start to Finish" => 2,
+        "Start to Start" => 3,
+        _ => 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_import_p6_xml() {
+        let xml = r#"
+        <APIBusinessObjects xmlns="http://xmlns.oracle.com/Primavera/P6/V8.3/API/BusinessObjects">
+            <Project>
+                <ObjectId>100</ObjectId>
+                <Name>Plant</Name>
+                <WBS>
+                    <ObjectId>10</ObjectId>
+                    <ParentObjectId>100</ParentObjectId>
+                    <Code>ENG</Code>
+                    <Name>Engineering</Name>
+                    <SequenceNumber>1</SequenceNumber>
+                </WBS>
+                <Activity>
+                    <ObjectId>1</ObjectId>
+                    <Id>A1000</Id>
+                    <Name>Design</Name>
+                    <WBSObjectId>10</WBSObjectId>
+                    <PlannedStartDate>2024-01-01T08:00:00</PlannedStartDate>
+                    <PlannedFinishDate>2024-01-05T17:00:00</PlannedFinishDate>
+                    <PlannedDuration>40</PlannedDuration>
+                    <PercentComplete>0.5</PercentComplete>
+                    <Status>In Progress</Status>
+                </Activity>
+                <Activity>
+                    <ObjectId>2</ObjectId>
+                    <Id>A1010</Id>
+                    <Name>Build</Name>
+                    <WBSObjectId>10</WBSObjectId>
+                    <PlannedStartDate>2024-01-08T08:00:00</PlannedStartDate>
+                    <PlannedFinishDate>2024-01-12T17:00:00</PlannedFinishDate>
+                    <PlannedDuration>40</PlannedDuration>
+                </Activity>
+                <Relationship>
+                    <PredecessorActivityObjectId>1</PredecessorActivityObjectId>
+                    <SuccessorActivityObjectId>2</SuccessorActivityObjectId>
+                    <Type>Finish to Start</Type>
+                    <Lag>8</Lag>
+                </Relationship>
+            </Project>
+        </APIBusinessObjects>
+        "#;
+
+        let tasks = import_p6_xml(xml.as_bytes()).unwrap();
+        assert_eq!(tasks.len(), 3);
+
+        assert_eq!(tasks[0].name, "Engineering");
+        assert_eq!(tasks[0].wbs, "1");
+        assert_eq!(tasks[0].start.date(), chrono::NaiveDate::from_ymd_opt(2024, 1, 1).unwrap());
+        assert_eq!(tasks[0].finish.date(), chrono::NaiveDate::from_ymd_opt(2024, 1, 12).unwrap());
+
+        assert_eq!(tasks[1].name, "Design");
+        assert_eq!(tasks[1].wbs, "1.1");
+        assert_eq!(tasks[1].duration, Duration::from_days(5));
+        assert_eq!(tasks[1].percent_complete, 50);
+        assert_eq!(tasks[1].status, TaskStatus::InProgress);
+
+        assert_eq!(tasks[2].predecessors, vec![Predecessor { predecessor_uid: 1, link_type: 1, link_lag: 4800, lag_format: 7 }]);
+    }
+
+    #[test]
+    fn test_import_p6_nested_wbs_and_projects() {
+        let wbs = |id: i32, parent: i32, name: &str| format!(
+            "<WBS><ObjectId>{id}</ObjectId><ParentObjectId>{parent}</ParentObjectId><Name>{name}</Name></WBS>"
+        );
+        let activity = |id: i32, node: i32, start: &str, finish: &str, percent: u32| format!(
+            "<Activity><ObjectId>{id}</ObjectId><Name>A{id}</Name><WBSObjectId>{node}</WBSObjectId>\
+             <PlannedStartDate>{start}T08:00:00</PlannedStartDate><PlannedFinishDate>{finish}T17:00:00</PlannedFinishDate>\
+             <PercentComplete>{percent}</PercentComplete></Activity>"
+        );
+        let xml = format!(
+            "<APIBusinessObjects><Project><ObjectId>100</ObjectId>{}{}{}{}{}</Project><Project><ObjectId>200</ObjectId>{}{}</Project></APIBusinessObjects>",
+            wbs(10, 100, "Plant"),
+            wbs(11, 10, "Civil"),
+            wbs(12, 10, "Empty"),
+            activity(1, 10, "2024-01-03", "2024-01-04", 1),
+            activity(2, 11, "2024-01-08", "2024-01-12", 50),
+            wbs(20, 200, "Second"),
+            activity(3, 20, "2024-02-01", "2024-02-02", 0),
+        );
+
+        let tasks = import_p6_xml(xml.as_bytes()).unwrap();
+        let summary = |wbs: &str| tasks.iter().find(|t| t.wbs == wbs).unwrap();
+        let date = |m, d| chrono::NaiveDate::from_ymd_opt(2024, m, d).unwrap();
+
+        // The outer node spans its own activity and its child node's, not today
+        assert_eq!(summary("1").name, "Plant");
+        assert_eq!((summary("1").start.date(), summary("1").finish.date()), (date(1, 3), date(1, 12)));
+        assert_eq!(summary("1.2").name, "Civil");
+        assert_eq!((summary("1.2").start.date(), summary("1.2").finish.date()), (date(1, 8), date(1, 12)));
+        // Nodes without activities are left out; the second project carries on numbering
+        assert!(tasks.iter().all(|t| t.name != "Empty"));
+        assert_eq!(summary("2").name, "Second");
+        let uids: std::collections::HashSet<i32> = tasks.iter().map(|t| t.uid).collect();
+        assert_eq!(uids.len(), tasks.len());
+        // 0-100 file: 1 is 1%, not 100%
+        assert_eq!(summary("1.1").percent_complete, 1);
+    }
+}
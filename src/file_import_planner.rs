@@ -0,0 +1,247 @@
+use chrono::NaiveDateTime;
+use quick_xml::de::from_str;
+use serde::Deserialize;
+use std::collections::HashMap;
+use crate::duration::{Duration, DurationUnit};
+use crate::registry::{Capabilities, Confidence, ImportOptions, Importer, Probe};
+use crate::task::{Predecessor, Task};
+
+/// GNOME Planner `.planner` files (`<project mrproject-version="...">` root).
+pub struct PlannerImporter;
+
+impl Importer for PlannerImporter {
+    fn name(&self) -> &'static str {
+        "GNOME Planner"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["planner", "mrproject"]
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::new(true, true, true)
+    }
+
+    fn detect(&self, probe: &Probe) -> Confidence {
+        match &probe.xml_root {
+            Some(root) if root.name == "project" && root.has_attribute("mrproject-version") => Confidence::Content,
+            _ if probe.has_extension(self.extensions()) => Confidence::Extension,
+            _ => Confidence::None,
+        }
+    }
+
+    fn import(&self, content: &[u8], _options: &ImportOptions) -> Result<Vec<Task>, Box<dyn std::error::Error>> {
+        import_planner_xml(content)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct PlannerProject {
+    #[serde(rename = "tasks", default)]
+    tasks: Option<PlannerTasks>,
+    #[serde(rename = "resources", default)]
+    resources: Option<PlannerResources>,
+    #[serde(rename = "allocations", default)]
+    allocations: Option<PlannerAllocations>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PlannerTasks {
+    #[serde(rename = "task", default)]
+    task: Vec<PlannerTask>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PlannerTask {
+    #[serde(rename = "@id")]
+    id: i32,
+    #[serde(rename = "@name", default)]
+    name: String,
+    #[serde(rename = "@note", default)]
+    note: String,
+    // Seconds
+    #[serde(rename = "@work", default)]
+    work: u32,
+    #[serde(rename = "@start", default)]
+    start: String,
+    #[serde(rename = "@end", default)]
+    end: String,
+    #[serde(rename = "@percent-complete", default)]
+    percent_complete: u32,
+    #[serde(rename = "@type", default)]
+    task_type: String,
+    #[serde(rename = "predecessors", default)]
+    predecessors: Option<PlannerPredecessors>,
+    #[serde(rename = "task", default)]
+    task: Vec<PlannerTask>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PlannerPredecessors {
+    #[serde(rename = "predecessor", default)]
+    predecessor: Vec<PlannerPredecessor>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PlannerPredecessor {
+    #[serde(rename = "@predecessor-id")]
+    predecessor_id: i32,
+    #[serde(rename = "@type", default)]
+    link_type: String,
+    // Seconds
+    #[serde(rename = "@lag", default)]
+    lag: i32,
+}
+
+#[derive(Debug, Deserialize)]
+struct PlannerResources {
+    #[serde(rename = "resource", default)]
+    resource: Vec<PlannerResource>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PlannerResource {
+    #[serde(rename = "@id")]
+    id: i32,
+    #[serde(rename = "@name", default)]
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct PlannerAllocations {
+    #[serde(rename = "allocation", default)]
+    allocation: Vec<PlannerAllocation>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PlannerAllocation {
+    #[serde(rename = "@task-id")]
+    task_id: i32,
+    #[serde(rename = "@resource-id")]
+    resource_id: i32,
+}
+
+fn import_planner_xml(content: &[u8]) -> Result<Vec<Task>, Box<dyn std::error::Error>> {
+    let xml_str = String::from_utf8_lossy(content);
+    let project: PlannerProject = from_str(&xml_str)?;
+
+    let resources: HashMap<i32, String> = project.resources
+        .map(|r| r.resource.into_iter().map(|r| (r.id, r.name)).collect())
+        .unwrap_or_default();
+    let mut assignees: HashMap<i32, Vec<String>> = HashMap::new();
+    for allocation in project.allocations.map(|a| a.allocation).unwrap_or_default() {
+        if let Some(name) = resources.get(&allocation.resource_id) {
+            assignees.entry(allocation.task_id).or_default().push(name.clone());
+        }
+    }
+
+    let mut tasks = Vec::new();
+    let roots = project.tasks.map(|t| t.task).unwrap_or_default();
+    for (i, planner_task) in roots.iter().enumerate() {
+        add_planner_task(planner_task, (i + 1).to_string(), &assignees, &mut tasks);
+    }
+
+    Ok(tasks)
+}
+
+fn add_planner_task(
+    planner_task: &PlannerTask,
+    wbs: String,
+    assignees: &HashMap<i32, Vec<String>>,
+    tasks: &mut Vec<Task>,
+) {
+    let mut task = Task::new(planner_task.name.clone());
+    task.uid = planner_task.id;
+    task.wbs = wbs.clone();
+    task.description = planner_task.note.clone();
+    task.assignee = assignees.get(&planner_task.id).map(|a| a.join(", ")).unwrap_or_default();
+
+    if let Some(start) = parse_planner_date(&planner_task.start) {
+        task.start = start;
+        task.finish = start;
+    }
+    if let Some(end) = parse_planner_date(&planner_task.end) {
+        task.finish = end;
+    }
+
+    task.milestone = planner_task.task_type == "milestone";
+    if !task.milestone {
+        // 8h working day
+        task.duration = Duration::from_minutes(planner_task.work.div_ceil(60), DurationUnit::Days);
+    }
+
+    task.percent_complete = planner_task.percent_complete.min(100);
+    task.status = crate::status::from_percent(task.percent_complete);
+
+    if let Some(preds) = &planner_task.predecessors {
+        for pred in &preds.predecessor {
+            task.predecessors.push(Predecessor {
+                predecessor_uid: pred.predecessor_id,
+                link_type: planner_link_type(&pred.link_type),
+                // Seconds to tenths of a minute
+                link_lag: pred.lag / 6,
+                lag_format: crate::dependency::LAG_FORMAT_DAYS,
+            });
+        }
+    }
+
+    tasks.push(task);
+
+    for (i, child) in planner_task.task.iter().enumerate() {
+        add_planner_task(child, format!("{}.{}", wbs, i + 1), assignees, tasks);
+    }
+}
+
+fn parse_planner_date(s: &str) -> Option<NaiveDateTime> {
+    // Planner writes compact ISO 8601 in UTC: 20240101T080000Z
+    NaiveDateTime::parse_from_str(s, "%Y%m%dT%H%M%SZ").ok()
+}
+
+fn planner_link_type(s: &str) -> i32 {
+    // MSPDI: 0 = FF, 1 = FS, 2 = SF, 3 = SS
+    match s {
+        "FF" => 0,
+        "SF" => 2,
+        "SS" => 3,
+        _ => 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::TaskStatus;
+
+    #[test]
+    fn test_import_planner_xml() {
+        let xml = r#"<?xml version="1.0"?>
+        <project name="Demo" company="" manager="" phase="" project-start="20240101T000000Z" mrproject-version="2" calendar="1">
+            <tasks>
+                <task id="1" name="Phase" note="" work="57600" start="20240101T080000Z" end="20240102T170000Z" work-start="20240101T080000Z" percent-complete="0" priority="0" type="normal" scheduling="fixed-work">
+                    <task id="2" name="Design" note="Sketches" work="28800" start="20240101T080000Z" end="20240101T170000Z" work-start="20240101T080000Z" percent-complete="100" priority="0" type="normal" scheduling="fixed-work"/>
+                    <task id="3" name="Build" note="" work="28800" start="20240102T080000Z" end="20240102T170000Z" work-start="20240102T080000Z" percent-complete="0" priority="0" type="normal" scheduling="fixed-work">
+                        <predecessors>
+                            <predecessor id="1" predecessor-id="2" type="FS"/>
+                        </predecessors>
+                    </task>
+                </task>
+            </tasks>
+            <resources>
+                <resource id="1" name="Bob" short-name="" type="1" units="0" email="" note="" std-rate="0"/>
+            </resources>
+            <allocations>
+                <allocation task-id="3" resource-id="1" units="100"/>
+            </allocations>
+        </project>
+        "#;
+
+        let tasks = import_planner_xml(xml.as_bytes()).unwrap();
+        assert_eq!(tasks.len(), 3);
+        assert_eq!(tasks[0].duration.days(), 2.0);
+        assert_eq!(tasks[1].wbs, "1.1");
+        assert_eq!(tasks[1].status, TaskStatus::Completed);
+        assert_eq!(tasks[1].description, "Sketches");
+        assert_eq!(tasks[2].assignee, "Bob");
+        assert_eq!(tasks[2].predecessors, vec![Predecessor { predecessor_uid: 2, link_type: 1, link_lag: 0, lag_format: 7 }]);
+    }
+}
@@ -0,0 +1,251 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use crate::merge::LoadedFile;
+use crate::task::{Task, TaskStatus};
+
+/// One test a task has to pass.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Condition {
+    StatusIn(Vec<TaskStatus>),
+    /// Case-insensitive; matches any of a comma separated assignee list.
+    AssigneeIs(String),
+    /// Inclusive on both ends.
+    DatesOverlap { from: NaiveDate, to: NaiveDate },
+    PercentBelow(u32),
+    /// The task itself or anything below it in the WBS.
+    WbsUnder(String),
+    /// A loaded file by name, so saved views survive files being
+    /// reordered or removed. Matches nothing if no such file is loaded,
+    /// and everything when empty.
+    SourceFile(String),
+    /// The loaded files disagree about the task.
+    HasConflict,
+    /// Past its finish on the status date and not complete or cancelled.
+    Late,
+}
+
+impl Condition {
+    /// Starting values for a newly added condition of each kind.
+    pub fn templates(today: NaiveDate) -> Vec<Condition> {
+        vec![
+            Condition::StatusIn(vec![TaskStatus::InProgress]),
+            Condition::AssigneeIs(String::new()),
+            Condition::DatesOverlap { from: today, to: today + chrono::Duration::days(30) },
+            Condition::PercentBelow(100),
+            Condition::WbsUnder(String::new()),
+            Condition::SourceFile(String::new()),
+            Condition::HasConflict,
+            Condition::Late,
+        ]
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Condition::StatusIn(_) => "Status is one of",
+            Condition::AssigneeIs(_) => "Assignee is",
+            Condition::DatesOverlap { .. } => "Dates overlap",
+            Condition::PercentBelow(_) => "% complete below",
+            Condition::WbsUnder(_) => "WBS under",
+            Condition::SourceFile(_) => "Has a field from",
+            Condition::HasConflict => "Files disagree",
+            Condition::Late => "Late",
+        }
+    }
+
+    fn matches(&self, task: &Task, context: &FilterContext) -> bool {
+        match self {
+            Condition::StatusIn(statuses) => statuses.contains(&task.status),
+            Condition::AssigneeIs(name) => {
+                let name = name.trim();
+                name.is_empty() || task.assignee.split([',', ';']).any(|a| a.trim().eq_ignore_ascii_case(name))
+            }
+            Condition::DatesOverlap { from, to } => task.start.date() <= *to && task.finish.date() >= *from,
+            Condition::PercentBelow(limit) => task.percent_complete < *limit,
+            Condition::WbsUnder(prefix) => {
+                let prefix = prefix.trim().trim_end_matches('.');
+                prefix.is_empty()
+                    || task.wbs == prefix
+                    || task.wbs.strip_prefix(prefix).is_some_and(|rest| rest.starts_with('.'))
+            }
+            Condition::SourceFile(name) => name.is_empty()
+                || context.files.iter().position(|f| f == name).is_some_and(|index| task.touched_by(index)),
+            Condition::HasConflict => context.conflicts.contains(&task.wbs),
+            Condition::Late => crate::status::is_late(task, context.today),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Combine {
+    #[default]
+    All,
+    Any,
+}
+
+/// Conditions combined with AND (`All`) or OR (`Any`). No conditions
+/// matches everything.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Filter {
+    pub combine: Combine,
+    pub conditions: Vec<Condition>,
+}
+
+/// What conditions need beyond the task itself.
+pub struct FilterContext {
+    /// The status date, for `Late`.
+    pub today: NaiveDate,
+    /// WBS codes the loaded files disagree about.
+    pub conflicts: HashSet<String>,
+    /// Names of the loaded files, in order, for `SourceFile`.
+    pub files: Vec<String>,
+}
+
+impl Filter {
+    pub fn is_empty(&self) -> bool {
+        self.conditions.is_empty()
+    }
+
+    pub fn needs_conflicts(&self) -> bool {
+        self.conditions.contains(&Condition::HasConflict)
+    }
+
+    pub fn matches(&self, task: &Task, context: &FilterContext) -> bool {
+        match self.combine {
+            Combine::All => self.conditions.iter().all(|c| c.matches(task, context)),
+            Combine::Any => self.is_empty() || self.conditions.iter().any(|c| c.matches(task, context)),
+        }
+    }
+}
+
+/// WBS codes of tasks that two or more enabled files give different dates,
+/// progress, status or assignee for.
+pub fn conflicting_wbs(files: &[LoadedFile]) -> HashSet<String> {
+    type Values = (NaiveDateTime, NaiveDateTime, u32, TaskStatus, String);
+    let mut seen: HashMap<&str, Values> = HashMap::new();
+    let mut conflicts = HashSet::new();
+    for task in files.iter().filter(|f| f.enabled).flat_map(|f| &f.tasks) {
+        if task.wbs.is_empty() {
+            continue;
+        }
+        let values = (task.start, task.finish, task.percent_complete, task.status, task.assignee.clone());
+        match seen.get(task.wbs.as_str()) {
+            Some(previous) if *previous != values => {
+                conflicts.insert(task.wbs.clone());
+            }
+            Some(_) => {}
+            None => {
+                seen.insert(&task.wbs, values);
+            }
+        }
+    }
+    conflicts
+}
+
+/// A named filter with the table's search text and sort order.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedView {
+    pub name: String,
+    pub filter: Filter,
+    #[serde(default)]
+    pub filter_text: String,
+    #[serde(default)]
+    pub sort_column: Option<usize>,
+    #[serde(default)]
+    pub sort_ascending: bool,
+}
+
+/// Saved views live outside session files so every session can use them.
+fn views_path() -> Option<PathBuf> {
+    crate::session::app_data_path("views.json")
+}
+
+pub fn load_views() -> Vec<SavedView> {
+    views_path()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_views(views: &[SavedView]) -> Result<(), Box<dyn std::error::Error>> {
+    let path = views_path().ok_or("No data directory for saved views")?;
+    crate::session::write_atomic(&path, &serde_json::to_string_pretty(views)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::Origin;
+
+    fn task(wbs: &str, status: TaskStatus, percent: u32, end_day: u32) -> Task {
+        let mut t = Task::new(format!("Task {}", wbs));
+        t.wbs = wbs.to_string();
+        t.status = status;
+        t.percent_complete = percent;
+        t.start = crate::task::at_start(NaiveDate::from_ymd_opt(2024, 3, 1).unwrap());
+        t.finish = crate::task::at_finish(NaiveDate::from_ymd_opt(2024, 3, end_day).unwrap());
+        t
+    }
+
+    #[test]
+    fn test_filter_combinations() {
+        let context = FilterContext {
+            today: NaiveDate::from_ymd_opt(2024, 3, 10).unwrap(),
+            conflicts: HashSet::from(["2".to_string()]),
+            files: Vec::new(),
+        };
+        let late = task("1.2", TaskStatus::InProgress, 50, 5);
+        let on_time = task("1.10", TaskStatus::InProgress, 50, 20);
+        let conflicting = task("2", TaskStatus::Completed, 100, 5);
+
+        let mut filter = Filter {
+            combine: Combine::All,
+            conditions: vec![Condition::WbsUnder("1".to_string()), Condition::Late],
+        };
+        assert!(filter.matches(&late, &context));
+        assert!(!filter.matches(&on_time, &context));
+        assert!(!filter.matches(&conflicting, &context));
+
+        filter.combine = Combine::Any;
+        filter.conditions = vec![Condition::Late, Condition::HasConflict];
+        assert!(filter.matches(&late, &context));
+        assert!(filter.matches(&conflicting, &context));
+        assert!(!filter.matches(&on_time, &context));
+
+        // "1.1" isn't a parent of "1.10"
+        let under = Condition::WbsUnder("1.1".to_string());
+        assert!(!under.matches(&on_time, &context));
+    }
+
+    #[test]
+    fn test_filter_touched_by_file() {
+        let context = FilterContext {
+            today: NaiveDate::from_ymd_opt(2024, 3, 10).unwrap(),
+            conflicts: HashSet::new(),
+            files: vec!["base.xml".to_string(), "overlay.xlsx".to_string(), "extra.xlsx".to_string()],
+        };
+        let at = crate::task::at_start(context.today);
+        let mut updated = task("1", TaskStatus::InProgress, 50, 5);
+        updated.set_field_origin("percent_complete", Origin::File(1), at);
+        let mut appended = task("2", TaskStatus::NotStarted, 0, 5);
+        appended.source_file = 2;
+        let untouched = task("3", TaskStatus::NotStarted, 0, 5);
+
+        let overlay = Condition::SourceFile("overlay.xlsx".to_string());
+        assert!(overlay.matches(&updated, &context));
+        assert!(!overlay.matches(&appended, &context));
+        assert!(!overlay.matches(&untouched, &context));
+        // The base touches everything it was imported with
+        assert!(Condition::SourceFile("base.xml".to_string()).matches(&updated, &context));
+        assert!(Condition::SourceFile("extra.xlsx".to_string()).matches(&appended, &context));
+
+        // Still the same file after the list is reordered, and nothing once
+        // it's removed
+        let reordered = FilterContext { files: vec!["base.xml".to_string(), "overlay.xlsx".to_string()], ..context };
+        updated.set_field_origin("percent_complete", Origin::File(0), at);
+        assert!(!overlay.matches(&updated, &reordered));
+        assert!(!Condition::SourceFile("extra.xlsx".to_string()).matches(&appended, &reordered));
+    }
+}
@@ -0,0 +1,169 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use eframe::egui;
+use crate::duration::Duration;
+use crate::filter::{Combine, Condition, Filter};
+use crate::task::TaskStatus;
+
+const STATUSES: [(TaskStatus, &str); 5] = [
+    (TaskStatus::NotStarted, "Not Started"),
+    (TaskStatus::InProgress, "In Progress"),
+    (TaskStatus::Completed, "Completed"),
+    (TaskStatus::OnHold, "On Hold"),
+    (TaskStatus::Cancelled, "Cancelled"),
+];
+
+/// Values the condition editors offer to pick from.
+pub struct FilterChoices {
+    pub assignees: Vec<String>,
+    pub files: Vec<String>,
+    pub today: NaiveDate,
+}
+
+/// Edits `filter` in place: AND/OR switch, one row per condition, and a
+/// menu to add more.
+pub fn show_filter_builder(ui: &mut egui::Ui, filter: &mut Filter, choices: &FilterChoices) {
+    ui.horizontal(|ui| {
+        ui.label("Show tasks matching");
+        ui.selectable_value(&mut filter.combine, Combine::All, "all");
+        ui.selectable_value(&mut filter.combine, Combine::Any, "any");
+        ui.label("of these conditions:");
+    });
+
+    let mut remove = None;
+    for (i, condition) in filter.conditions.iter_mut().enumerate() {
+        ui.horizontal(|ui| {
+            if ui.small_button("❌").clicked() {
+                remove = Some(i);
+            }
+            ui.label(condition.label());
+            edit_condition(ui, i, condition, choices);
+        });
+    }
+    if let Some(i) = remove {
+        filter.conditions.remove(i);
+    }
+
+    ui.horizontal(|ui| {
+        ui.menu_button("➕ Add condition", |ui| {
+            for template in Condition::templates(choices.today) {
+                if ui.button(template.label()).clicked() {
+                    filter.conditions.push(template);
+                    ui.close_menu();
+                }
+            }
+        });
+        if !filter.is_empty() && ui.button("Clear conditions").clicked() {
+            filter.conditions.clear();
+        }
+    });
+}
+
+fn edit_condition(ui: &mut egui::Ui, i: usize, condition: &mut Condition, choices: &FilterChoices) {
+    match condition {
+        Condition::StatusIn(statuses) => {
+            for (status, label) in STATUSES {
+                let mut on = statuses.contains(&status);
+                if ui.checkbox(&mut on, label).changed() {
+                    if on {
+                        statuses.push(status);
+                    } else {
+                        statuses.retain(|s| *s != status);
+                    }
+                }
+            }
+        }
+        Condition::AssigneeIs(name) => {
+            ui.add(egui::TextEdit::singleline(name).desired_width(120.0).hint_text("name"));
+            egui::ComboBox::from_id_salt(("filter_assignee", i))
+                .selected_text("▾")
+                .width(24.0)
+                .show_ui(ui, |ui| {
+                    for assignee in &choices.assignees {
+                        if ui.selectable_label(name == assignee, assignee).clicked() {
+                            *name = assignee.clone();
+                        }
+                    }
+                });
+        }
+        Condition::DatesOverlap { from, to } => {
+            date_field(ui, ("filter_from", i), from);
+            ui.label("to");
+            date_field(ui, ("filter_to", i), to);
+        }
+        Condition::PercentBelow(limit) => {
+            ui.add(egui::DragValue::new(limit).range(0..=100).suffix("%"));
+        }
+        Condition::WbsUnder(prefix) => {
+            ui.add(egui::TextEdit::singleline(prefix).desired_width(80.0).hint_text("e.g. 1.2"));
+        }
+        Condition::SourceFile(file) => {
+            let selected = match file.as_str() {
+                "" => "Any file".to_string(),
+                name if choices.files.iter().any(|f| f == name) => name.to_string(),
+                name => format!("{} (not loaded)", name),
+            };
+            egui::ComboBox::from_id_salt(("filter_source", i))
+                .selected_text(selected)
+                .show_ui(ui, |ui| {
+                    for name in &choices.files {
+                        ui.selectable_value(file, name.clone(), name);
+                    }
+                });
+        }
+        Condition::HasConflict | Condition::Late => {}
+    }
+}
+
+/// YYYY-MM-DD text field. The text is kept while it's being typed, and the
+/// date only changes once it parses.
+pub fn date_field(ui: &mut egui::Ui, id_salt: impl std::hash::Hash, date: &mut NaiveDate) {
+    let id = ui.id().with(id_salt);
+    let mut text = ui.data_mut(|d| d.get_temp::<String>(id))
+        .unwrap_or_else(|| date.format("%Y-%m-%d").to_string());
+    let response = ui.add(egui::TextEdit::singleline(&mut text).id(id).desired_width(90.0));
+    if let Ok(parsed) = NaiveDate::parse_from_str(text.trim(), "%Y-%m-%d") {
+        *date = parsed;
+    }
+    if response.has_focus() {
+        ui.data_mut(|d| d.insert_temp(id, text));
+    } else {
+        ui.data_mut(|d| d.remove::<String>(id));
+    }
+}
+
+/// "YYYY-MM-DD HH:MM" text field. A date on its own keeps the current time
+/// of day. Like `date_field`, the value only changes once the text parses.
+pub fn datetime_field(ui: &mut egui::Ui, id_salt: impl std::hash::Hash, moment: &mut NaiveDateTime) {
+    let id = ui.id().with(id_salt);
+    let mut text = ui.data_mut(|d| d.get_temp::<String>(id))
+        .unwrap_or_else(|| moment.format("%Y-%m-%d %H:%M").to_string());
+    let response = ui.add(egui::TextEdit::singleline(&mut text).id(id).desired_width(130.0));
+    let trimmed = text.trim();
+    if let Ok(parsed) = NaiveDateTime::parse_from_str(trimmed, "%Y-%m-%d %H:%M") {
+        *moment = parsed;
+    } else if let Ok(date) = NaiveDate::parse_from_str(trimmed, "%Y-%m-%d") {
+        *moment = date.and_time(moment.time());
+    }
+    if response.has_focus() {
+        ui.data_mut(|d| d.insert_temp(id, text));
+    } else {
+        ui.data_mut(|d| d.remove::<String>(id));
+    }
+}
+
+/// Duration text field, e.g. "4h" or "2.5 days?". Like `date_field`, the
+/// value only changes once the text parses.
+pub fn duration_field(ui: &mut egui::Ui, id_salt: impl std::hash::Hash, duration: &mut Duration) {
+    let id = ui.id().with(id_salt);
+    let mut text = ui.data_mut(|d| d.get_temp::<String>(id))
+        .unwrap_or_else(|| duration.to_string());
+    let response = ui.add(egui::TextEdit::singleline(&mut text).id(id).desired_width(90.0));
+    if let Some(parsed) = Duration::parse(&text) {
+        *duration = parsed;
+    }
+    if response.has_focus() {
+        ui.data_mut(|d| d.insert_temp(id, text));
+    } else {
+        ui.data_mut(|d| d.remove::<String>(id));
+    }
+}
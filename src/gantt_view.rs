@@ -0,0 +1,362 @@
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime};
+use eframe::egui;
+use std::collections::HashMap;
+use crate::task::{day_fraction, default_finish_time, default_start_time, format_moment, is_working_day, working_day_offset, Task};
+
+/// The task table's header row.
+const HEADER_HEIGHT: f32 = 30.0;
+const HANDLE_WIDTH: f32 = 6.0;
+
+const BAR_COLOR: egui::Color32 = egui::Color32::from_rgb(65, 72, 104); // #414868
+const PROGRESS_COLOR: egui::Color32 = egui::Color32::from_rgb(122, 162, 247); // #7AA2F7
+const MILESTONE_COLOR: egui::Color32 = egui::Color32::from_rgb(224, 175, 104); // #E0AF68
+const LINK_COLOR: egui::Color32 = egui::Color32::from_rgb(169, 177, 214); // #A9B1D6
+const TODAY_COLOR: egui::Color32 = egui::Color32::from_rgb(247, 118, 142); // #F7768E
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GanttZoom {
+    Day,
+    Week,
+    Month,
+}
+
+impl GanttZoom {
+    fn pixels_per_day(self) -> f32 {
+        match self {
+            GanttZoom::Day => 28.0,
+            GanttZoom::Week => 8.0,
+            GanttZoom::Month => 2.5,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DragMode {
+    Move,
+    ResizeEnd,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Drag {
+    index: usize,
+    mode: DragMode,
+}
+
+pub struct GanttState {
+    pub zoom: GanttZoom,
+    drag: Option<Drag>,
+    rolled_up: RolledUp,
+}
+
+impl Default for GanttState {
+    fn default() -> Self {
+        Self {
+            zoom: GanttZoom::Week,
+            drag: None,
+            rolled_up: RolledUp::default(),
+        }
+    }
+}
+
+/// The tasks with summary bars following their children, kept until the
+/// tasks change.
+#[derive(Default)]
+struct RolledUp {
+    source: Vec<Task>,
+    tasks: Vec<Task>,
+}
+
+impl RolledUp {
+    fn get(&mut self, tasks: &[Task]) -> &[Task] {
+        if self.source != tasks {
+            self.source = tasks.to_vec();
+            self.tasks = tasks.to_vec();
+            crate::outline::apply_rollups(&mut self.tasks);
+        }
+        &self.tasks
+    }
+}
+
+/// A change to a task, produced when a bar drag is released.
+pub enum Reschedule {
+    /// Moved by whole working days; the duration stays.
+    Move { index: usize, days: i32 },
+    /// New finish; the duration follows it.
+    Resize { index: usize, finish: NaiveDateTime },
+}
+
+impl Reschedule {
+    pub fn index(&self) -> usize {
+        match *self {
+            Reschedule::Move { index, .. } | Reschedule::Resize { index, .. } => index,
+        }
+    }
+
+    pub fn apply(&self, task: &mut Task) {
+        match *self {
+            Reschedule::Move { days, .. } => task.shift(days),
+            Reschedule::Resize { finish, .. } => task.reschedule(task.start, finish),
+        }
+    }
+}
+
+/// Where a drag of `calendar_days` puts the task's finish when resizing:
+/// never before the start, and off weekends.
+fn resized_finish(task: &Task, calendar_days: i64) -> NaiveDateTime {
+    let finish = task.finish.max(task.start) + Duration::days(calendar_days);
+    let mut date = finish.date();
+    while !is_working_day(date) && date > task.start.date() {
+        date = date.pred_opt().unwrap_or(date);
+    }
+    date.and_time(finish.time()).max(task.start)
+}
+
+/// Paints a Gantt chart of `rows` (indices into `tasks`, in display order),
+/// `row_height` apart and scrolled to `scroll_y` like the task table beside
+/// it. Summary bars follow their children. Returns a reschedule when the
+/// user finishes dragging a bar.
+pub fn show_gantt(
+    ui: &mut egui::Ui,
+    tasks: &[Task],
+    rows: &[usize],
+    state: &mut GanttState,
+    row_height: f32,
+    scroll_y: &mut f32,
+) -> Option<Reschedule> {
+    ui.horizontal(|ui| {
+        ui.label(egui::RichText::new("Timeline").strong());
+        ui.selectable_value(&mut state.zoom, GanttZoom::Day, "Day");
+        ui.selectable_value(&mut state.zoom, GanttZoom::Week, "Week");
+        ui.selectable_value(&mut state.zoom, GanttZoom::Month, "Month");
+    });
+
+    if rows.is_empty() {
+        ui.label(egui::RichText::new("No tasks to show.").italics().color(egui::Color32::GRAY));
+        return None;
+    }
+
+    let tasks = state.rolled_up.get(tasks);
+    let ppd = state.zoom.pixels_per_day();
+    let first = rows.iter().map(|&i| tasks[i].start.date()).min()?;
+    let last = rows.iter().map(|&i| tasks[i].finish.max(tasks[i].start).date()).max()?;
+    // Pad so bars and arrows don't touch the edges
+    let range_start = first - Duration::days(7);
+    let range_end = last + Duration::days(14);
+    let day_count = (range_end - range_start).num_days() + 1;
+
+    // Lines up with the table's header, spacing included
+    let header_height = HEADER_HEIGHT + ui.spacing().item_spacing.y;
+    let mut result = None;
+
+    let output = egui::ScrollArea::both()
+        .auto_shrink([false, false])
+        .vertical_scroll_offset(*scroll_y)
+        .show(ui, |ui| {
+            let size = egui::vec2(
+                (day_count as f32 * ppd).max(ui.available_width()),
+                header_height + rows.len() as f32 * row_height,
+            );
+            let (response, painter) = ui.allocate_painter(size, egui::Sense::hover());
+            let origin = response.rect.min;
+            let x_of = |date: NaiveDate| origin.x + (date - range_start).num_days() as f32 * ppd;
+            // Part-way through a day when the time isn't a day boundary
+            let x_at = |moment: NaiveDateTime| x_of(moment.date()) + ppd * day_fraction(moment);
+            let row_top = |row: usize| origin.y + header_height + row as f32 * row_height;
+
+            let text_color = ui.visuals().text_color();
+            let grid_color = ui.visuals().widgets.noninteractive.bg_stroke.color;
+            let small_font = egui::FontId::proportional(11.0);
+
+            // Background: weekends (day zoom only), row stripes and the timescale
+            for offset in 0..day_count {
+                let date = range_start + Duration::days(offset);
+                let x = x_of(date);
+                if state.zoom == GanttZoom::Day && !is_working_day(date) {
+                    painter.rect_filled(
+                        egui::Rect::from_min_max(egui::pos2(x, origin.y + header_height), egui::pos2(x + ppd, response.rect.max.y)),
+                        0.0,
+                        ui.visuals().faint_bg_color,
+                    );
+                }
+
+                let label = match state.zoom {
+                    GanttZoom::Day => Some(date.format("%d").to_string()),
+                    GanttZoom::Week if date.weekday() == chrono::Weekday::Mon => Some(date.format("%d %b").to_string()),
+                    GanttZoom::Month if date.day() == 1 => Some(date.format("%b %Y").to_string()),
+                    _ => None,
+                };
+                if let Some(label) = label {
+                    painter.line_segment(
+                        [egui::pos2(x, origin.y + header_height * 0.5), egui::pos2(x, response.rect.max.y)],
+                        egui::Stroke::new(1.0, grid_color),
+                    );
+                    painter.text(egui::pos2(x + 2.0, origin.y + 4.0), egui::Align2::LEFT_TOP, label, small_font.clone(), text_color);
+                }
+            }
+            for row in (1..rows.len()).step_by(2) {
+                painter.rect_filled(
+                    egui::Rect::from_min_size(egui::pos2(origin.x, row_top(row)), egui::vec2(size.x, row_height)),
+                    0.0,
+                    ui.visuals().faint_bg_color.gamma_multiply(0.5),
+                );
+            }
+
+            // Where the current drag would put its task
+            let drag_days = ui.input(|i| match (i.pointer.press_origin(), i.pointer.interact_pos()) {
+                (Some(from), Some(to)) => ((to.x - from.x) / ppd).round() as i64,
+                _ => 0,
+            });
+            let active_drag = state.drag;
+            let change_of = |index: usize| {
+                let drag = active_drag.filter(|d| d.index == index && drag_days != 0)?;
+                Some(match drag.mode {
+                    DragMode::Move => Reschedule::Move { index, days: working_day_offset(tasks[index].start.date(), drag_days) },
+                    DragMode::ResizeEnd => Reschedule::Resize { index, finish: resized_finish(&tasks[index], drag_days) },
+                })
+            };
+            let dates_of = |index: usize| {
+                let mut task = tasks[index].clone();
+                if let Some(change) = change_of(index) {
+                    change.apply(&mut task);
+                }
+                (task.start, task.finish.max(task.start))
+            };
+
+            // Bars
+            let mut bar_rects: HashMap<i32, egui::Rect> = HashMap::new();
+            for (row, &index) in rows.iter().enumerate() {
+                let task = &tasks[index];
+                let (start, end) = dates_of(index);
+                let top = row_top(row);
+                let bar = egui::Rect::from_min_max(
+                    egui::pos2(x_at(start), top + row_height * 0.2),
+                    egui::pos2(x_at(end), top + row_height * 0.8),
+                );
+                if task.uid > 0 {
+                    bar_rects.insert(task.uid, bar);
+                }
+
+                let id = ui.id().with(("gantt_bar", index));
+                let mut hover_text = format!(
+                    "{}\n{} → {}\n{}% complete",
+                    task.name,
+                    format_moment(start, default_start_time()),
+                    format_moment(end, default_finish_time()),
+                    task.percent_complete
+                );
+
+                if task.milestone {
+                    let center = egui::pos2(x_of(start.date()) + ppd * 0.5, bar.center().y);
+                    let r = row_height * 0.3;
+                    painter.add(egui::Shape::convex_polygon(
+                        vec![
+                            center + egui::vec2(0.0, -r),
+                            center + egui::vec2(r, 0.0),
+                            center + egui::vec2(0.0, r),
+                            center + egui::vec2(-r, 0.0),
+                        ],
+                        MILESTONE_COLOR,
+                        egui::Stroke::NONE,
+                    ));
+                    hover_text = format!("{}\n{}", task.name, format_moment(start, default_start_time()));
+                } else {
+                    painter.rect_filled(bar, 3.0, BAR_COLOR);
+                    let progress = bar.width() * task.percent_complete.min(100) as f32 / 100.0;
+                    if progress > 0.0 {
+                        painter.rect_filled(
+                            egui::Rect::from_min_size(bar.min, egui::vec2(progress, bar.height())),
+                            3.0,
+                            PROGRESS_COLOR,
+                        );
+                    }
+                }
+                painter.text(
+                    egui::pos2(bar.max.x + 6.0, bar.center().y),
+                    egui::Align2::LEFT_CENTER,
+                    &task.name,
+                    small_font.clone(),
+                    text_color,
+                );
+
+                // Dragging the body moves the task, the right edge changes its end
+                let body = ui.interact(bar.expand2(egui::vec2(0.0, 2.0)), id, egui::Sense::drag())
+                    .on_hover_text(hover_text)
+                    .on_hover_cursor(egui::CursorIcon::Grab);
+                let handle = if task.milestone {
+                    None
+                } else {
+                    let handle_rect = egui::Rect::from_min_max(egui::pos2(bar.max.x - HANDLE_WIDTH, bar.min.y), bar.max);
+                    Some(ui.interact(handle_rect, id.with("end"), egui::Sense::drag())
+                        .on_hover_cursor(egui::CursorIcon::ResizeHorizontal))
+                };
+
+                if handle.as_ref().is_some_and(|h| h.drag_started()) {
+                    state.drag = Some(Drag { index, mode: DragMode::ResizeEnd });
+                } else if body.drag_started() {
+                    state.drag = Some(Drag { index, mode: DragMode::Move });
+                }
+                if body.drag_stopped() || handle.as_ref().is_some_and(|h| h.drag_stopped()) {
+                    if let Some(change) = change_of(index) {
+                        result = Some(change);
+                    }
+                    state.drag = None;
+                }
+            }
+
+            // Dependency arrows
+            for &index in rows {
+                let task = &tasks[index];
+                let Some(&to) = bar_rects.get(&task.uid) else { continue };
+                for pred in &task.predecessors {
+                    let Some(&from) = bar_rects.get(&pred.predecessor_uid) else { continue };
+                    // MSPDI link types: 0 = FF, 1 = FS, 2 = SF, 3 = SS
+                    let (out_of_end, into_start) = match pred.link_type {
+                        0 => (true, false),
+                        2 => (false, false),
+                        3 => (false, true),
+                        _ => (true, true),
+                    };
+                    let start = if out_of_end { from.right_center() } else { from.left_center() };
+                    let end = if into_start { to.left_center() } else { to.right_center() };
+                    draw_link(&painter, start, end, out_of_end, into_start);
+                }
+            }
+
+            // Today line
+            let today = chrono::Local::now().date_naive();
+            if today >= range_start && today <= range_end {
+                let x = x_of(today) + ppd * 0.5;
+                painter.line_segment(
+                    [egui::pos2(x, origin.y), egui::pos2(x, response.rect.max.y)],
+                    egui::Stroke::new(2.0, TODAY_COLOR),
+                );
+            }
+        });
+    *scroll_y = output.state.offset.y;
+
+    result
+}
+
+/// Elbow connector with an arrowhead. Links leave the predecessor from its end
+/// or start and enter the successor at its start (from the left) or end.
+fn draw_link(painter: &egui::Painter, from: egui::Pos2, to: egui::Pos2, out_of_end: bool, into_start: bool) {
+    let stroke = egui::Stroke::new(1.2, LINK_COLOR);
+    let gap = 8.0;
+    let leave_x = if out_of_end { from.x + gap } else { from.x - gap };
+    let approach_x = if into_start { to.x - gap } else { to.x + gap };
+    let points = vec![
+        from,
+        egui::pos2(leave_x, from.y),
+        egui::pos2(leave_x, to.y),
+        egui::pos2(approach_x, to.y),
+        to,
+    ];
+    painter.add(egui::Shape::line(points, stroke));
+
+    let dir = if into_start { 1.0 } else { -1.0 };
+    painter.add(egui::Shape::convex_polygon(
+        vec![to, egui::pos2(to.x - 6.0 * dir, to.y - 4.0), egui::pos2(to.x - 6.0 * dir, to.y + 4.0)],
+        LINK_COLOR,
+        egui::Stroke::NONE,
+    ));
+}
@@ -0,0 +1,249 @@
+use serde::{Deserialize, Serialize};
+use crate::task::Task;
+
+/// Oldest entries are dropped beyond this many undo steps.
+const MAX_UNDO: usize = 200;
+
+/// A reversible change to the task list. Commands store whole tasks rather
+/// than field diffs so every mutation, however it's made, can be undone.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Command {
+    Edit { index: usize, before: Box<Task>, after: Box<Task> },
+    /// New tasks placed together starting at `index`.
+    Insert { index: usize, tasks: Vec<Task> },
+    /// Removed tasks with their original indices, ascending.
+    Delete { removed: Vec<(usize, Task)> },
+    /// New task `i` is old task `order[i]`.
+    Reorder { order: Vec<usize> },
+    /// Several commands undone and redone as one step.
+    Group { label: String, commands: Vec<Command> },
+    /// Wholesale change such as a merge.
+    Replace { label: String, before: Vec<Task>, after: Vec<Task> },
+}
+
+impl Command {
+    pub fn label(&self) -> String {
+        match self {
+            Command::Edit { after, .. } => format!("Edit '{}'", after.name),
+            Command::Insert { tasks, .. } if tasks.len() == 1 => format!("Add '{}'", tasks[0].name),
+            Command::Insert { tasks, .. } => format!("Add {} tasks", tasks.len()),
+            Command::Delete { removed } if removed.len() == 1 => format!("Delete '{}'", removed[0].1.name),
+            Command::Delete { removed } => format!("Delete {} tasks", removed.len()),
+            Command::Reorder { .. } => "Move tasks".to_string(),
+            Command::Group { label, .. } | Command::Replace { label, .. } => label.clone(),
+        }
+    }
+
+    fn apply(&self, tasks: &mut Vec<Task>) {
+        match self {
+            Command::Edit { index, after, .. } => {
+                if let Some(task) = tasks.get_mut(*index) {
+                    *task = (**after).clone();
+                }
+            }
+            Command::Insert { index, tasks: new } => {
+                let at = (*index).min(tasks.len());
+                tasks.splice(at..at, new.iter().cloned());
+            }
+            Command::Delete { removed } => {
+                for (index, _) in removed.iter().rev() {
+                    if *index < tasks.len() {
+                        tasks.remove(*index);
+                    }
+                }
+            }
+            Command::Reorder { order } => permute(tasks, order),
+            Command::Group { commands, .. } => {
+                for command in commands {
+                    command.apply(tasks);
+                }
+            }
+            Command::Replace { after, .. } => *tasks = after.clone(),
+        }
+    }
+
+    fn revert(&self, tasks: &mut Vec<Task>) {
+        match self {
+            Command::Edit { index, before, .. } => {
+                if let Some(task) = tasks.get_mut(*index) {
+                    *task = (**before).clone();
+                }
+            }
+            Command::Insert { index, tasks: new } => {
+                let at = (*index).min(tasks.len());
+                tasks.drain(at..(at + new.len()).min(tasks.len()));
+            }
+            Command::Delete { removed } => {
+                for (index, task) in removed {
+                    tasks.insert((*index).min(tasks.len()), task.clone());
+                }
+            }
+            Command::Reorder { order } => {
+                let mut inverse = vec![0; order.len()];
+                for (new, &old) in order.iter().enumerate() {
+                    inverse[old] = new;
+                }
+                permute(tasks, &inverse);
+            }
+            Command::Group { commands, .. } => {
+                for command in commands.iter().rev() {
+                    command.revert(tasks);
+                }
+            }
+            Command::Replace { before, .. } => *tasks = before.clone(),
+        }
+    }
+}
+
+fn permute(tasks: &mut Vec<Task>, order: &[usize]) {
+    if order.len() != tasks.len() {
+        return;
+    }
+    let mut old: Vec<Option<Task>> = std::mem::take(tasks).into_iter().map(Some).collect();
+    *tasks = order.iter().filter_map(|&i| old[i].take()).collect();
+}
+
+/// Undo and redo stacks for the task list.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct History {
+    undo: Vec<Command>,
+    redo: Vec<Command>,
+    /// While set, further edits to the last edited task extend its entry,
+    /// so typing a name is one step rather than one per keystroke.
+    #[serde(skip)]
+    open_edit: bool,
+}
+
+impl History {
+    /// Applies `command` to `tasks` and records it.
+    pub fn execute(&mut self, tasks: &mut Vec<Task>, command: Command) {
+        command.apply(tasks);
+        self.redo.clear();
+
+        if let (true, Command::Edit { index, after, .. }) = (self.open_edit, &command) {
+            if let Some(Command::Edit { index: last, after: last_after, .. }) = self.undo.last_mut() {
+                if last == index {
+                    last_after.clone_from(after);
+                    return;
+                }
+            }
+        }
+
+        self.open_edit = matches!(command, Command::Edit { .. });
+        self.undo.push(command);
+        if self.undo.len() > MAX_UNDO {
+            self.undo.remove(0);
+        }
+    }
+
+    /// Ends the current edit so the next one starts a new undo step.
+    pub fn seal(&mut self) {
+        self.open_edit = false;
+    }
+
+    pub fn undo(&mut self, tasks: &mut Vec<Task>) -> bool {
+        self.seal();
+        let Some(command) = self.undo.pop() else { return false };
+        command.revert(tasks);
+        self.redo.push(command);
+        true
+    }
+
+    pub fn redo(&mut self, tasks: &mut Vec<Task>) -> bool {
+        self.seal();
+        let Some(command) = self.redo.pop() else { return false };
+        command.apply(tasks);
+        self.undo.push(command);
+        true
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Applied commands, oldest first.
+    pub fn done(&self) -> impl Iterator<Item = &Command> {
+        self.undo.iter()
+    }
+
+    /// Undone commands, next to redo first.
+    pub fn undone(&self) -> impl Iterator<Item = &Command> {
+        self.redo.iter().rev()
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tasks(names: &[&str]) -> Vec<Task> {
+        names.iter().map(|n| Task::new(n.to_string())).collect()
+    }
+
+    fn names(tasks: &[Task]) -> Vec<&str> {
+        tasks.iter().map(|t| t.name.as_str()).collect()
+    }
+
+    fn rename(tasks: &[Task], index: usize, name: &str) -> Command {
+        let mut after = tasks[index].clone();
+        after.name = name.to_string();
+        Command::Edit { index, before: Box::new(tasks[index].clone()), after: Box::new(after) }
+    }
+
+    #[test]
+    fn test_undo_redo_round_trip() {
+        let mut list = tasks(&["A", "B", "C"]);
+        let mut history = History::default();
+
+        history.execute(&mut list, Command::Reorder { order: vec![1, 0, 2] });
+        let removed = vec![(0, list[0].clone()), (2, list[2].clone())];
+        history.execute(&mut list, Command::Delete { removed });
+        assert_eq!(names(&list), ["A"]);
+
+        assert!(history.undo(&mut list));
+        assert_eq!(names(&list), ["B", "A", "C"]);
+        assert!(history.undo(&mut list));
+        assert_eq!(names(&list), ["A", "B", "C"]);
+        assert!(!history.undo(&mut list));
+
+        assert!(history.redo(&mut list));
+        assert_eq!(names(&list), ["B", "A", "C"]);
+
+        // A new command discards what was undone
+        let command = rename(&list, 0, "B2");
+        history.execute(&mut list, command);
+        assert!(!history.can_redo());
+
+        history.execute(&mut list, Command::Insert { index: 1, tasks: tasks(&["X", "Y"]) });
+        assert_eq!(names(&list), ["B2", "X", "Y", "A", "C"]);
+        assert!(history.undo(&mut list));
+        assert_eq!(names(&list), ["B2", "A", "C"]);
+    }
+
+    #[test]
+    fn test_edits_coalesce_until_sealed() {
+        let mut list = tasks(&["A"]);
+        let mut history = History::default();
+        for name in ["Ab", "Abc"] {
+            let command = rename(&list, 0, name);
+            history.execute(&mut list, command);
+        }
+        history.seal();
+        let command = rename(&list, 0, "Abcd");
+        history.execute(&mut list, command);
+
+        assert_eq!(history.done().count(), 2);
+        history.undo(&mut list);
+        assert_eq!(list[0].name, "Abc");
+        history.undo(&mut list);
+        assert_eq!(list[0].name, "A");
+    }
+}
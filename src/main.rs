@@ -2,20 +2,56 @@ use eframe::egui;
 use std::path::{Path, PathBuf};
 use std::collections::HashSet;
 
+mod bulk_edit;
+mod chart;
+mod cli;
+mod critical_path;
+mod dates;
+mod dependency;
+mod diagnostics;
+mod diff;
+mod duration;
+mod earned_value;
+mod filter;
+mod filter_view;
+mod error;
 mod task;
 mod file_import;
+mod file_import_gan;
+mod file_import_p6;
+mod file_import_planner;
 mod file_export;
+mod file_export_ics;
+mod file_export_pdf;
+mod file_export_png;
+mod file_export_svg;
 mod file_export_xml;
+mod gantt_view;
+mod history;
 mod mspdi;
 mod merge;
+mod notifications;
+mod outline;
+mod priority;
+mod registry;
+mod session;
+mod status;
 
-use task::Task;
-use file_import::{import_xlsx, import_mpp};
-use file_export::export_to_xlsx;
-use file_export_xml::export_to_xml;
+use task::{at_finish, default_finish_time, default_start_time, format_moment, Origin, Predecessor, Task};
+use history::{Command, History};
+use chart::TimeScale;
+use registry::{ExportOptions, ImportOptions, Importer, Registry};
+use session::Session;
+use serde::{Deserialize, Serialize};
 use egui_extras::{TableBuilder, Column};
 
 fn main() -> Result<(), eframe::Error> {
+    // A known subcommand switches to the command line interface
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().is_some_and(|arg| cli::is_command(arg)) {
+        std::process::exit(cli::run(&args));
+    }
+
     let options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
             .with_inner_size([1200.0, 800.0])
@@ -30,16 +66,36 @@ fn main() -> Result<(), eframe::Error> {
     )
 }
 
-#[derive(PartialEq)]
+/// Seconds between autosaves of the running session.
+const AUTOSAVE_INTERVAL: f64 = 30.0;
+
+#[derive(PartialEq, Clone, Copy, Serialize, Deserialize)]
 enum WizardStep {
     SelectFiles,
     Review,
     Export,
+    Compare,
+}
+
+/// Review-step actions that create tasks.
+#[derive(PartialEq, Clone, Copy)]
+enum NewTask {
+    Task,
+    Subtask,
+    Milestone,
+    Duplicate,
 }
 
 struct MsProjectMergerApp {
+    registry: Registry,
     tasks: Vec<Task>,
-    file_paths: Vec<PathBuf>,
+    /// Imported files in merge order, with the tasks as they were read.
+    files: Vec<merge::LoadedFile>,
+    /// The file list changed since the last merge.
+    merge_stale: bool,
+    merge_options: merge::MergeOptions,
+    /// Used for files added from now on; each file keeps its own copy.
+    import_options: ImportOptions,
     selected_tasks: HashSet<usize>, // Changed type to HashSet
     sort_column: Option<usize>,
     sort_ascending: bool,
@@ -47,10 +103,48 @@ struct MsProjectMergerApp {
     show_import_dialog: bool,
     show_export_dialog: bool,
     export_path: String,
+    export_options: ExportOptions,
     current_step: WizardStep,
     export_success_msg: Option<String>,
     selected_task_for_edit: Option<usize>,
     edit_panel_open: bool,
+    show_gantt: bool,
+    gantt: gantt_view::GanttState,
+    /// Shared by the task table and the timeline so their rows line up.
+    task_scroll: f32,
+    history: History,
+    show_history: bool,
+    session_path: Option<PathBuf>,
+    autosave_path: Option<PathBuf>,
+    last_autosave: f64,
+    last_autosave_json: String,
+    /// The last autosave failed and that was already reported.
+    autosave_failing: bool,
+    recovered_session: Option<Session>,
+    diff: Option<diff::ProjectDiff>,
+    diff_files: (usize, usize),
+    diff_hidden: HashSet<diff::ChangeKind>,
+    /// Only show tasks with a field from this file.
+    source_filter: Option<usize>,
+    notifications: notifications::Notifications,
+    show_diagnostics: bool,
+    /// Links the last merge had to drop.
+    merge_warnings: Vec<diagnostics::Diagnostic>,
+    /// Summary tasks folded in the tree, by `tree_key`.
+    collapsed: HashSet<String>,
+    filter: filter::Filter,
+    show_filter_builder: bool,
+    saved_views: Vec<filter::SavedView>,
+    new_view_name: String,
+    /// Search text and pick for the edit panel's "add predecessor" row.
+    dependency_search: String,
+    dependency_candidate: Option<i32>,
+    /// Why the last dependency edit was rejected.
+    dependency_error: Option<String>,
+    show_bulk_edit: bool,
+    bulk_edit: bulk_edit::BulkEdit,
+    /// Adds planned/earned/actual value columns to the review table.
+    show_earned_value: bool,
 }
 
 impl MsProjectMergerApp {
@@ -94,13 +188,25 @@ impl MsProjectMergerApp {
         style.spacing.item_spacing = egui::vec2(8.0, 8.0);
         cc.egui_ctx.set_style(style);
 
+        // A leftover autosave means the last run didn't finish cleanly
+        let autosave_path = session::autosave_path();
+        let recovered_session = autosave_path.as_deref()
+            .filter(|p| p.exists())
+            .and_then(|p| Session::load(p).ok())
+            .filter(|s| !s.tasks.is_empty() || !s.files.is_empty());
+
         Self {
+            registry: Registry::with_builtin_formats(),
             tasks: Vec::new(),
-            file_paths: Vec::new(),
+            files: Vec::new(),
+            merge_stale: false,
+            merge_options: merge::MergeOptions::default(),
+            import_options: ImportOptions::default(),
             filter_text: String::new(),
             show_import_dialog: false,
             show_export_dialog: false,
             export_path: String::new(),
+            export_options: ExportOptions::default(),
             selected_tasks: HashSet::new(), // Initialized as HashSet
             sort_column: None,
             sort_ascending: true,
@@ -108,12 +214,58 @@ impl MsProjectMergerApp {
             export_success_msg: None,
             selected_task_for_edit: None,
             edit_panel_open: false,
+            show_gantt: true,
+            gantt: gantt_view::GanttState::default(),
+            task_scroll: 0.0,
+            history: History::default(),
+            show_history: false,
+            session_path: None,
+            autosave_path,
+            last_autosave: 0.0,
+            last_autosave_json: String::new(),
+            autosave_failing: false,
+            recovered_session,
+            diff: None,
+            diff_files: (0, 1),
+            diff_hidden: HashSet::new(),
+            source_filter: None,
+            notifications: notifications::Notifications::default(),
+            show_diagnostics: false,
+            merge_warnings: Vec::new(),
+            collapsed: HashSet::new(),
+            filter: filter::Filter::default(),
+            show_filter_builder: false,
+            saved_views: filter::load_views(),
+            new_view_name: String::new(),
+            dependency_search: String::new(),
+            dependency_candidate: None,
+            dependency_error: None,
+            show_bulk_edit: false,
+            bulk_edit: bulk_edit::BulkEdit::default(),
+            show_earned_value: false,
         }
     }
 }
 
 impl eframe::App for MsProjectMergerApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        // Text fields keep their own Ctrl+Z while focused
+        if ctx.memory(|m| m.focused().is_none()) {
+            let undo = egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::Z);
+            let redo = egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::Y);
+            let redo_shift = egui::KeyboardShortcut::new(egui::Modifiers::COMMAND | egui::Modifiers::SHIFT, egui::Key::Z);
+            // Check the shifted shortcut first; consuming Ctrl+Z would also match it
+            if ctx.input_mut(|i| i.consume_shortcut(&redo_shift) || i.consume_shortcut(&redo)) {
+                self.redo();
+            } else if ctx.input_mut(|i| i.consume_shortcut(&undo)) {
+                self.undo();
+            }
+        }
+        let save = egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::S);
+        if ctx.input_mut(|i| i.consume_shortcut(&save)) && !self.tasks.is_empty() {
+            self.save_session();
+        }
+
         egui::CentralPanel::default()
             .frame(egui::Frame::none().inner_margin(8.0).fill(ctx.style().visuals.window_fill()))
             .show(ctx, |ui| {
@@ -129,6 +281,9 @@ impl eframe::App for MsProjectMergerApp {
                             if ui.button(egui::RichText::new("➕ Add Project File").size(18.0)).clicked() {
                                 self.show_import_dialog = true;
                             }
+                            if ui.button("📂 Open Session…").clicked() {
+                                self.open_session();
+                            }
                         });
 
                         ui.add_space(20.0);
@@ -136,37 +291,27 @@ impl eframe::App for MsProjectMergerApp {
                         ui.add_space(10.0);
 
                         // List of files
-                        if !self.file_paths.is_empty() {
+                        if !self.files.is_empty() {
                             ui.label(egui::RichText::new("Selected Files:").strong());
-                            let mut to_remove = None;
-                            
-                            for (i, path) in self.file_paths.iter().enumerate() {
-                                ui.horizontal(|ui| {
-                                    let label = if i == 0 {
-                                        egui::RichText::new("Base Project").color(egui::Color32::GREEN).strong()
-                                    } else {
-                                        egui::RichText::new("Overlay").color(egui::Color32::LIGHT_BLUE)
-                                    };
-                                    ui.label(label);
-                                    ui.label(path.file_name().unwrap_or_default().to_string_lossy());
-                                    
-                                    if ui.small_button("❌").clicked() {
-                                        to_remove = Some(i);
-                                    }
-                                });
-                            }
-
-                            if let Some(idx) = to_remove {
-                                self.file_paths.remove(idx);
-                                // Remove tasks from this file
-                                self.tasks.retain(|t| t.source_file != idx);
-                                // Update source_file indices
-                                for task in &mut self.tasks {
-                                    if task.source_file > idx {
-                                        task.source_file -= 1;
-                                    }
+                            ui.label(egui::RichText::new("Drag ☰ to change precedence: later overlays win over earlier ones.")
+                                .small()
+                                .color(egui::Color32::GRAY));
+                            self.show_file_list(ui);
+                            ui.add_space(6.0);
+                            ui.horizontal(|ui| {
+                                ui.label("Actual start/finish:");
+                                let before = self.merge_options;
+                                egui::ComboBox::from_id_salt("actuals_policy")
+                                    .selected_text(self.merge_options.actuals.label())
+                                    .show_ui(ui, |ui| {
+                                        for policy in [merge::ActualsPolicy::OverlayWins, merge::ActualsPolicy::FillGaps] {
+                                            ui.selectable_value(&mut self.merge_options.actuals, policy, policy.label());
+                                        }
+                                    });
+                                if self.merge_options != before {
+                                    self.files_changed();
                                 }
-                            }
+                            });
                         } else {
                             ui.centered_and_justified(|ui| {
                                 ui.label(egui::RichText::new("No files selected yet.").italics().color(egui::Color32::GRAY));
@@ -175,13 +320,45 @@ impl eframe::App for MsProjectMergerApp {
 
                         ui.with_layout(egui::Layout::bottom_up(egui::Align::Center), |ui| {
                             ui.add_space(20.0);
-                            if ui.add_enabled(
-                                self.file_paths.len() >= 2,
+                            let warnings = self.diagnostic_count();
+                            let can_merge = self.files.iter().any(|f| f.enabled);
+                            let merged = !self.tasks.is_empty() && !self.merge_stale;
+                            if merged {
+                                // Keep edits made in Review unless the user asks for a fresh merge
+                                if ui.button(egui::RichText::new("Next: Review ➡").size(18.0)).clicked() {
+                                    self.current_step = WizardStep::Review;
+                                }
+                                if ui.button("🔄 Re-merge")
+                                    .on_hover_text("Recompute the merge from the imported files; edits can be undone")
+                                    .clicked()
+                                {
+                                    self.merge_loaded_files();
+                                    self.current_step = WizardStep::Review;
+                                }
+                            } else if ui.add_enabled(
+                                can_merge,
                                 egui::Button::new(egui::RichText::new("Next: Merge Files ➡").size(18.0))
                             ).clicked() {
                                 self.merge_loaded_files();
                                 self.current_step = WizardStep::Review;
                             }
+                            if ui.add_enabled(self.files.len() >= 2, egui::Button::new("🔍 Compare Files"))
+                                .on_hover_text("Show what changed between two files without merging")
+                                .clicked()
+                            {
+                                self.diff_files = (0, self.files.len() - 1);
+                                self.compute_diff();
+                                self.current_step = WizardStep::Compare;
+                            }
+                            if warnings > 0 {
+                                ui.horizontal(|ui| {
+                                    ui.label(egui::RichText::new(format!("⚠ {} value(s) were defaulted on import", warnings))
+                                        .color(egui::Color32::YELLOW));
+                                    if ui.button("Review").clicked() {
+                                        self.show_diagnostics = true;
+                                    }
+                                });
+                            }
                         });
                     }
                     WizardStep::Review => {
@@ -197,10 +374,55 @@ impl eframe::App for MsProjectMergerApp {
                                     self.current_step = WizardStep::Export;
                                 }
                                 ui.add(egui::TextEdit::singleline(&mut self.filter_text).hint_text("🔍 Filter tasks..."));
+                                let filters = match self.filter.conditions.len() {
+                                    0 => "🔽 Filters".to_string(),
+                                    n => format!("🔽 Filters ({})", n),
+                                };
+                                ui.toggle_value(&mut self.show_filter_builder, filters);
+                                self.show_view_menu(ui);
+                                self.show_status_menu(ui);
+                                self.show_source_filter(ui);
+                                ui.toggle_value(&mut self.show_gantt, "📊 Timeline");
+                                ui.toggle_value(&mut self.show_earned_value, "📈 Earned Value");
+                                ui.toggle_value(&mut self.show_history, "🕘 History");
+                                let warnings = self.diagnostic_count();
+                                if warnings > 0 {
+                                    ui.toggle_value(&mut self.show_diagnostics, format!("⚠ Import Warnings ({})", warnings));
+                                }
+                                if ui.button("💾 Save Session").on_hover_text("Ctrl+S").clicked() {
+                                    self.save_session();
+                                }
+                                if ui.add_enabled(self.history.can_redo(), egui::Button::new("↪ Redo"))
+                                    .on_hover_text("Ctrl+Y")
+                                    .clicked()
+                                {
+                                    self.redo();
+                                }
+                                if ui.add_enabled(self.history.can_undo(), egui::Button::new("↩ Undo"))
+                                    .on_hover_text("Ctrl+Z")
+                                    .clicked()
+                                {
+                                    self.undo();
+                                }
                             });
                         });
                         ui.separator();
+
+                        if self.show_filter_builder {
+                            egui::TopBottomPanel::top("filter_panel").show_inside(ui, |ui| {
+                                self.show_filter_panel(ui);
+                            });
+                        }
                         
+                        if self.show_history {
+                            egui::SidePanel::left("history_panel")
+                                .resizable(true)
+                                .default_width(220.0)
+                                .show_inside(ui, |ui| {
+                                    self.show_history_panel(ui);
+                                });
+                        }
+
                         // Split layout: Table on left, Edit panel on right (if open)
                         if self.edit_panel_open && self.selected_task_for_edit.is_some() {
                             egui::SidePanel::right("edit_panel")
@@ -211,10 +433,36 @@ impl eframe::App for MsProjectMergerApp {
                                 });
                         }
                         
+                        if self.show_gantt {
+                            egui::SidePanel::right("gantt_panel")
+                                .resizable(true)
+                                .default_width(520.0)
+                                .show_inside(ui, |ui| {
+                                    let rows = self.visible_task_indices();
+                                    // Table rows are spaced as well as sized
+                                    let row_height = Self::row_height(ui) + ui.spacing().item_spacing.y;
+                                    // Dragging a summary bar does nothing; it follows its children
+                                    let change = gantt_view::show_gantt(ui, &self.tasks, &rows, &mut self.gantt, row_height, &mut self.task_scroll)
+                                        .filter(|c| !outline::is_summary(&self.tasks, c.index()));
+                                    if let Some(change) = change {
+                                        let before = self.tasks[change.index()].clone();
+                                        let mut after = before.clone();
+                                        change.apply(&mut after);
+                                        after.record_changes(&before, Origin::Manual, chrono::Local::now().naive_local());
+                                        self.history.seal();
+                                        self.history.execute(&mut self.tasks, Command::Edit { index: change.index(), before: Box::new(before), after: Box::new(after) });
+                                        self.history.seal();
+                                    }
+                                });
+                        }
+                        
                         egui::CentralPanel::default().show_inside(ui, |ui| {
                             self.show_task_table(ui);
                         });
                     }
+                    WizardStep::Compare => {
+                        self.show_compare_view(ui);
+                    }
                     WizardStep::Export => {
                         ui.vertical_centered(|ui| {
                             ui.add_space(40.0);
@@ -223,16 +471,18 @@ impl eframe::App for MsProjectMergerApp {
                             ui.label("Your merged project is ready to be exported.");
                             ui.add_space(30.0);
 
-                            if ui.button(egui::RichText::new("💾 Export to MSPDI (XML)").size(20.0)).clicked() {
-                                self.export_path = "merged_project.xml".to_string(); // Default name
-                                self.show_export_dialog = true;
-                            }
-                            
-                            ui.add_space(10.0);
-                            
-                            if ui.button("Export to Excel").clicked() {
-                                self.export_path = "merged_project.xlsx".to_string();
-                                self.show_export_dialog = true;
+                            // One button per registered exporter; the default one stands out
+                            let exporters: Vec<(&str, &str)> = self.registry.exporters()
+                                .map(|e| (e.name(), e.extension()))
+                                .collect();
+                            for (i, (name, extension)) in exporters.into_iter().enumerate() {
+                                let text = egui::RichText::new(format!("💾 Export to {}", name));
+                                let text = if i == 0 { text.size(20.0) } else { text };
+                                if ui.button(text).clicked() {
+                                    self.export_path = format!("merged_project.{}", extension); // Default name
+                                    self.show_export_dialog = true;
+                                }
+                                ui.add_space(10.0);
                             }
 
                             if let Some(msg) = &self.export_success_msg {
@@ -243,8 +493,13 @@ impl eframe::App for MsProjectMergerApp {
                             ui.add_space(40.0);
                             if ui.button("🔄 Start Over").clicked() {
                                 self.tasks.clear();
-                                self.file_paths.clear();
+                                self.files.clear();
+                                self.merge_stale = false;
+                                self.merge_warnings.clear();
                                 self.selected_tasks.clear();
+                                self.history.clear();
+                                self.session_path = None;
+                                self.source_filter = None;
                                 self.current_step = WizardStep::SelectFiles;
                                 self.export_success_msg = None;
                             }
@@ -265,49 +520,886 @@ impl eframe::App for MsProjectMergerApp {
         if self.show_export_dialog {
             self.show_export_dialog(ctx);
         }
+        if self.show_diagnostics {
+            self.show_diagnostics_window(ctx);
+        }
+        if self.show_bulk_edit {
+            self.show_bulk_edit_window(ctx);
+        }
+        if self.recovered_session.is_some() {
+            self.show_recovery_dialog(ctx);
+        } else {
+            self.autosave(ctx);
+        }
+        self.notifications.show(ctx);
+    }
+
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        // A clean exit leaves nothing to recover
+        if let Some(path) = &self.autosave_path {
+            let _ = std::fs::remove_file(path);
+        }
     }
 }
 
 impl MsProjectMergerApp {
+    /// Recomputes the merge from the imported files, replacing the current
+    /// tasks as one undoable step.
     fn merge_loaded_files(&mut self) {
-        // Strategy:
-        // 1. Separate tasks by source file
-        // 2. Take file 0 as base
-        // 3. Merge file 1, then file 2, etc. into base
-        
-        if self.file_paths.is_empty() {
+        let merged = merge::merge_files(&self.files, self.merge_options);
+        let enabled = self.files.iter().filter(|f| f.enabled).count();
+        let label = format!("Merge {} files", enabled);
+        let before = self.tasks.clone();
+        self.history.execute(&mut self.tasks, Command::Replace { label, before, after: merged.tasks });
+        if !merged.dropped_links.is_empty() {
+            self.show_diagnostics = true;
+        }
+        self.merge_warnings = merged.dropped_links;
+        self.merge_stale = false;
+        self.selected_tasks.clear();
+        self.selected_task_for_edit = None;
+        self.edit_panel_open = false;
+    }
+
+    fn show_file_list(&mut self, ui: &mut egui::Ui) {
+        let mut to_remove = None;
+        let mut make_base = None;
+        let mut moved = None;
+        let mut toggled = false;
+        let mut reread = None;
+        let base = self.files.iter().position(|f| f.enabled);
+
+        for i in 0..self.files.len() {
+            let row = ui.horizontal(|ui| {
+                ui.dnd_drag_source(egui::Id::new(("file_row", i)), i, |ui| {
+                    ui.label("☰");
+                });
+                let file = &mut self.files[i];
+                toggled |= ui.checkbox(&mut file.enabled, "").on_hover_text("Include in the merge").changed();
+                let label = if !file.enabled {
+                    egui::RichText::new("Skipped").color(egui::Color32::GRAY)
+                } else if Some(i) == base {
+                    egui::RichText::new("Base Project").color(egui::Color32::GREEN).strong()
+                } else {
+                    egui::RichText::new("Overlay").color(egui::Color32::LIGHT_BLUE)
+                };
+                ui.label(label);
+                ui.label(file.file_name());
+                ui.label(egui::RichText::new(format!("{} · {} tasks", file.format, file.tasks.len()))
+                    .italics()
+                    .color(egui::Color32::GRAY));
+                if file.format == file_import::XlsxImporter.name() {
+                    let before = file.import_options;
+                    egui::ComboBox::from_id_salt(("date_locale", i))
+                        .selected_text(file.import_options.date_locale.label())
+                        .show_ui(ui, |ui| {
+                            for locale in dates::DateLocale::ALL {
+                                ui.selectable_value(&mut file.import_options.date_locale, locale, locale.label());
+                            }
+                        })
+                        .response
+                        .on_hover_text("How dates typed as text are read");
+                    if file.import_options != before {
+                        reread = Some(i);
+                    }
+                }
+                if !file.diagnostics.is_empty()
+                    && ui.small_button(egui::RichText::new(format!("⚠ {}", file.diagnostics.len())).color(egui::Color32::YELLOW))
+                        .on_hover_text("Values defaulted on import")
+                        .clicked()
+                {
+                    self.show_diagnostics = true;
+                }
+
+                if i > 0 && ui.small_button("⭐").on_hover_text("Make this the base project").clicked() {
+                    make_base = Some(i);
+                }
+                if ui.small_button("❌").clicked() {
+                    to_remove = Some(i);
+                }
+            });
+
+            let response = row.response;
+            if let Some(from) = response.dnd_release_payload::<usize>() {
+                moved = Some((*from, i));
+            } else if response.dnd_hover_payload::<usize>().is_some() {
+                ui.painter().hline(response.rect.x_range(), response.rect.bottom(), ui.visuals().selection.stroke);
+            }
+        }
+
+        if let Some((from, to)) = moved.filter(|(from, to)| from != to) {
+            let file = self.files.remove(from);
+            self.files.insert(to, file);
+            self.files_changed();
+        } else if let Some(i) = make_base {
+            let mut file = self.files.remove(i);
+            file.enabled = true;
+            self.files.insert(0, file);
+            self.files_changed();
+        } else if let Some(i) = to_remove {
+            self.files.remove(i);
+            self.files_changed();
+        } else if let Some(i) = reread {
+            self.reread_file(i);
+        } else if toggled {
+            self.files_changed();
+        }
+    }
+
+    /// Imports file `i` again with its current import options, e.g. after
+    /// its date locale changed.
+    fn reread_file(&mut self, i: usize) {
+        let file = &self.files[i];
+        match self.registry.import_file(&file.path, &file.import_options) {
+            Ok(mut reread) => {
+                for task in &mut reread.tasks {
+                    task.source_file = i;
+                }
+                reread.enabled = file.enabled;
+                if !reread.diagnostics.is_empty() {
+                    self.show_diagnostics = true;
+                }
+                self.files[i] = reread;
+            }
+            Err(e) => self.notifications.file_error("Import", &e),
+        }
+        self.files_changed();
+    }
+
+    /// Defaulted values across the files that take part in the merge, plus
+    /// the links the last merge dropped.
+    fn diagnostic_count(&self) -> usize {
+        let imported: usize = self.files.iter().filter(|f| f.enabled).map(|f| f.diagnostics.len()).sum();
+        imported + self.merge_warnings.len()
+    }
+
+    fn show_bulk_edit_window(&mut self, ctx: &egui::Context) {
+        let mut open = !self.selected_tasks.is_empty();
+        let mut apply = false;
+        let edit = &mut self.bulk_edit;
+        egui::Window::new(format!("Edit {} Tasks", self.selected_tasks.len()))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label("Tick the fields to change on every selected task:");
+                ui.add_space(5.0);
+                egui::Grid::new("bulk_edit_grid").num_columns(2).spacing([12.0, 8.0]).show(ui, |ui| {
+                    let mut on = edit.status.is_some();
+                    if ui.checkbox(&mut on, "Status").changed() {
+                        edit.status = on.then_some(crate::task::TaskStatus::InProgress);
+                    }
+                    if let Some(status) = &mut edit.status {
+                        egui::ComboBox::from_id_salt("bulk_status")
+                            .selected_text(format!("{:?}", status))
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(status, crate::task::TaskStatus::NotStarted, "NotStarted");
+                                ui.selectable_value(status, crate::task::TaskStatus::InProgress, "InProgress");
+                                ui.selectable_value(status, crate::task::TaskStatus::Completed, "Completed");
+                                ui.selectable_value(status, crate::task::TaskStatus::OnHold, "OnHold");
+                                ui.selectable_value(status, crate::task::TaskStatus::Cancelled, "Cancelled");
+                            });
+                    }
+                    ui.end_row();
+
+                    let mut on = edit.assignee.is_some();
+                    if ui.checkbox(&mut on, "Assignee").changed() {
+                        edit.assignee = on.then(String::new);
+                    }
+                    if let Some(assignee) = &mut edit.assignee {
+                        ui.add(egui::TextEdit::singleline(assignee).hint_text("Blank to unassign"));
+                    }
+                    ui.end_row();
+
+                    let mut on = edit.shift_days.is_some();
+                    if ui.checkbox(&mut on, "Shift dates").changed() {
+                        edit.shift_days = on.then_some(1);
+                    }
+                    if let Some(days) = &mut edit.shift_days {
+                        ui.add(egui::DragValue::new(days).range(-365..=365).suffix(" working days"));
+                    }
+                    ui.end_row();
+
+                    let mut on = edit.percent_complete.is_some();
+                    if ui.checkbox(&mut on, "% Complete").changed() {
+                        edit.percent_complete = on.then_some(0);
+                    }
+                    if let Some(percent) = &mut edit.percent_complete {
+                        ui.add(egui::Slider::new(percent, 0..=100).suffix("%"));
+                    }
+                    ui.end_row();
+
+                    let mut on = edit.priority.is_some();
+                    if ui.checkbox(&mut on, "Priority").changed() {
+                        edit.priority = on.then_some(priority::DEFAULT);
+                    }
+                    if let Some(value) = &mut edit.priority {
+                        let level = priority::label(*value);
+                        ui.add(egui::Slider::new(value, 0..=priority::MAX).text(level));
+                    }
+                    ui.end_row();
+
+                    let mut on = edit.note.is_some();
+                    if ui.checkbox(&mut on, "Append note").changed() {
+                        edit.note = on.then(String::new);
+                    }
+                    if let Some(note) = &mut edit.note {
+                        ui.add(egui::TextEdit::multiline(note).desired_rows(2));
+                    }
+                    ui.end_row();
+                });
+                ui.label(egui::RichText::new("Summary tasks keep the dates and progress rolled up from their subtasks.").small().color(egui::Color32::GRAY));
+                ui.add_space(5.0);
+                ui.horizontal(|ui| {
+                    if ui.add_enabled(!edit.is_empty(), egui::Button::new("Apply")).clicked() {
+                        apply = true;
+                    }
+                    if ui.button("Reset").clicked() {
+                        *edit = bulk_edit::BulkEdit::default();
+                    }
+                });
+            });
+        if apply {
+            self.apply_bulk_edit();
+            open = false;
+        }
+        if !open {
+            self.show_bulk_edit = false;
+        }
+    }
+
+    /// Applies the bulk edit to every selected task as a single undo step.
+    fn apply_bulk_edit(&mut self) {
+        let now = chrono::Local::now().naive_local();
+        let mut indices: Vec<usize> = self.selected_tasks.iter().copied().filter(|&i| i < self.tasks.len()).collect();
+        indices.sort_unstable();
+        let commands: Vec<Command> = indices.iter().map(|&index| {
+            let before = self.tasks[index].clone();
+            let mut after = before.clone();
+            self.bulk_edit.apply(&mut after, outline::is_summary(&self.tasks, index));
+            status::reconcile(&mut after, &before);
+            after.record_changes(&before, Origin::Manual, now);
+            Command::Edit { index, before: Box::new(before), after: Box::new(after) }
+        }).collect();
+        if commands.is_empty() {
+            return;
+        }
+        let label = format!("Bulk edit {} tasks", commands.len());
+        self.history.seal();
+        self.history.execute(&mut self.tasks, Command::Group { label, commands });
+    }
+
+    fn show_diagnostics_window(&mut self, ctx: &egui::Context) {
+        let mut open = true;
+        egui::Window::new("Import Warnings")
+            .open(&mut open)
+            .resizable(true)
+            .default_width(640.0)
+            .show(ctx, |ui| {
+                ui.label("These values couldn't be read as found and were replaced or dropped:");
+                egui::ScrollArea::vertical().max_height(420.0).show(ui, |ui| {
+                    for (i, file) in self.files.iter().enumerate().filter(|(_, f)| !f.diagnostics.is_empty()) {
+                        let mut title = format!("{} ({})", file.file_name(), file.diagnostics.len());
+                        if !file.enabled {
+                            title.push_str(" · skipped");
+                        }
+                        egui::CollapsingHeader::new(title)
+                            .id_salt(("diagnostics", i))
+                            .default_open(true)
+                            .show(ui, |ui| Self::diagnostics_grid(ui, ("diagnostics_grid", i), &file.diagnostics));
+                    }
+                    if !self.merge_warnings.is_empty() {
+                        egui::CollapsingHeader::new(format!("Merge ({})", self.merge_warnings.len()))
+                            .id_salt("merge_diagnostics")
+                            .default_open(true)
+                            .show(ui, |ui| Self::diagnostics_grid(ui, "merge_diagnostics_grid", &self.merge_warnings));
+                    }
+                });
+            });
+        if !open {
+            self.show_diagnostics = false;
+        }
+    }
+
+    fn diagnostics_grid(ui: &mut egui::Ui, id: impl std::hash::Hash, diagnostics: &[diagnostics::Diagnostic]) {
+        egui::Grid::new(id)
+            .striped(true)
+            .num_columns(4)
+            .show(ui, |ui| {
+                for header in ["Row / UID", "Column", "Found", "Used"] {
+                    ui.label(egui::RichText::new(header).strong());
+                }
+                ui.end_row();
+                for diagnostic in diagnostics {
+                    ui.label(&diagnostic.record);
+                    ui.label(&diagnostic.column);
+                    if diagnostic.raw.is_empty() {
+                        ui.label(egui::RichText::new("blank").italics().color(egui::Color32::GRAY));
+                    } else {
+                        ui.label(&diagnostic.raw);
+                    }
+                    ui.label(egui::RichText::new(&diagnostic.substituted).color(egui::Color32::YELLOW));
+                    ui.end_row();
+                }
+            });
+    }
+
+    /// File indices in the current tasks' provenance no longer line up, so
+    /// the merge has to be redone before review.
+    fn files_changed(&mut self) {
+        self.merge_stale = true;
+        self.source_filter = None;
+    }
+
+    fn to_session(&self) -> Session {
+        let mut session = Session::new(self.current_step);
+        session.files = self.files.clone();
+        session.merge_stale = self.merge_stale;
+        session.tasks = self.tasks.clone();
+        session.history = self.history.clone();
+        session.filter_text = self.filter_text.clone();
+        session.filter = self.filter.clone();
+        session.sort_column = self.sort_column;
+        session.sort_ascending = self.sort_ascending;
+        session.export_options = self.export_options.clone();
+        session.status_rules = self.registry.status_rules.clone();
+        session.merge_options = self.merge_options;
+        session.import_options = self.import_options;
+        session
+    }
+
+    fn restore_session(&mut self, session: Session) {
+        self.files = session.files;
+        self.merge_stale = session.merge_stale;
+        self.tasks = session.tasks;
+        self.history = session.history;
+        self.merge_warnings.clear();
+        self.current_step = session.step;
+        self.filter_text = session.filter_text;
+        self.filter = session.filter;
+        self.sort_column = session.sort_column;
+        self.sort_ascending = session.sort_ascending;
+        self.export_options = session.export_options;
+        self.registry.status_rules = session.status_rules;
+        self.merge_options = session.merge_options;
+        self.import_options = session.import_options;
+        if self.current_step == WizardStep::Compare {
+            self.compute_diff();
+        }
+        self.selected_tasks.clear();
+        self.selected_task_for_edit = None;
+        self.edit_panel_open = false;
+        self.export_success_msg = None;
+    }
+
+    fn save_session(&mut self) {
+        let path = match &self.session_path {
+            Some(path) => Some(path.clone()),
+            None => rfd::FileDialog::new()
+                .add_filter("Merge Session", &[session::EXTENSION])
+                .set_file_name(format!("merge.{}", session::EXTENSION))
+                .save_file(),
+        };
+        let Some(path) = path else { return };
+        match self.to_session().save(&path) {
+            Ok(()) => {
+                self.notifications.success(format!("Session saved to {}", path.display()));
+                self.session_path = Some(path);
+            }
+            Err(e) => self.notifications.error("Save session", Some(&path), e),
+        }
+    }
+
+    fn open_session(&mut self) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("Merge Session", &[session::EXTENSION])
+            .pick_file()
+        else {
+            return;
+        };
+        match Session::load(&path) {
+            Ok(session) => {
+                self.restore_session(session);
+                self.session_path = Some(path);
+            }
+            Err(e) => self.notifications.error("Open session", Some(&path), e),
+        }
+    }
+
+    /// Writes the session to the autosave file every `AUTOSAVE_INTERVAL` if it changed.
+    fn autosave(&mut self, ctx: &egui::Context) {
+        let Some(path) = self.autosave_path.clone() else { return };
+        let now = ctx.input(|i| i.time);
+        if now - self.last_autosave < AUTOSAVE_INTERVAL {
+            ctx.request_repaint_after(std::time::Duration::from_secs_f64(AUTOSAVE_INTERVAL - (now - self.last_autosave)));
+            return;
+        }
+        self.last_autosave = now;
+
+        if self.tasks.is_empty() && self.files.is_empty() {
             return;
         }
+        // saved_at changes every time, so compare without it
+        let mut session = self.to_session();
+        session.saved_at = chrono::DateTime::default();
+        let Ok(json) = session.to_json() else { return };
+        if json != self.last_autosave_json {
+            session.saved_at = chrono::Local::now();
+            match session.save(&path) {
+                Ok(()) => {
+                    self.last_autosave_json = json;
+                    self.autosave_failing = false;
+                }
+                // Retried every interval, so only the first failure is reported
+                Err(e) if !self.autosave_failing => {
+                    self.notifications.error("Autosave", Some(&path), e);
+                    self.autosave_failing = true;
+                }
+                Err(_) => {}
+            }
+        }
+    }
+
+    fn show_recovery_dialog(&mut self, ctx: &egui::Context) {
+        let Some(session) = &self.recovered_session else { return };
+        let mut restore = false;
+        let mut discard = false;
 
-        let mut base_tasks: Vec<Task> = self.tasks.iter()
-            .filter(|t| t.source_file == 0)
-            .cloned()
+        egui::Window::new("Recover Session")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                ui.label("The last session did not close normally.");
+                ui.label(format!(
+                    "Autosaved {} with {} file(s) and {} task(s).",
+                    session.saved_at.format("%Y-%m-%d %H:%M"),
+                    session.files.len(),
+                    session.tasks.len()
+                ));
+                ui.add_space(10.0);
+                ui.horizontal(|ui| {
+                    restore = ui.button(egui::RichText::new("Restore").strong()).clicked();
+                    discard = ui.button("Discard").clicked();
+                });
+            });
+
+        if restore {
+            if let Some(session) = self.recovered_session.take() {
+                self.restore_session(session);
+            }
+        } else if discard {
+            self.recovered_session = None;
+            if let Some(path) = &self.autosave_path {
+                let _ = std::fs::remove_file(path);
+            }
+        }
+    }
+
+    /// Compares the two chosen files as imported, independent of any merge or edits.
+    fn compute_diff(&mut self) {
+        let (old, new) = self.diff_files;
+        self.diff = match (self.files.get(old), self.files.get(new)) {
+            (Some(old), Some(new)) => Some(diff::diff_tasks(&old.tasks, &new.tasks)),
+            _ => None,
+        };
+    }
+
+    fn file_name(&self, index: usize) -> String {
+        self.files.get(index).map(|f| f.file_name()).unwrap_or_default()
+    }
+
+    fn show_compare_view(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            if ui.button("⬅ Back").clicked() {
+                self.current_step = WizardStep::SelectFiles;
+            }
+            ui.heading("Compare Schedules");
+        });
+        ui.separator();
+
+        let before = self.diff_files;
+        ui.horizontal(|ui| {
+            for (label, id, index) in [("Old:", "diff_old", &mut self.diff_files.0), ("New:", "diff_new", &mut self.diff_files.1)] {
+                ui.label(label);
+                egui::ComboBox::from_id_salt(id)
+                    .selected_text(self.files.get(*index).map(|f| f.file_name()).unwrap_or_default())
+                    .show_ui(ui, |ui| {
+                        for (i, file) in self.files.iter().enumerate() {
+                            ui.selectable_value(index, i, file.file_name());
+                        }
+                    });
+            }
+        });
+        if self.diff_files != before {
+            self.compute_diff();
+        }
+
+        let Some(diff) = &self.diff else { return };
+
+        let mut export = false;
+        ui.horizontal(|ui| {
+            ui.label(egui::RichText::new(diff.summary()).strong());
+            ui.separator();
+            for kind in [diff::ChangeKind::Added, diff::ChangeKind::Removed, diff::ChangeKind::Modified] {
+                let mut shown = !self.diff_hidden.contains(&kind);
+                if ui.checkbox(&mut shown, format!("{} ({})", kind.label(), diff.count(kind))).changed() {
+                    if shown {
+                        self.diff_hidden.remove(&kind);
+                    } else {
+                        self.diff_hidden.insert(kind);
+                    }
+                }
+            }
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                export = ui.button("💾 Export Report").clicked();
+            });
+        });
+        ui.separator();
+
+        egui::ScrollArea::vertical().auto_shrink([false, false]).show(ui, |ui| {
+            for entry in diff.entries.iter().filter(|e| !self.diff_hidden.contains(&e.kind)) {
+                let color = match entry.kind {
+                    diff::ChangeKind::Added => egui::Color32::from_rgb(158, 206, 106), // #9ECE6A
+                    diff::ChangeKind::Removed => egui::Color32::from_rgb(247, 118, 142), // #F7768E
+                    diff::ChangeKind::Modified => egui::Color32::from_rgb(224, 175, 104), // #E0AF68
+                };
+                ui.horizontal(|ui| {
+                    ui.label(egui::RichText::new(entry.kind.label()).color(color).strong());
+                    ui.label(egui::RichText::new(&entry.key).color(egui::Color32::GRAY));
+                    ui.label(&entry.name);
+                });
+                if !entry.changes.is_empty() {
+                    ui.indent(("diff_changes", &entry.key), |ui| {
+                        egui::Grid::new(("diff_grid", &entry.key)).num_columns(4).show(ui, |ui| {
+                            for change in &entry.changes {
+                                ui.label(change.field);
+                                ui.label(egui::RichText::new(&change.before).strikethrough().color(egui::Color32::from_rgb(247, 118, 142)));
+                                ui.label("→");
+                                ui.label(egui::RichText::new(&change.after).color(egui::Color32::from_rgb(158, 206, 106)));
+                                ui.end_row();
+                            }
+                        });
+                    });
+                }
+                ui.add_space(4.0);
+            }
+        });
+
+        if export {
+            if let Some(path) = rfd::FileDialog::new()
+                .add_filter("Markdown", &["md"])
+                .add_filter("CSV", &["csv"])
+                .set_file_name("schedule-changes.md")
+                .save_file()
+            {
+                let is_csv = path.extension().is_some_and(|e| e.eq_ignore_ascii_case("csv"));
+                let report = if is_csv {
+                    diff.to_csv()
+                } else {
+                    diff.to_markdown(&self.file_name(self.diff_files.0), &self.file_name(self.diff_files.1))
+                };
+                if let Err(e) = std::fs::write(&path, report) {
+                    self.notifications.error("Write report", Some(&path), e);
+                }
+            }
+        }
+    }
+
+    fn undo(&mut self) {
+        if self.history.undo(&mut self.tasks) {
+            self.after_history_change();
+        }
+    }
+
+    fn redo(&mut self) {
+        if self.history.redo(&mut self.tasks) {
+            self.after_history_change();
+        }
+    }
+
+    /// Indices may now point at different tasks, so drop anything keyed by them.
+    fn after_history_change(&mut self) {
+        self.selected_tasks.clear();
+        if self.selected_task_for_edit.is_some_and(|i| i >= self.tasks.len()) {
+            self.selected_task_for_edit = None;
+            self.edit_panel_open = false;
+        }
+    }
+
+    fn show_history_panel(&mut self, ui: &mut egui::Ui) {
+        ui.heading("History");
+        ui.separator();
+
+        // Clicking an entry undoes or redoes until the list is in that state
+        let done: Vec<String> = self.history.done().map(Command::label).collect();
+        let undone: Vec<String> = self.history.undone().map(Command::label).collect();
+        let mut undo_steps = 0;
+        let mut redo_steps = 0;
+
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            if ui.selectable_label(done.is_empty(), "Initial state").clicked() {
+                undo_steps = done.len();
+            }
+            for (i, label) in done.iter().enumerate() {
+                if ui.selectable_label(i + 1 == done.len(), label).clicked() {
+                    undo_steps = done.len() - i - 1;
+                }
+            }
+            for (i, label) in undone.iter().enumerate() {
+                let text = egui::RichText::new(label).color(egui::Color32::GRAY).italics();
+                if ui.selectable_label(false, text).clicked() {
+                    redo_steps = i + 1;
+                }
+            }
+        });
+
+        for _ in 0..undo_steps {
+            self.undo();
+        }
+        for _ in 0..redo_steps {
+            self.redo();
+        }
+    }
+
+    fn origin_name(&self, origin: Origin) -> String {
+        match origin {
+            Origin::File(index) if index < self.files.len() => self.file_name(index),
+            Origin::File(index) => format!("File {}", index + 1),
+            Origin::Manual => "Manual edit".to_string(),
+        }
+    }
+
+    /// The distinct sources of a task's fields, e.g. "base.xml + overlay.xlsx".
+    fn source_summary(&self, task: &Task) -> String {
+        let mut origins: Vec<Origin> = Vec::new();
+        for (field, _) in task::TRACKED_FIELDS {
+            let origin = task.field_origin(field);
+            if !origins.contains(&origin) {
+                origins.push(origin);
+            }
+        }
+        origins.into_iter().map(|o| self.origin_name(o)).collect::<Vec<_>>().join(" + ")
+    }
+
+    /// One line per tracked field: where its value came from and when.
+    fn source_details(&self, task: &Task) -> String {
+        task::TRACKED_FIELDS.iter()
+            .map(|(field, label)| match task.provenance.get(*field) {
+                Some(source) => format!(
+                    "{}: {} ({})",
+                    label,
+                    self.origin_name(source.origin),
+                    source.at.format("%Y-%m-%d %H:%M")
+                ),
+                None => format!("{}: {} (imported)", label, self.origin_name(Origin::File(task.source_file))),
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn show_source_filter(&mut self, ui: &mut egui::Ui) {
+        let selected = match self.source_filter {
+            Some(index) => format!("Touched by {}", self.origin_name(Origin::File(index))),
+            None => "All sources".to_string(),
+        };
+        let names: Vec<String> = self.files.iter().map(|f| f.file_name()).collect();
+        egui::ComboBox::from_id_salt("source_filter")
+            .selected_text(selected)
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut self.source_filter, None, "All sources");
+                for (i, name) in names.into_iter().enumerate() {
+                    let label = if i == 0 { format!("{} (base)", name) } else { name };
+                    ui.selectable_value(&mut self.source_filter, Some(i), label);
+                }
+            });
+    }
+
+    fn show_filter_panel(&mut self, ui: &mut egui::Ui) {
+        let mut assignees: Vec<String> = self.tasks.iter()
+            .flat_map(file_export_ics::assignees)
+            .map(str::to_string)
             .collect();
+        assignees.sort_unstable_by_key(|n| n.to_lowercase());
+        assignees.dedup_by(|a, b| a.eq_ignore_ascii_case(b));
+        let choices = filter_view::FilterChoices {
+            assignees,
+            files: self.files.iter().map(|f| f.file_name()).collect(),
+            today: chrono::Local::now().date_naive(),
+        };
+        filter_view::show_filter_builder(ui, &mut self.filter, &choices);
+
+        ui.horizontal(|ui| {
+            ui.label("Save as view:");
+            ui.add(egui::TextEdit::singleline(&mut self.new_view_name).desired_width(160.0).hint_text("View name"));
+            let name = self.new_view_name.trim().to_string();
+            let exists = self.saved_views.iter().any(|v| v.name == name);
+            let label = if exists { "💾 Replace View" } else { "💾 Save View" };
+            if ui.add_enabled(!name.is_empty(), egui::Button::new(label)).clicked() {
+                let view = filter::SavedView {
+                    name: name.clone(),
+                    filter: self.filter.clone(),
+                    filter_text: self.filter_text.clone(),
+                    sort_column: self.sort_column,
+                    sort_ascending: self.sort_ascending,
+                };
+                match self.saved_views.iter_mut().find(|v| v.name == name) {
+                    Some(existing) => *existing = view,
+                    None => self.saved_views.push(view),
+                }
+                self.store_views();
+                self.new_view_name.clear();
+            }
+        });
+        ui.add_space(4.0);
+    }
+
+    /// Status rules and fixes for statuses that contradict progress.
+    fn show_status_menu(&mut self, ui: &mut egui::Ui) {
+        let conflicts = self.status_conflicts();
+        let title = match conflicts.len() {
+            0 => "🚦 Status".to_string(),
+            n => format!("🚦 Status ({} ⚠)", n),
+        };
+        ui.menu_button(title, |ui| {
+            let rules = &mut self.registry.status_rules;
+            ui.checkbox(&mut rules.percent_wins, "% complete overrides a contradicting status on import");
+            ui.horizontal(|ui| {
+                let mut fixed = rules.status_date.is_some();
+                if ui.checkbox(&mut fixed, "Status date").on_hover_text("Judge lateness against this date instead of today").changed() {
+                    rules.status_date = fixed.then(|| chrono::Local::now().date_naive());
+                }
+                if let Some(date) = &mut rules.status_date {
+                    filter_view::date_field(ui, "status_date", date);
+                }
+            });
+            ui.horizontal(|ui| {
+                ui.label("MS Project status field:").on_hover_text("Custom field read for On Hold and Cancelled on import");
+                let selected = crate::mspdi::TEXT_FIELD_IDS.iter()
+                    .find(|(id, _)| *id == rules.status_field)
+                    .map_or_else(|| rules.status_field.to_string(), |(_, name)| name.to_string());
+                egui::ComboBox::from_id_salt("status_field")
+                    .selected_text(selected)
+                    .show_ui(ui, |ui| {
+                        for (id, name) in crate::mspdi::TEXT_FIELD_IDS {
+                            ui.selectable_value(&mut rules.status_field, id, name);
+                        }
+                    });
+            });
+            ui.separator();
+            if ui.add_enabled(!conflicts.is_empty(), egui::Button::new(format!("Set {} status(es) from % complete", conflicts.len()))).clicked() {
+                self.fix_status_conflicts(&conflicts);
+                ui.close_menu();
+            }
+        });
+    }
+
+    /// Leaf tasks whose status contradicts their percent complete.
+    fn status_conflicts(&self) -> Vec<usize> {
+        (0..self.tasks.len())
+            .filter(|&i| !outline::is_summary(&self.tasks, i) && status::conflict(&self.tasks[i]).is_some())
+            .collect()
+    }
 
-        for i in 1..self.file_paths.len() {
-            let overlay_tasks: Vec<Task> = self.tasks.iter()
-                .filter(|t| t.source_file == i)
-                .cloned()
-                .collect();
-            
-            crate::merge::merge_projects(&mut base_tasks, &overlay_tasks);
+    fn fix_status_conflicts(&mut self, indices: &[usize]) {
+        let now = chrono::Local::now().naive_local();
+        let commands: Vec<Command> = indices.iter().map(|&index| {
+            let before = self.tasks[index].clone();
+            let mut after = before.clone();
+            after.status = status::from_percent(after.percent_complete);
+            after.record_changes(&before, Origin::Manual, now);
+            Command::Edit { index, before: Box::new(before), after: Box::new(after) }
+        }).collect();
+        let label = format!("Fix {} statuses", commands.len());
+        self.history.seal();
+        self.history.execute(&mut self.tasks, Command::Group { label, commands });
+    }
+
+    /// Applies or deletes saved views.
+    fn show_view_menu(&mut self, ui: &mut egui::Ui) {
+        let mut apply = None;
+        let mut delete = None;
+        ui.menu_button("👁 Views", |ui| {
+            if self.saved_views.is_empty() {
+                ui.label(egui::RichText::new("No saved views yet. Open Filters to save one.").italics().color(egui::Color32::GRAY));
+            }
+            for (i, view) in self.saved_views.iter().enumerate() {
+                ui.horizontal(|ui| {
+                    if ui.button(&view.name).clicked() {
+                        apply = Some(i);
+                        ui.close_menu();
+                    }
+                    if ui.small_button("🗑").on_hover_text("Delete this view").clicked() {
+                        delete = Some(i);
+                    }
+                });
+            }
+        });
+
+        if let Some(i) = apply {
+            let view = self.saved_views[i].clone();
+            self.filter = view.filter;
+            self.filter_text = view.filter_text;
+            self.sort_column = view.sort_column;
+            self.sort_ascending = view.sort_ascending;
+        } else if let Some(i) = delete {
+            self.saved_views.remove(i);
+            self.store_views();
         }
-        
-        // Replace all tasks with the merged result
-        // Note: We lose the source_file distinction for the merged result, 
-        // essentially everything becomes "Base" (source 0) or we could mark them differently.
-        // For now, let's just update the main list.
-        self.tasks = base_tasks;
-        
-        // Optional: Clear other files from the list since they are merged?
-        // Or keep them? Let's keep them but maybe update the UI to show they are merged.
-        // Actually, simpler to just replace the view with the merged result.
     }
-    fn show_task_table(&mut self, ui: &mut egui::Ui) {
-        let mut filtered_tasks: Vec<(usize, Task)> = self
+
+    fn store_views(&mut self) {
+        if let Err(e) = filter::save_views(&self.saved_views) {
+            self.notifications.error("Save views", None, e);
+        }
+    }
+
+    /// The outline only makes sense in file order with every task present;
+    /// sorting or filtering falls back to a flat list.
+    fn tree_mode(&self) -> bool {
+        self.sort_column.is_none()
+            && self.filter_text.is_empty()
+            && self.filter.is_empty()
+            && self.source_filter.is_none()
+    }
+
+    /// Identifies a task across reorders; WBS codes are the stable key.
+    fn tree_key(task: &Task) -> String {
+        if task.wbs.is_empty() {
+            format!("uid:{}", task.uid)
+        } else {
+            task.wbs.clone()
+        }
+    }
+
+    /// Indices of the tasks that pass the filter, in the table's sort order.
+    /// In tree mode, children of collapsed summaries are left out.
+    fn visible_task_indices(&self) -> Vec<usize> {
+        if self.tree_mode() {
+            let mut rows = Vec::new();
+            let mut i = 0;
+            while i < self.tasks.len() {
+                rows.push(i);
+                i = if self.collapsed.contains(&Self::tree_key(&self.tasks[i])) {
+                    outline::subtree_end(&self.tasks, i)
+                } else {
+                    i + 1
+                };
+            }
+            return rows;
+        }
+
+        let context = filter::FilterContext {
+            today: self.registry.status_rules.status_date(),
+            conflicts: if self.filter.needs_conflicts() { filter::conflicting_wbs(&self.files) } else { HashSet::new() },
+            files: self.files.iter().map(|f| f.file_name()).collect(),
+        };
+        let mut filtered_tasks: Vec<(usize, &Task)> = self
             .tasks
             .iter()
             .enumerate()
+            .filter(|(_, task)| self.source_filter.is_none_or(|file| task.touched_by(file)))
+            .filter(|(_, task)| self.filter.matches(task, &context))
             .filter(|(_, task)| {
                 if self.filter_text.is_empty() {
                     true
@@ -318,7 +1410,6 @@ impl MsProjectMergerApp {
                         || task.assignee.to_lowercase().contains(&filter)
                 }
             })
-            .map(|(i, t)| (i, t.clone()))
             .collect();
 
         // Apply sorting
@@ -326,8 +1417,8 @@ impl MsProjectMergerApp {
             filtered_tasks.sort_by(|a, b| {
                 let cmp = match col {
                     0 => a.1.name.cmp(&b.1.name),
-                    1 => a.1.start_date.cmp(&b.1.start_date),
-                    2 => a.1.end_date.cmp(&b.1.end_date),
+                    1 => a.1.start.cmp(&b.1.start),
+                    2 => a.1.finish.cmp(&b.1.finish),
                     3 => a.1.status.cmp(&b.1.status),
                     4 => a.1.priority.cmp(&b.1.priority),
                     _ => std::cmp::Ordering::Equal,
@@ -340,19 +1431,101 @@ impl MsProjectMergerApp {
             });
         }
 
-        let text_height = egui::TextStyle::Body.resolve(ui.style()).size + 8.0; // Increased row height
+        filtered_tasks.into_iter().map(|(i, _)| i).collect()
+    }
+
+    fn row_height(ui: &egui::Ui) -> f32 {
+        egui::TextStyle::Body.resolve(ui.style()).size + 8.0 // Increased row height
+    }
+
+    fn show_task_table(&mut self, ui: &mut egui::Ui) {
+        let filtered_tasks: Vec<(usize, Task)> = self
+            .visible_task_indices()
+            .into_iter()
+            .map(|i| (i, self.tasks[i].clone()))
+            .collect();
+
+        let text_height = Self::row_height(ui);
+        let tree = self.tree_mode();
+        let status_date = self.registry.status_rules.status_date();
+        let ev_basis = earned_value::Basis::for_tasks(&self.tasks);
+        let ev_columns = if self.show_earned_value { 5 } else { 0 };
+
+        ui.horizontal(|ui| {
+            let anchor = self.insert_anchor();
+            if ui.small_button("➕ Task").on_hover_text("Add a task after the selected one").clicked() {
+                self.add_task(NewTask::Task);
+            }
+            let has_wbs = anchor.is_some_and(|a| !self.tasks[a].wbs.is_empty());
+            if ui.add_enabled(has_wbs, egui::Button::new("➕ Subtask").small())
+                .on_hover_text("Add a task under the selected one")
+                .on_disabled_hover_text("Select one task with a WBS code")
+                .clicked()
+            {
+                self.add_task(NewTask::Subtask);
+            }
+            if ui.small_button("◆ Milestone").on_hover_text("Add a milestone after the selected task").clicked() {
+                self.add_task(NewTask::Milestone);
+            }
+            if ui.add_enabled(anchor.is_some(), egui::Button::new("⧉ Duplicate").small())
+                .on_hover_text("Copy the selected task with its subtasks")
+                .on_disabled_hover_text("Select one task")
+                .clicked()
+            {
+                self.add_task(NewTask::Duplicate);
+            }
+            ui.separator();
+
+            if tree {
+                if ui.small_button("⊞ Expand All").clicked() {
+                    self.collapsed.clear();
+                }
+                if ui.small_button("⊟ Collapse All").clicked() {
+                    self.collapsed = (0..self.tasks.len())
+                        .filter(|&i| outline::is_summary(&self.tasks, i))
+                        .map(|i| Self::tree_key(&self.tasks[i]))
+                        .collect();
+                }
+            } else {
+                ui.label(egui::RichText::new("Showing a flat list while sorted or filtered.").small().color(egui::Color32::GRAY));
+                if self.sort_column.is_some() && ui.small_button("Clear sort").clicked() {
+                    self.sort_column = None;
+                }
+            }
 
-        TableBuilder::new(ui)
+            if self.show_earned_value {
+                ui.separator();
+                let totals = earned_value::totals(&self.tasks, status_date, ev_basis);
+                ui.label(format!(
+                    "At {} ({}): PV {:.1} · EV {:.1} · AC {:.1} · SV {:+.1} · CV {:+.1} · SPI {} · CPI {}",
+                    status_date.format("%Y-%m-%d"),
+                    ev_basis.unit(),
+                    totals.bcws,
+                    totals.bcwp,
+                    totals.acwp,
+                    totals.sv(),
+                    totals.cv(),
+                    format_index(totals.spi()),
+                    format_index(totals.cpi()),
+                ));
+            }
+        });
+
+        let scrolled = TableBuilder::new(ui)
             .striped(true)
             .resizable(true)
+            .vertical_scroll_offset(self.task_scroll)
             .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
             .column(Column::auto().resizable(false)) // Selection
             .column(Column::initial(300.0).resizable(true)) // Name
             .column(Column::initial(100.0).resizable(true)) // Start
             .column(Column::initial(100.0).resizable(true)) // End
+            .column(Column::initial(70.0).resizable(true)) // % Complete
             .column(Column::initial(120.0).resizable(true)) // Status (Wider for badge)
             .column(Column::initial(80.0).resizable(true)) // Priority
             .column(Column::initial(150.0).resizable(true)) // Assignee
+            .column(Column::initial(140.0).resizable(true)) // Source
+            .columns(Column::initial(70.0).resizable(true), ev_columns) // PV, EV, AC, SPI, CPI
             .column(Column::auto().resizable(false)) // Edit button
             .header(30.0, |mut header| { // Taller header
                 header.col(|ui| { ui.label(""); });
@@ -374,6 +1547,7 @@ impl MsProjectMergerApp {
                         self.sort_column = Some(2);
                     }
                 });
+                header.col(|ui| { ui.label(egui::RichText::new("% Done").strong()); });
                 header.col(|ui| { 
                     if ui.button(egui::RichText::new("Status").strong()).clicked() { 
                         self.sort_ascending = if self.sort_column == Some(3) { !self.sort_ascending } else { true };
@@ -387,6 +1561,18 @@ impl MsProjectMergerApp {
                     }
                 });
                 header.col(|ui| { ui.label(egui::RichText::new("Assignee").strong()); });
+                header.col(|ui| { ui.label(egui::RichText::new("Source").strong()); });
+                if ev_columns > 0 {
+                    for (title, hover) in [
+                        ("PV", "Planned value (BCWS) at the status date"),
+                        ("EV", "Earned value (BCWP)"),
+                        ("AC", "Actual cost (ACWP)"),
+                        ("SPI", "Schedule performance index, EV / PV"),
+                        ("CPI", "Cost performance index, EV / AC"),
+                    ] {
+                        header.col(|ui| { ui.label(egui::RichText::new(title).strong()).on_hover_text(hover); });
+                    }
+                }
                 header.col(|ui| { ui.label(""); });
             })
             .body(|mut body| {
@@ -402,16 +1588,42 @@ impl MsProjectMergerApp {
                                 }
                             }
                         });
-                        row.col(|ui| { ui.label(&task.name); });
-                        row.col(|ui| { ui.label(task.start_date.format("%Y-%m-%d").to_string()); });
-                        row.col(|ui| { ui.label(task.end_date.format("%Y-%m-%d").to_string()); });
+                        // Summary rows show values rolled up from their children
+                        let rollup = outline::rollup(&self.tasks, original_idx);
+                        row.col(|ui| {
+                            ui.add_space((task.outline_level() - 1) as f32 * 16.0);
+                            if tree && rollup.is_some() {
+                                let key = Self::tree_key(&task);
+                                let collapsed = self.collapsed.contains(&key);
+                                if ui.small_button(if collapsed { "⏵" } else { "⏷" }).clicked() {
+                                    if collapsed {
+                                        self.collapsed.remove(&key);
+                                    } else {
+                                        self.collapsed.insert(key);
+                                    }
+                                }
+                            }
+                            if rollup.is_some() {
+                                ui.label(egui::RichText::new(&task.name).strong());
+                            } else {
+                                ui.label(&task.name);
+                            }
+                        });
+                        let (start, end, percent) = match rollup {
+                            Some(r) => (r.start, r.finish, r.percent_complete),
+                            None => (task.start, task.finish, task.percent_complete),
+                        };
+                        row.col(|ui| { ui.label(format_moment(start, default_start_time())); });
+                        row.col(|ui| { ui.label(format_moment(end, default_finish_time())); });
+                        row.col(|ui| { ui.label(format!("{}%", percent)); });
                         row.col(|ui| { 
                             // Status Badge
                             let (bg_color, text_color, text) = match task.status {
                                 crate::task::TaskStatus::Completed => (egui::Color32::from_rgb(20, 80, 40), egui::Color32::from_rgb(150, 255, 150), "Completed"),
                                 crate::task::TaskStatus::InProgress => (egui::Color32::from_rgb(20, 60, 100), egui::Color32::from_rgb(150, 200, 255), "In Progress"),
                                 crate::task::TaskStatus::NotStarted => (egui::Color32::from_rgb(60, 60, 60), egui::Color32::from_rgb(200, 200, 200), "Not Started"),
-                                _ => (egui::Color32::GRAY, egui::Color32::WHITE, "Unknown"),
+                                crate::task::TaskStatus::OnHold => (egui::Color32::from_rgb(90, 70, 20), egui::Color32::from_rgb(255, 220, 130), "On Hold"),
+                                crate::task::TaskStatus::Cancelled => (egui::Color32::from_rgb(70, 30, 30), egui::Color32::from_rgb(220, 160, 160), "Cancelled"),
                             };
                             
                             egui::Frame::none()
@@ -421,18 +1633,53 @@ impl MsProjectMergerApp {
                                 .show(ui, |ui| {
                                     ui.label(egui::RichText::new(text).color(text_color).size(12.0));
                                 });
+                            if rollup.is_none() {
+                                if let Some(reason) = status::conflict(&task) {
+                                    ui.label(egui::RichText::new("⚠").color(egui::Color32::YELLOW))
+                                        .on_hover_text(format!("Status disagrees with progress: {}", reason));
+                                }
+                            }
+                            if status::is_late(&task, self.registry.status_rules.status_date()) {
+                                ui.label(egui::RichText::new("⏰").color(egui::Color32::from_rgb(230, 90, 90)))
+                                    .on_hover_text("Late: due before the status date and not complete");
+                            }
                         });
-                        row.col(|ui| { ui.label(task.priority.to_string()); });
+                        row.col(|ui| { ui.label(task.priority.to_string()).on_hover_text(priority::label(task.priority)); });
                         row.col(|ui| { ui.label(&task.assignee); });
+                        row.col(|ui| {
+                            ui.label(self.source_summary(&task)).on_hover_text(self.source_details(&task));
+                        });
+                        if ev_columns > 0 {
+                            let metrics = earned_value::rollup(&self.tasks, original_idx, status_date, ev_basis);
+                            row.col(|ui| { ui.label(format!("{:.1}", metrics.bcws)); });
+                            row.col(|ui| { ui.label(format!("{:.1}", metrics.bcwp)); });
+                            row.col(|ui| { ui.label(format!("{:.1}", metrics.acwp)); });
+                            for index in [metrics.spi(), metrics.cpi()] {
+                                row.col(|ui| {
+                                    let color = match index {
+                                        Some(i) if i < 0.9 => egui::Color32::from_rgb(230, 90, 90),
+                                        Some(i) if i < 1.0 => egui::Color32::YELLOW,
+                                        _ => ui.visuals().text_color(),
+                                    };
+                                    ui.label(egui::RichText::new(format_index(index)).color(color));
+                                });
+                            }
+                        }
                         row.col(|ui| {
                             if ui.small_button("✏ Edit").clicked() {
                                 self.selected_task_for_edit = Some(original_idx);
                                 self.edit_panel_open = true;
+                                self.dependency_error = None;
                             }
                         });
                     });
                 }
             });
+        // The timeline was drawn first, so catch it up next frame
+        if scrolled.state.offset.y != self.task_scroll {
+            self.task_scroll = scrolled.state.offset.y;
+            ui.ctx().request_repaint();
+        }
 
         ui.separator();
 
@@ -442,11 +1689,17 @@ impl MsProjectMergerApp {
                 ui.label(format!("{} task(s) selected", self.selected_tasks.len()));
                 if ui.button("Delete Selected").clicked() {
                     let mut indices_to_remove: Vec<usize> = self.selected_tasks.iter().copied().collect();
-                    indices_to_remove.sort_unstable_by(|a, b| b.cmp(a)); // Sort descending
-                    for idx in indices_to_remove {
-                        self.tasks.remove(idx);
-                    }
+                    indices_to_remove.sort_unstable();
+                    let removed = indices_to_remove.into_iter()
+                        .map(|idx| (idx, self.tasks[idx].clone()))
+                        .collect();
+                    self.history.execute(&mut self.tasks, Command::Delete { removed });
                     self.selected_tasks.clear();
+                    self.selected_task_for_edit = None;
+                    self.edit_panel_open = false;
+                }
+                if ui.button("✎ Bulk Edit…").clicked() {
+                    self.show_bulk_edit = true;
                 }
                 if ui.button("Move Up").clicked() {
                     self.move_selected_tasks_up();
@@ -458,32 +1711,112 @@ impl MsProjectMergerApp {
         }
     }
 
-    fn move_selected_tasks_up(&mut self) {
-        let mut indices: Vec<usize> = self.selected_tasks.iter().copied().collect();
-        indices.sort();
-        
-        for &idx in &indices {
-            if idx > 0 && !indices.contains(&(idx - 1)) {
-                self.tasks.swap(idx, idx - 1);
-                // Update selection
-                self.selected_tasks.remove(&idx);
-                self.selected_tasks.insert(idx - 1);
+    /// The task new rows go next to: the only selected one, or the one
+    /// being edited.
+    fn insert_anchor(&self) -> Option<usize> {
+        let anchor = match self.selected_tasks.len() {
+            1 => self.selected_tasks.iter().next().copied(),
+            0 => self.selected_task_for_edit,
+            _ => None,
+        };
+        anchor.filter(|&i| i < self.tasks.len())
+    }
+
+    /// Inserts a task with the next WBS code and a fresh UID, then selects
+    /// it for editing.
+    fn add_task(&mut self, kind: NewTask) {
+        let anchor = self.insert_anchor();
+        let Some((index, wbs)) = outline::new_task_slot(&self.tasks, anchor, kind == NewTask::Subtask) else {
+            return;
+        };
+        let uid = outline::next_uid(&self.tasks);
+        let mut new_tasks = match (kind, anchor) {
+            (NewTask::Duplicate, Some(a)) => outline::duplicate_subtree(&self.tasks, a, &wbs, uid),
+            (NewTask::Duplicate, None) => return,
+            _ => {
+                let milestone = kind == NewTask::Milestone;
+                let mut task = Task::new(if milestone { "New Milestone" } else { "New Task" }.to_string());
+                task.uid = uid;
+                task.wbs = wbs;
+                task.milestone = milestone;
+                if let Some(a) = anchor {
+                    task.start = self.tasks[a].start;
+                    task.finish = if milestone { task.start } else { at_finish(task.start.date()) };
+                }
+                vec![task]
+            }
+        };
+
+        let now = chrono::Local::now().naive_local();
+        for task in &mut new_tasks {
+            for (field, _) in task::TRACKED_FIELDS {
+                task.set_field_origin(field, Origin::Manual, now);
             }
         }
+        if let (NewTask::Subtask, Some(a)) = (kind, anchor) {
+            self.collapsed.remove(&Self::tree_key(&self.tasks[a]));
+        }
+
+        self.history.execute(&mut self.tasks, Command::Insert { index, tasks: new_tasks });
+        self.selected_tasks = HashSet::from([index]);
+        self.selected_task_for_edit = Some(index);
+        self.edit_panel_open = true;
+        self.dependency_error = None;
+    }
+
+    fn move_selected_tasks_up(&mut self) {
+        self.move_selected_subtrees(true);
     }
 
     fn move_selected_tasks_down(&mut self) {
-        let mut indices: Vec<usize> = self.selected_tasks.iter().copied().collect();
-        indices.sort_by(|a, b| b.cmp(a)); // Sort descending
-        
-        for &idx in &indices {
-            if idx < self.tasks.len() - 1 && !indices.contains(&(idx + 1)) {
-                self.tasks.swap(idx, idx + 1);
-                // Update selection
-                self.selected_tasks.remove(&idx);
-                self.selected_tasks.insert(idx + 1);
+        self.move_selected_subtrees(false);
+    }
+
+    /// Moves each selected task with its subtree past the neighbouring
+    /// sibling. Selected tasks inside a selected subtree just come along, and
+    /// a selected task never jumps over another selected one.
+    fn move_selected_subtrees(&mut self, up: bool) {
+        let mut selected: Vec<usize> = self.selected_tasks.iter().copied().collect();
+        selected.sort_unstable();
+        let mut roots: Vec<usize> = Vec::new();
+        for i in selected {
+            if roots.last().is_none_or(|&r| outline::subtree_end(&self.tasks, r) <= i) {
+                roots.push(i);
             }
         }
+        if !up {
+            roots.reverse();
+        }
+
+        let mut order: Vec<usize> = (0..self.tasks.len()).collect();
+        for root in roots {
+            let Some(pos) = order.iter().position(|&i| i == root) else { continue };
+            let before = order.clone();
+            let Some(new_pos) = outline::move_subtree(&self.tasks, &mut order, pos, up) else { continue };
+            // The sibling that was jumped over now starts right after (up) or at (down) the old spot
+            let jumped = if up {
+                order[new_pos + (outline::subtree_end(&self.tasks, root) - root)]
+            } else {
+                order[pos]
+            };
+            if self.selected_tasks.contains(&jumped) {
+                order = before;
+            }
+        }
+
+        let selection = order.iter()
+            .enumerate()
+            .filter(|(_, old)| self.selected_tasks.contains(old))
+            .map(|(new, _)| new)
+            .collect();
+        self.reorder_tasks(order);
+        self.selected_tasks = selection;
+    }
+
+    fn reorder_tasks(&mut self, order: Vec<usize>) {
+        if order.iter().enumerate().any(|(new, &old)| new != old) {
+            self.history.execute(&mut self.tasks, Command::Reorder { order });
+        }
     }
 
     fn show_edit_panel(&mut self, ui: &mut egui::Ui) {
@@ -496,11 +1829,28 @@ impl MsProjectMergerApp {
                 let mut task_name = self.tasks[idx].name.clone();
                 let mut task_desc = self.tasks[idx].description.clone();
                 let mut task_assignee = self.tasks[idx].assignee.clone();
-                let mut task_start = self.tasks[idx].start_date;
-                let mut task_end = self.tasks[idx].end_date;
-                let mut task_status = self.tasks[idx].status.clone();
+                let mut task_start = self.tasks[idx].start;
+                let mut task_end = self.tasks[idx].finish;
+                let mut task_status = self.tasks[idx].status;
+                let mut task_percent = self.tasks[idx].percent_complete;
                 let mut task_priority = self.tasks[idx].priority;
-                let task_predecessors = self.tasks[idx].predecessors.clone();
+                let mut actual_start = self.tasks[idx].actual_start;
+                let mut actual_finish = self.tasks[idx].actual_finish;
+                let mut task_duration = self.tasks[idx].duration;
+                let mut actual_duration = self.tasks[idx].actual_duration;
+                let mut remaining_duration = self.tasks[idx].remaining_duration;
+                let mut task_predecessors = self.tasks[idx].predecessors.clone();
+                let own_uid = self.tasks[idx].uid;
+                let candidates: Vec<(i32, String)> = self.tasks.iter()
+                    .filter(|t| t.uid > 0 && t.uid != own_uid)
+                    .map(|t| (t.uid, if t.wbs.is_empty() { t.name.clone() } else { format!("{} {}", t.wbs, t.name) }))
+                    .collect();
+                let candidate_label = |uid: i32| candidates.iter()
+                    .find(|(u, _)| *u == uid)
+                    .map_or_else(|| format!("UID {}", uid), |(_, label)| label.clone());
+                let mut predecessors_changed = false;
+                // Summary dates and progress come from the subtasks
+                let rollup = outline::rollup(&self.tasks, idx);
                 
                 let mut should_delete = false;
                 let mut should_close = false;
@@ -535,63 +1885,203 @@ impl MsProjectMergerApp {
                     ui.label(egui::RichText::new("Dates").strong());
                     ui.add_space(5.0);
 
-                    ui.label("Start Date (YYYY-MM-DD):");
-                    let mut start_str = task_start.format("%Y-%m-%d").to_string();
-                    if ui.text_edit_singleline(&mut start_str).changed() {
-                        if let Ok(date) = chrono::NaiveDate::parse_from_str(&start_str, "%Y-%m-%d") {
-                            task_start = date;
-                            has_changes = true;
-                        }
-                    }
-                    ui.add_space(5.0);
+                    if let Some(rollup) = rollup {
+                        ui.label(egui::RichText::new("Rolled up from subtasks").small().italics().color(egui::Color32::GRAY));
+                        egui::Grid::new("summary_rollup").num_columns(2).show(ui, |ui| {
+                            ui.label("Start:");
+                            ui.label(format_moment(rollup.start, default_start_time()));
+                            ui.end_row();
+                            ui.label("Finish:");
+                            ui.label(format_moment(rollup.finish, default_finish_time()));
+                            ui.end_row();
+                            ui.label("Duration:");
+                            let minutes = crate::task::working_minutes_between(rollup.start, rollup.finish);
+                            ui.label(duration::Duration::from_minutes(minutes, duration::DurationUnit::Days).to_string());
+                            ui.end_row();
+                            ui.label("% Complete:");
+                            ui.label(format!("{}%", rollup.percent_complete));
+                            ui.end_row();
+                        });
+                        ui.add_space(10.0);
+                    } else {
+                        ui.label("Start (YYYY-MM-DD HH:MM):");
+                        let previous = task_start;
+                        filter_view::datetime_field(ui, "task_start", &mut task_start);
+                        has_changes |= task_start != previous;
+                        ui.add_space(5.0);
 
-                    ui.label("End Date (YYYY-MM-DD):");
-                    let mut end_str = task_end.format("%Y-%m-%d").to_string();
-                    if ui.text_edit_singleline(&mut end_str).changed() {
-                        if let Ok(date) = chrono::NaiveDate::parse_from_str(&end_str, "%Y-%m-%d") {
-                            task_end = date;
-                            has_changes = true;
-                        }
+                        ui.label("Finish (YYYY-MM-DD HH:MM):");
+                        let previous = task_end;
+                        filter_view::datetime_field(ui, "task_end", &mut task_end);
+                        has_changes |= task_end != previous;
+                        ui.add_space(5.0);
+
+                        ui.label("Duration (e.g. 4h, 2.5d, 1w?):");
+                        let previous = task_duration;
+                        filter_view::duration_field(ui, "task_duration", &mut task_duration);
+                        has_changes |= task_duration != previous;
+                        ui.add_space(10.0);
                     }
-                    ui.add_space(10.0);
 
                     // Status and Priority
                     ui.label(egui::RichText::new("Status & Priority").strong());
                     ui.add_space(5.0);
 
                     ui.label("Status:");
-                    if egui::ComboBox::from_id_salt("status_combo")
+                    let status_changed = egui::ComboBox::from_id_salt("status_combo")
                         .selected_text(format!("{:?}", task_status))
                         .show_ui(ui, |ui| {
-                            ui.selectable_value(&mut task_status, crate::task::TaskStatus::NotStarted, "NotStarted");
-                            ui.selectable_value(&mut task_status, crate::task::TaskStatus::InProgress, "InProgress");
-                            ui.selectable_value(&mut task_status, crate::task::TaskStatus::Completed, "Completed");
-                            ui.selectable_value(&mut task_status, crate::task::TaskStatus::OnHold, "OnHold");
+                            let mut changed = false;
+                            for status in [
+                                crate::task::TaskStatus::NotStarted,
+                                crate::task::TaskStatus::InProgress,
+                                crate::task::TaskStatus::Completed,
+                                crate::task::TaskStatus::OnHold,
+                                crate::task::TaskStatus::Cancelled,
+                            ] {
+                                changed |= ui.selectable_value(&mut task_status, status, format!("{:?}", status)).changed();
+                            }
+                            changed
                         })
-                        .response.changed() {
+                        .inner
+                        .unwrap_or(false);
+                    if status_changed {
                         has_changes = true;
                     }
                     ui.add_space(5.0);
 
+                    if rollup.is_none() {
+                        ui.label("% Complete:");
+                        if ui.add(egui::Slider::new(&mut task_percent, 0..=100).suffix("%")).changed() {
+                            has_changes = true;
+                        }
+                        if !status::is_explicit(task_status) && status::from_percent(task_percent) != task_status {
+                            ui.colored_label(egui::Color32::YELLOW, format!("⚠ {:?} doesn't match {}% complete", task_status, task_percent));
+                        }
+                        ui.add_space(5.0);
+                    }
+
                     ui.label("Priority:");
-                    if ui.add(egui::Slider::new(&mut task_priority, 1..=10)).changed() {
+                    let level = priority::label(task_priority);
+                    if ui.add(egui::Slider::new(&mut task_priority, 0..=priority::MAX).text(level)).changed() {
                         has_changes = true;
                     }
                     ui.add_space(10.0);
 
+                    if rollup.is_none() {
+                        ui.label(egui::RichText::new("Actuals").strong());
+                        ui.add_space(5.0);
+                        egui::Grid::new("actuals").num_columns(2).show(ui, |ui| {
+                            for (label, id, moment, planned) in [
+                                ("Actual start", "actual_start", &mut actual_start, task_start),
+                                ("Actual finish", "actual_finish", &mut actual_finish, task_end),
+                            ] {
+                                let mut set = moment.is_some();
+                                if ui.checkbox(&mut set, label).changed() {
+                                    *moment = set.then_some(planned);
+                                    has_changes = true;
+                                }
+                                if let Some(moment) = moment {
+                                    let previous = *moment;
+                                    filter_view::datetime_field(ui, id, moment);
+                                    has_changes |= *moment != previous;
+                                }
+                                ui.end_row();
+                            }
+                            for (label, id, duration) in [
+                                ("Actual duration:", "actual_duration", &mut actual_duration),
+                                ("Remaining:", "remaining_duration", &mut remaining_duration),
+                            ] {
+                                ui.label(label);
+                                let previous = *duration;
+                                filter_view::duration_field(ui, id, duration);
+                                has_changes |= *duration != previous;
+                                ui.end_row();
+                            }
+                        });
+                        ui.add_space(10.0);
+                    }
+
                     // Dependencies
                     ui.label(egui::RichText::new("Dependencies").strong());
                     ui.add_space(5.0);
 
-                    if task_predecessors.is_empty() {
-                        ui.label(egui::RichText::new("No dependencies").italics().color(egui::Color32::GRAY));
+                    if own_uid <= 0 {
+                        ui.label(egui::RichText::new("Tasks without a UID can't take part in links").italics().color(egui::Color32::GRAY));
                     } else {
-                        for pred in &task_predecessors {
+                        if task_predecessors.is_empty() {
+                            ui.label(egui::RichText::new("No dependencies").italics().color(egui::Color32::GRAY));
+                        }
+                        let mut remove = None;
+                        for (i, pred) in task_predecessors.iter_mut().enumerate() {
+                            ui.label(candidate_label(pred.predecessor_uid));
                             ui.horizontal(|ui| {
-                                ui.label(format!("Predecessor UID: {}", pred.predecessor_uid));
-                                ui.label(format!("Type: {}", pred.link_type));
+                                egui::ComboBox::from_id_salt(("link_type", i))
+                                    .selected_text(dependency::link_type_label(pred.link_type))
+                                    .width(50.0)
+                                    .show_ui(ui, |ui| {
+                                        for (code, short, long) in dependency::LINK_TYPES {
+                                            if ui.selectable_value(&mut pred.link_type, code, short).on_hover_text(long).changed() {
+                                                predecessors_changed = true;
+                                            }
+                                        }
+                                    });
+
+                                let mut lag = dependency::Lag::from_mspdi(pred.link_lag, pred.lag_format);
+                                let mut lag_changed = ui.add(egui::DragValue::new(&mut lag.value).speed(0.1).max_decimals(2)).changed();
+                                egui::ComboBox::from_id_salt(("lag_unit", i))
+                                    .selected_text(lag.unit.label())
+                                    .show_ui(ui, |ui| {
+                                        for unit in dependency::LAG_UNITS {
+                                            lag_changed |= ui.selectable_value(&mut lag.unit, unit, unit.label()).changed();
+                                        }
+                                    });
+                                lag_changed |= ui.checkbox(&mut lag.elapsed, "elapsed")
+                                    .on_hover_text("Count calendar time instead of working time")
+                                    .changed();
+                                if lag_changed {
+                                    (pred.link_lag, pred.lag_format) = lag.to_mspdi();
+                                    predecessors_changed = true;
+                                }
+
+                                if ui.small_button("❌").on_hover_text("Remove link").clicked() {
+                                    remove = Some(i);
+                                }
                             });
                         }
+                        if let Some(i) = remove {
+                            task_predecessors.remove(i);
+                            predecessors_changed = true;
+                        }
+
+                        ui.add_space(5.0);
+                        ui.horizontal(|ui| {
+                            ui.add(egui::TextEdit::singleline(&mut self.dependency_search).desired_width(100.0).hint_text("Name or WBS"));
+                            let search = self.dependency_search.trim().to_lowercase();
+                            egui::ComboBox::from_id_salt("add_predecessor")
+                                .selected_text(self.dependency_candidate.map_or_else(|| "Pick a task".to_string(), candidate_label))
+                                .width(160.0)
+                                .show_ui(ui, |ui| {
+                                    for (uid, label) in candidates.iter().filter(|(_, l)| l.to_lowercase().contains(&search)) {
+                                        ui.selectable_value(&mut self.dependency_candidate, Some(*uid), label);
+                                    }
+                                });
+                            if ui.add_enabled(self.dependency_candidate.is_some(), egui::Button::new("➕ Add")).clicked() {
+                                if let Some(uid) = self.dependency_candidate.take() {
+                                    task_predecessors.push(Predecessor {
+                                        predecessor_uid: uid,
+                                        link_type: 1,
+                                        link_lag: 0,
+                                        lag_format: dependency::LAG_FORMAT_DAYS,
+                                    });
+                                    predecessors_changed = true;
+                                }
+                            }
+                        });
+
+                        if let Some(error) = &self.dependency_error {
+                            ui.colored_label(egui::Color32::from_rgb(230, 90, 90), error);
+                        }
                     }
 
                     ui.add_space(10.0);
@@ -611,19 +2101,57 @@ impl MsProjectMergerApp {
                     });
                 });
 
+                // Links are only applied if they keep the schedule acyclic
+                if predecessors_changed {
+                    match dependency::validate_predecessors(&self.tasks, idx, &task_predecessors) {
+                        Ok(()) => {
+                            self.dependency_error = None;
+                            has_changes = true;
+                        }
+                        Err(error) => {
+                            self.dependency_error = Some(error);
+                            predecessors_changed = false;
+                        }
+                    }
+                }
+
                 // Apply changes after the borrow ends
                 if has_changes && idx < self.tasks.len() {
-                    self.tasks[idx].name = task_name;
-                    self.tasks[idx].description = task_desc;
-                    self.tasks[idx].assignee = task_assignee;
-                    self.tasks[idx].start_date = task_start;
-                    self.tasks[idx].end_date = task_end;
-                    self.tasks[idx].status = task_status;
-                    self.tasks[idx].priority = task_priority;
+                    let before = self.tasks[idx].clone();
+                    let mut after = before.clone();
+                    after.name = task_name;
+                    after.description = task_desc;
+                    after.assignee = task_assignee;
+                    // New dates set the duration; a new duration moves the finish
+                    if (task_start, task_end) != (before.start, before.finish) {
+                        after.reschedule(task_start, task_end);
+                    } else if task_duration != before.duration {
+                        after.duration = task_duration;
+                        after.finish = crate::task::add_working_minutes(task_start, task_duration.minutes);
+                    }
+                    after.status = task_status;
+                    after.percent_complete = task_percent;
+                    after.actual_start = actual_start;
+                    after.actual_finish = actual_finish;
+                    after.actual_duration = actual_duration;
+                    after.remaining_duration = remaining_duration;
+                    status::reconcile(&mut after, &before);
+                    after.priority = task_priority;
+                    if predecessors_changed {
+                        after.predecessors = task_predecessors;
+                    }
+                    after.record_changes(&before, Origin::Manual, chrono::Local::now().naive_local());
+                    self.history.execute(&mut self.tasks, Command::Edit { index: idx, before: Box::new(before), after: Box::new(after) });
+                }
+                // Keystrokes and slider drags in one field make a single undo step
+                if ui.memory(|m| m.focused().is_none()) && !ui.input(|i| i.pointer.any_down()) {
+                    self.history.seal();
                 }
 
                 if should_delete {
-                    self.tasks.remove(idx);
+                    let removed = vec![(idx, self.tasks[idx].clone())];
+                    self.history.execute(&mut self.tasks, Command::Delete { removed });
+                    self.selected_tasks.clear();
                     self.edit_panel_open = false;
                     self.selected_task_for_edit = None;
                 } else if should_close {
@@ -639,41 +2167,49 @@ impl MsProjectMergerApp {
             .collapsible(false)
             .resizable(true)
             .show(ctx, |ui| {
-                ui.label("Select MPP or XLSX files to import:");
+                ui.label("Select MSPDI, P6, GanttProject, Planner or XLSX files to import:");
+
+                ui.horizontal(|ui| {
+                    ui.label("Excel dates typed as text:");
+                    egui::ComboBox::from_id_salt("import_date_locale")
+                        .selected_text(self.import_options.date_locale.label())
+                        .show_ui(ui, |ui| {
+                            for locale in dates::DateLocale::ALL {
+                                ui.selectable_value(&mut self.import_options.date_locale, locale, locale.label());
+                            }
+                        });
+                });
 
                 ui.horizontal(|ui| {
                     if ui.button("Select Files").clicked() {
                         if let Some(paths) = rfd::FileDialog::new()
-                            .add_filter("Project Files", &["mpp", "xlsx", "xml"])
+                            .add_filter("Project Files", &self.registry.import_extensions())
                             .pick_files()
                         {
                             for path in paths {
-                                if !self.file_paths.contains(&path) {
-                                    self.file_paths.push(path.clone());
-                                    let file_idx = self.file_paths.len() - 1;
+                                if !self.files.iter().any(|f| f.path == path) {
+                                    let file_idx = self.files.len();
                                     
-                                    // Import tasks based on file extension
-                                    if path.extension().and_then(|s| s.to_str()) == Some("xlsx") {
-                                        if let Ok(mut tasks) = import_xlsx(&path) {
-                                            for task in &mut tasks {
+                                    // Pick the importer from the file content
+                                    match self.registry.import_file(&path, &self.import_options) {
+                                        Ok(mut file) => {
+                                            for task in &mut file.tasks {
                                                 task.source_file = file_idx;
                                             }
-                                            self.tasks.append(&mut tasks);
-                                        } else {
-                                            eprintln!("Failed to import XLSX file: {}", path.display());
-                                        }
-                                    } else if let Some(ext) = path.extension().and_then(|s| s.to_str()) {
-                                        if ext == "mpp" || ext == "xml" {
-                                            if let Ok(mut tasks) = import_mpp(&path) {
-                                                for task in &mut tasks {
-                                                    task.source_file = file_idx;
-                                                }
-                                                self.tasks.append(&mut tasks);
-                                            } else {
-                                                eprintln!("Failed to import MPP/XML file: {}", path.display());
+                                            if !file.diagnostics.is_empty() {
+                                                self.show_diagnostics = true;
                                             }
+                                            self.files.push(file);
+                                        }
+                                        Err(e) => {
+                                            self.notifications.file_error("Import", &e);
+                                            let mut file = merge::LoadedFile::new(path, "Unreadable", Vec::new());
+                                            file.enabled = false;
+                                            file.import_options = self.import_options;
+                                            self.files.push(file);
                                         }
                                     }
+                                    self.files_changed();
                                 }
                             }
                         }
@@ -688,6 +2224,62 @@ impl MsProjectMergerApp {
             });
     }
 
+    fn show_calendar_options(&mut self, ui: &mut egui::Ui) {
+        let options = &mut self.export_options.calendar;
+        ui.separator();
+        ui.checkbox(&mut options.milestones_only, "Milestones only");
+        ui.checkbox(&mut options.tasks_as_todos, "Tasks as to-dos (with % complete)");
+        ui.horizontal(|ui| {
+            ui.label("Project:");
+            ui.text_edit_singleline(&mut options.project)
+                .on_hover_text("Keeps this plan's events apart from other projects in the same calendar. The base file's name when empty");
+        });
+
+        let mut names: Vec<&str> = self.tasks.iter()
+            .flat_map(file_export_ics::assignees)
+            .collect();
+        names.sort_unstable_by_key(|n| n.to_lowercase());
+        names.dedup_by(|a, b| a.eq_ignore_ascii_case(b));
+
+        ui.horizontal(|ui| {
+            ui.label("Assignee:");
+            egui::ComboBox::from_id_salt("ics_assignee")
+                .selected_text(options.assignee.as_deref().unwrap_or("Everyone"))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut options.assignee, None, "Everyone");
+                    for name in names {
+                        ui.selectable_value(&mut options.assignee, Some(name.to_string()), name);
+                    }
+                });
+        });
+    }
+
+    fn show_chart_options(&mut self, ui: &mut egui::Ui) {
+        let options = &mut self.export_options.chart;
+        ui.separator();
+        ui.horizontal(|ui| {
+            ui.label("Title:");
+            ui.text_edit_singleline(&mut options.title);
+        });
+        ui.horizontal(|ui| {
+            ui.label("Timescale:");
+            let label = |scale: Option<TimeScale>| match scale {
+                None => "Automatic",
+                Some(TimeScale::Day) => "Days",
+                Some(TimeScale::Week) => "Weeks",
+                Some(TimeScale::Month) => "Months",
+            };
+            egui::ComboBox::from_id_salt("chart_scale")
+                .selected_text(label(options.scale))
+                .show_ui(ui, |ui| {
+                    for scale in [None, Some(TimeScale::Day), Some(TimeScale::Week), Some(TimeScale::Month)] {
+                        ui.selectable_value(&mut options.scale, scale, label(scale));
+                    }
+                });
+        });
+        ui.checkbox(&mut options.highlight_critical, "Highlight critical path");
+    }
+
     fn show_export_dialog(&mut self, ctx: &egui::Context) {
         egui::Window::new("Export Tasks")
             .collapsible(false)
@@ -696,10 +2288,28 @@ impl MsProjectMergerApp {
                 ui.label("Export path:");
                 ui.text_edit_singleline(&mut self.export_path);
 
+                // Warn about what the chosen format will drop
+                let mut extension = None;
+                if let Some(exporter) = self.registry.exporter_for_path(Path::new(&self.export_path)) {
+                    extension = Some(exporter.extension());
+                    let missing = exporter.capabilities().missing();
+                    if !missing.is_empty() {
+                        ui.label(egui::RichText::new(format!("{} does not keep: {}", exporter.name(), missing.join(", ")))
+                            .color(egui::Color32::YELLOW));
+                    }
+                }
+
+                // Format-specific options
+                match extension {
+                    Some("ics") => self.show_calendar_options(ui),
+                    Some("svg" | "png" | "pdf") => self.show_chart_options(ui),
+                    _ => {}
+                }
+
                 ui.horizontal(|ui| {
                     if ui.button("Browse").clicked() {
                         if let Some(path) = rfd::FileDialog::new()
-                            .add_filter("Project Files", &["xml", "xlsx"])
+                            .add_filter("Project Files", &self.registry.export_extensions())
                             .save_file()
                         {
                             self.export_path = path.to_string_lossy().to_string();
@@ -710,26 +2320,27 @@ impl MsProjectMergerApp {
                 ui.separator();
 
                 ui.horizontal(|ui| {
-                    if ui.button("Export").clicked() {
-                        if !self.export_path.is_empty() {
-                            let result = if self.export_path.ends_with(".xlsx") {
-                                export_to_xlsx(&self.tasks, &self.export_path)
-                            } else {
-                                // Default to XML if no extension or .xml
-                                let path = if !self.export_path.contains('.') {
-                                    format!("{}.xml", self.export_path)
-                                } else {
-                                    self.export_path.clone()
-                                };
-                                export_to_xml(&self.tasks, &path)
-                            };
+                    if ui.button("Export").clicked() && !self.export_path.is_empty() {
+                        // Default to the first exporter's format if no extension
+                        let mut path = PathBuf::from(&self.export_path);
+                        if path.extension().is_none() {
+                            if let Some(default) = self.registry.exporters().next() {
+                                path.set_extension(default.extension());
+                            }
+                        }
 
-                            if let Err(e) = result {
-                                eprintln!("Export error: {}", e);
-                            } else {
+                        let mut options = self.export_options.clone();
+                        if options.calendar.project.is_empty() {
+                            options.calendar.project = self.files.iter().find(|f| f.enabled).map(|f| f.file_name()).unwrap_or_default();
+                        }
+                        match self.registry.export_file(&self.tasks, &path, &options) {
+                            Ok(_) => {
+                                self.export_success_msg = Some(format!("Exported to {}", path.display()));
                                 self.show_export_dialog = false;
                                 self.export_path.clear();
                             }
+                            // Keep the dialog open so the path can be fixed
+                            Err(e) => self.notifications.file_error("Export", &e),
                         }
                     }
                     if ui.button("Cancel").clicked() {
@@ -741,3 +2352,7 @@ impl MsProjectMergerApp {
     }
 }
 
+/// SPI/CPI for display; a dash when there is nothing to divide by.
+fn format_index(index: Option<f64>) -> String {
+    index.map_or_else(|| "—".to_string(), |i| format!("{:.2}", i))
+}
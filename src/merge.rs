@@ -1,7 +1,108 @@
-use crate::task::Task;
-use std::collections::HashMap;
+use crate::diagnostics::Diagnostic;
+use crate::registry::ImportOptions;
+use crate::task::{Origin, Task};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+
+/// A file as it was imported, kept so merges can be recomputed from scratch.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoadedFile {
+    pub path: PathBuf,
+    pub format: String,
+    pub tasks: Vec<Task>,
+    pub enabled: bool,
+    /// Values the importer had to default or drop.
+    #[serde(default)]
+    pub diagnostics: Vec<Diagnostic>,
+    /// How it was read, for reading it again.
+    #[serde(default)]
+    pub import_options: ImportOptions,
+}
+
+impl LoadedFile {
+    pub fn new(path: PathBuf, format: &str, tasks: Vec<Task>) -> Self {
+        Self { path, format: format.to_string(), tasks, enabled: true, diagnostics: Vec::new(), import_options: ImportOptions::default() }
+    }
+
+    pub fn file_name(&self) -> String {
+        self.path.file_name().unwrap_or_default().to_string_lossy().into_owned()
+    }
+}
+
+/// Which file's actual start, finish and durations a merged task keeps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ActualsPolicy {
+    /// Actuals reported by an overlay replace the base's; team updates are
+    /// usually the most recent word on progress.
+    #[default]
+    OverlayWins,
+    /// Overlays only fill in actuals the base doesn't have yet.
+    FillGaps,
+}
+
+impl ActualsPolicy {
+    pub fn label(self) -> &'static str {
+        match self {
+            ActualsPolicy::OverlayWins => "Overlay actuals win",
+            ActualsPolicy::FillGaps => "Overlays only fill missing actuals",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MergeOptions {
+    pub actuals: ActualsPolicy,
+}
+
+/// The merged tasks and what had to be left out to get them.
+#[derive(Debug, Default)]
+pub struct MergeResult {
+    pub tasks: Vec<Task>,
+    /// Predecessor links of appended tasks that point at no task in their
+    /// own file, recorded against that file's name.
+    pub dropped_links: Vec<Diagnostic>,
+}
+
+/// Merges the enabled files in list order: the first is the base, later
+/// overlays win over earlier ones. `source_file` becomes the list position.
+/// Summary tasks are rolled up from their children afterwards.
+pub fn merge_files(files: &[LoadedFile], options: MergeOptions) -> MergeResult {
+    let mut enabled = files.iter().enumerate().filter(|(_, f)| f.enabled);
+    let Some((base_index, base)) = enabled.next() else {
+        return MergeResult::default();
+    };
+
+    let tag = |index: usize, tasks: &[Task]| -> Vec<Task> {
+        tasks.iter()
+            .cloned()
+            .map(|mut t| {
+                t.source_file = index;
+                t.provenance.clear();
+                t
+            })
+            .collect()
+    };
+
+    let mut merged = tag(base_index, &base.tasks);
+    let mut dropped_links = Vec::new();
+    for (index, file) in enabled {
+        for mut dropped in merge_projects(&mut merged, &tag(index, &file.tasks), options) {
+            dropped.record = format!("{}, {}", file.file_name(), dropped.record);
+            dropped_links.push(dropped);
+        }
+    }
+    crate::outline::apply_rollups(&mut merged);
+    MergeResult { tasks: merged, dropped_links }
+}
+
+/// Merges `overlay` into `base`. Returns the predecessor links dropped from
+/// appended tasks because the overlay has no task with that UID; kept, they
+/// could point at an unrelated base task.
+pub fn merge_projects(base: &mut Vec<Task>, overlay: &[Task], options: MergeOptions) -> Vec<Diagnostic> {
+    let merged_at = chrono::Local::now().naive_local();
 
-pub fn merge_projects(base: &mut Vec<Task>, overlay: &[Task]) {
     // Create a map of WBS -> Index for the base project
     let mut wbs_map: HashMap<String, usize> = HashMap::new();
     for (idx, task) in base.iter().enumerate() {
@@ -10,11 +111,50 @@ pub fn merge_projects(base: &mut Vec<Task>, overlay: &[Task]) {
         }
     }
 
+    // UIDs are only unique within their own file. Matched tasks take the
+    // base task's UID; appended ones keep theirs unless it is taken, and
+    // their predecessor links follow.
+    let mut used: HashSet<i32> = base.iter().map(|t| t.uid).collect();
+    let mut next_uid = base.iter().chain(overlay).map(|t| t.uid).max().unwrap_or(0).max(0) + 1;
+    let mut uid_map: HashMap<i32, i32> = HashMap::new();
+    let mut uids = Vec::with_capacity(overlay.len());
     for overlay_task in overlay {
+        let uid = match wbs_map.get(&overlay_task.wbs).filter(|_| !overlay_task.wbs.is_empty()) {
+            Some(&idx) => base[idx].uid,
+            None if overlay_task.uid > 0 && used.insert(overlay_task.uid) => overlay_task.uid,
+            None => {
+                next_uid += 1;
+                used.insert(next_uid - 1);
+                next_uid - 1
+            }
+        };
+        uid_map.entry(overlay_task.uid).or_insert(uid);
+        uids.push(uid);
+    }
+    let mut dropped = Vec::new();
+    let mut append = |base: &mut Vec<Task>, overlay_task: &Task, uid: i32| {
+        let mut task = overlay_task.clone();
+        task.uid = uid;
+        task.predecessors.retain_mut(|link| match uid_map.get(&link.predecessor_uid) {
+            Some(&uid) => {
+                link.predecessor_uid = uid;
+                true
+            }
+            None => {
+                let record = format!("UID {}", overlay_task.uid);
+                dropped.push(Diagnostic::new(record, "Predecessors", &link.predecessor_uid.to_string(), "dropped (no such task)"));
+                false
+            }
+        });
+        base.push(task);
+    };
+
+    for (overlay_task, &uid) in overlay.iter().zip(&uids) {
         if !overlay_task.wbs.is_empty() {
             if let Some(&idx) = wbs_map.get(&overlay_task.wbs) {
                 // Update existing task
                 let base_task = &mut base[idx];
+                let origin = Origin::File(overlay_task.source_file);
                 
                 // Update fields if they are present in overlay (and not default/empty)
                 // Note: This logic assumes overlay has newer/better data
@@ -24,17 +164,33 @@ pub fn merge_projects(base: &mut Vec<Task>, overlay: &[Task]) {
                 base_task.status = overlay_task.status;
                 
                 // Update dates
-                base_task.start_date = overlay_task.start_date;
-                base_task.end_date = overlay_task.end_date;
-                base_task.duration_days = overlay_task.duration_days;
+                base_task.start = overlay_task.start;
+                base_task.finish = overlay_task.finish;
+                base_task.duration = overlay_task.duration;
+                for field in ["percent_complete", "status", "start", "finish", "duration"] {
+                    base_task.set_field_origin(field, origin, merged_at);
+                }
                 
                 // Update other fields
                 if !overlay_task.assignee.is_empty() {
                     base_task.assignee = overlay_task.assignee.clone();
+                    base_task.set_field_origin("assignee", origin, merged_at);
                 }
                 if !overlay_task.description.is_empty() {
                     base_task.description = overlay_task.description.clone();
+                    base_task.set_field_origin("description", origin, merged_at);
+                }
+                // Costs and baselines only when the overlay has them
+                if overlay_task.cost > 0.0 {
+                    base_task.cost = overlay_task.cost;
+                }
+                if overlay_task.actual_cost > 0.0 {
+                    base_task.actual_cost = overlay_task.actual_cost;
                 }
+                if overlay_task.baseline.is_some() {
+                    base_task.baseline = overlay_task.baseline;
+                }
+                merge_actuals(base_task, overlay_task, options.actuals, origin, merged_at);
                 
                 // Optional: Update name? Usually WBS implies same task, but name might be refined
                 // base_task.name = overlay_task.name.clone();
@@ -43,7 +199,7 @@ pub fn merge_projects(base: &mut Vec<Task>, overlay: &[Task]) {
                 // New task - append it
                 // Note: Inserting into the correct hierarchy position is hard without a full tree structure.
                 // For now, we just append it.
-                base.push(overlay_task.clone());
+                append(base, overlay_task, uid);
             }
         } else {
             // Overlay task has no WBS. 
@@ -53,18 +209,43 @@ pub fn merge_projects(base: &mut Vec<Task>, overlay: &[Task]) {
             // User requirement was WBS-based. Let's ignore for now to avoid duplicates, 
             // or append if it looks important.
             // Let's append.
-            base.push(overlay_task.clone());
+            append(base, overlay_task, uid);
         }
     }
     
     // Re-sort by WBS if possible?
     // base.sort_by(|a, b| a.wbs.cmp(&b.wbs)); // Simple string sort might be wrong for 1.10 vs 1.2
+    dropped
+}
+
+/// Takes the overlay's actuals into `base` as `policy` allows. An overlay
+/// that reports no actuals for a task leaves the base's alone either way.
+fn merge_actuals(base: &mut Task, overlay: &Task, policy: ActualsPolicy, origin: Origin, at: chrono::NaiveDateTime) {
+    let replace = |has_base: bool| policy == ActualsPolicy::OverlayWins || !has_base;
+    let base_reports = base.actual_start.is_some() || base.actual_duration.minutes > 0;
+    if let Some(start) = overlay.actual_start.filter(|_| replace(base.actual_start.is_some())) {
+        base.actual_start = Some(start);
+        base.set_field_origin("actual_start", origin, at);
+    }
+    if let Some(finish) = overlay.actual_finish.filter(|_| replace(base.actual_finish.is_some())) {
+        base.actual_finish = Some(finish);
+        base.set_field_origin("actual_finish", origin, at);
+    }
+    // Durations travel together so actual + remaining stays consistent
+    let overlay_reports = overlay.actual_start.is_some() || overlay.actual_duration.minutes > 0;
+    if overlay_reports && replace(base_reports) {
+        base.actual_duration = overlay.actual_duration;
+        base.remaining_duration = overlay.remaining_duration;
+        base.set_field_origin("actual_duration", origin, at);
+        base.set_field_origin("remaining_duration", origin, at);
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::task::{Task, TaskStatus};
+    use crate::duration::Duration;
+    use crate::task::{Predecessor, Task, TaskStatus};
 
     #[test]
     fn test_merge_update_existing() {
@@ -76,15 +257,78 @@ mod tests {
         overlay_task.wbs = "1.1".to_string();
         overlay_task.percent_complete = 50;
         overlay_task.status = TaskStatus::InProgress;
+        overlay_task.source_file = 1;
         
         let mut base = vec![base_task];
         let overlay = vec![overlay_task];
         
-        merge_projects(&mut base, &overlay);
+        merge_projects(&mut base, &overlay, MergeOptions::default());
         
         assert_eq!(base.len(), 1);
         assert_eq!(base[0].percent_complete, 50);
         assert_eq!(base[0].status, TaskStatus::InProgress);
+        // Overlay fields are attributed to the overlay, the rest to the base
+        assert_eq!(base[0].field_origin("percent_complete"), Origin::File(1));
+        assert_eq!(base[0].field_origin("name"), Origin::File(0));
+        assert!(base[0].touched_by(1));
+    }
+
+    #[test]
+    fn test_merge_files_order_and_enabled() {
+        let file = |name: &str, percent: u32| {
+            let mut task = Task::new(name.to_string());
+            task.wbs = "1".to_string();
+            task.percent_complete = percent;
+            LoadedFile::new(PathBuf::from(format!("{}.xml", name)), "MSPDI", vec![task])
+        };
+        let mut files = vec![file("base", 0), file("early", 30), file("late", 60)];
+
+        // Later overlays take precedence
+        assert_eq!(merge_files(&files, MergeOptions::default()).tasks[0].percent_complete, 60);
+
+        files.swap(1, 2);
+        let merged = merge_files(&files, MergeOptions::default()).tasks;
+        assert_eq!(merged[0].percent_complete, 30);
+        assert_eq!(merged[0].field_origin("percent_complete"), Origin::File(2));
+
+        // Disabling the base promotes the next file
+        files[0].enabled = false;
+        let merged = merge_files(&files, MergeOptions::default()).tasks;
+        assert_eq!(merged[0].name, "late");
+        assert_eq!(merged[0].source_file, 1);
+    }
+
+    #[test]
+    fn test_merge_remaps_clashing_uids() {
+        let task = |uid: i32, wbs: &str, predecessor: Option<i32>| {
+            let mut task = Task::new(format!("Task {}", wbs));
+            task.uid = uid;
+            task.wbs = wbs.to_string();
+            task.predecessors = predecessor.into_iter()
+                .map(|uid| Predecessor { predecessor_uid: uid, link_type: 1, link_lag: 0, lag_format: 7 })
+                .collect();
+            task
+        };
+        let base = LoadedFile::new(PathBuf::from("base.xml"), "MSPDI", vec![task(1, "1", None), task(2, "2", Some(1))]);
+        // UID 2 here is a different task from the base's UID 2
+        let overlay = LoadedFile::new(PathBuf::from("team.xml"), "MSPDI", vec![
+            task(1, "1", None),
+            task(2, "3", Some(1)),
+            task(3, "4", Some(2)),
+            // UID 9 isn't in this file; without remapping it could hit a base task
+            task(4, "5", Some(9)),
+        ]);
+
+        let result = merge_files(&[base, overlay], MergeOptions::default());
+        let merged = result.tasks;
+        let uids: HashSet<i32> = merged.iter().map(|t| t.uid).collect();
+        assert_eq!(uids.len(), merged.len());
+        let by_wbs = |wbs: &str| merged.iter().find(|t| t.wbs == wbs).unwrap();
+        assert_eq!(by_wbs("3").predecessors[0].predecessor_uid, by_wbs("1").uid);
+        assert_eq!(by_wbs("4").predecessors[0].predecessor_uid, by_wbs("3").uid);
+        assert_eq!(by_wbs("2").predecessors[0].predecessor_uid, by_wbs("1").uid);
+        assert!(by_wbs("5").predecessors.is_empty());
+        assert_eq!(result.dropped_links, vec![Diagnostic::new("team.xml, UID 4", "Predecessors", "9", "dropped (no such task)")]);
     }
 
     #[test]
@@ -98,10 +342,38 @@ mod tests {
         let mut base = vec![base_task];
         let overlay = vec![overlay_task];
         
-        merge_projects(&mut base, &overlay);
+        merge_projects(&mut base, &overlay, MergeOptions::default());
         
         assert_eq!(base.len(), 2);
         assert_eq!(base[1].wbs, "1.2");
         assert_eq!(base[1].name, "New Task");
     }
+
+    #[test]
+    fn test_merge_actuals_policy() {
+        let date = |day| crate::task::at_start(chrono::NaiveDate::from_ymd_opt(2024, 1, day).unwrap());
+        let mut base_task = Task::new("Build".to_string());
+        base_task.wbs = "1".to_string();
+        base_task.actual_start = Some(date(2));
+        base_task.actual_duration = Duration::from_days(3);
+
+        let mut overlay_task = base_task.clone();
+        overlay_task.source_file = 1;
+        overlay_task.actual_start = Some(date(4));
+        overlay_task.actual_finish = Some(date(9));
+        overlay_task.actual_duration = Duration::from_days(4);
+
+        let mut base = vec![base_task.clone()];
+        merge_projects(&mut base, &[overlay_task.clone()], MergeOptions::default());
+        assert_eq!(base[0].actual_start, Some(date(4)));
+        assert_eq!(base[0].actual_duration, Duration::from_days(4));
+        assert_eq!(base[0].field_origin("actual_start"), Origin::File(1));
+
+        // Filling gaps keeps the base's start but takes the missing finish
+        let mut base = vec![base_task];
+        merge_projects(&mut base, &[overlay_task], MergeOptions { actuals: ActualsPolicy::FillGaps });
+        assert_eq!(base[0].actual_start, Some(date(2)));
+        assert_eq!(base[0].actual_duration, Duration::from_days(3));
+        assert_eq!(base[0].actual_finish, Some(date(9)));
+    }
 }
@@ -5,6 +5,11 @@ use serde::{Deserialize, Serialize};
 pub struct Project {
     #[serde(rename = "Title", default)]
     pub title: String,
+    /// Times of day for dates given without one, e.g. "08:00:00".
+    #[serde(rename = "DefaultStartTime", default, skip_serializing_if = "String::is_empty")]
+    pub default_start_time: String,
+    #[serde(rename = "DefaultFinishTime", default, skip_serializing_if = "String::is_empty")]
+    pub default_finish_time: String,
     #[serde(rename = "Tasks")]
     pub tasks: Tasks,
     // Add other project-level fields as needed
@@ -30,20 +35,42 @@ pub struct MspdiTask {
     pub finish: String,
     #[serde(rename = "Duration", default)]
     pub duration: String,
+    /// Unit code for `Duration`; elapsed and estimated variants included.
+    #[serde(rename = "DurationFormat", default)]
+    pub duration_format: i32,
     #[serde(rename = "PercentComplete", default)]
     pub percent_complete: i32,
-    #[serde(rename = "Active", default)]
+    #[serde(rename = "Active", default = "default_active")]
     pub active: i32,
     #[serde(rename = "Manual", default)]
     pub manual: i32,
+    #[serde(rename = "Milestone", default)]
+    pub milestone: i32,
+    #[serde(rename = "Summary", default)]
+    pub summary: i32,
+    #[serde(rename = "Estimated", default)]
+    pub estimated: i32,
     #[serde(rename = "OutlineNumber", default)]
     pub outline_number: String,
     #[serde(rename = "OutlineLevel", default)]
     pub outline_level: i32,
-    #[serde(rename = "Priority", default)]
+    #[serde(rename = "Priority", default = "default_priority")]
     pub priority: i32,
     #[serde(rename = "Notes", default)]
     pub notes: String,
+    /// Costs are in hundredths of the currency unit.
+    #[serde(rename = "Cost", default)]
+    pub cost: f64,
+    #[serde(rename = "ActualCost", default)]
+    pub actual_cost: f64,
+    #[serde(rename = "ActualStart", default, skip_serializing_if = "String::is_empty")]
+    pub actual_start: String,
+    #[serde(rename = "ActualFinish", default, skip_serializing_if = "String::is_empty")]
+    pub actual_finish: String,
+    #[serde(rename = "ActualDuration", default, skip_serializing_if = "String::is_empty")]
+    pub actual_duration: String,
+    #[serde(rename = "RemainingDuration", default, skip_serializing_if = "String::is_empty")]
+    pub remaining_duration: String,
     
     // Extended fields often found in MSPDI
     #[serde(rename = "WBS", default)]
@@ -51,6 +78,63 @@ pub struct MspdiTask {
 
     #[serde(rename = "PredecessorLink", default)]
     pub predecessor_link: Vec<MspdiPredecessorLink>,
+
+    #[serde(rename = "ExtendedAttribute", default, skip_serializing_if = "Vec::is_empty")]
+    pub extended_attribute: Vec<MspdiExtendedAttribute>,
+
+    #[serde(rename = "Baseline", default, skip_serializing_if = "Vec::is_empty")]
+    pub baseline: Vec<MspdiBaseline>,
+}
+
+/// MS Project's default priority, for tasks that leave it out.
+fn default_priority() -> i32 {
+    crate::priority::DEFAULT as i32
+}
+
+/// Tasks are active unless the file says otherwise.
+fn default_active() -> i32 {
+    1
+}
+
+/// `FieldID` of the Text1 custom field.
+pub const TEXT1_FIELD_ID: u32 = 188743731;
+
+/// `FieldID`s of the first ten text custom fields.
+pub const TEXT_FIELD_IDS: [(u32, &str); 10] = [
+    (TEXT1_FIELD_ID, "Text1"),
+    (188743734, "Text2"),
+    (188743737, "Text3"),
+    (188743740, "Text4"),
+    (188743743, "Text5"),
+    (188743746, "Text6"),
+    (188743747, "Text7"),
+    (188743748, "Text8"),
+    (188743749, "Text9"),
+    (188743750, "Text10"),
+];
+
+/// A saved baseline; number 0 is the one MS Project calls "Baseline".
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct MspdiBaseline {
+    #[serde(rename = "Number", default)]
+    pub number: i32,
+    #[serde(rename = "Start", default)]
+    pub start: String,
+    #[serde(rename = "Finish", default)]
+    pub finish: String,
+    #[serde(rename = "Duration", default)]
+    pub duration: String,
+    #[serde(rename = "Cost", default)]
+    pub cost: f64,
+}
+
+/// A custom field value on a task.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct MspdiExtendedAttribute {
+    #[serde(rename = "FieldID", default)]
+    pub field_id: String,
+    #[serde(rename = "Value", default)]
+    pub value: String,
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq)]
@@ -72,6 +156,8 @@ impl Default for Project {
     fn default() -> Self {
         Self {
             title: String::new(),
+            default_start_time: String::new(),
+            default_finish_time: String::new(),
             tasks: Tasks { task: Vec::new() },
         }
     }
@@ -0,0 +1,196 @@
+use chrono::{DateTime, Local};
+use eframe::egui;
+use std::path::{Path, PathBuf};
+use crate::error::FileError;
+
+/// Seconds a toast stays on screen.
+const TOAST_SECONDS: f64 = 6.0;
+
+const ERROR_COLOR: egui::Color32 = egui::Color32::from_rgb(247, 118, 142); // #F7768E
+const SUCCESS_COLOR: egui::Color32 = egui::Color32::from_rgb(158, 206, 106); // #9ECE6A
+
+/// A failure kept for the error panel after its toast is gone.
+pub struct ErrorReport {
+    pub at: DateTime<Local>,
+    /// What was being done, e.g. "Import" or "Export".
+    pub action: String,
+    pub file: Option<PathBuf>,
+    pub location: Option<String>,
+    pub cause: String,
+}
+
+impl ErrorReport {
+    fn to_text(&self) -> String {
+        let mut text = format!("{} failed at {}", self.action, self.at.format("%Y-%m-%d %H:%M:%S"));
+        if let Some(file) = &self.file {
+            text.push_str(&format!("\nFile: {}", file.display()));
+        }
+        if let Some(location) = &self.location {
+            text.push_str(&format!("\nLocation: {}", location));
+        }
+        text.push_str(&format!("\nCause: {}", self.cause));
+        text
+    }
+}
+
+struct Toast {
+    text: String,
+    is_error: bool,
+    /// Set the first frame the toast is drawn.
+    shown_at: Option<f64>,
+}
+
+/// Short-lived toasts in the corner plus a panel listing every error of the session.
+#[derive(Default)]
+pub struct Notifications {
+    toasts: Vec<Toast>,
+    errors: Vec<ErrorReport>,
+    show_panel: bool,
+}
+
+impl Notifications {
+    pub fn success(&mut self, text: impl Into<String>) {
+        self.toasts.push(Toast { text: text.into(), is_error: false, shown_at: None });
+    }
+
+    pub fn file_error(&mut self, action: &str, error: &FileError) {
+        self.push_error(ErrorReport {
+            at: Local::now(),
+            action: action.to_string(),
+            file: error.path().map(Path::to_path_buf),
+            location: error.location(),
+            cause: error.cause(),
+        });
+    }
+
+    /// For failures that aren't a [`FileError`], such as session files.
+    pub fn error(&mut self, action: &str, file: Option<&Path>, cause: impl ToString) {
+        self.push_error(ErrorReport {
+            at: Local::now(),
+            action: action.to_string(),
+            file: file.map(Path::to_path_buf),
+            location: None,
+            cause: cause.to_string(),
+        });
+    }
+
+    fn push_error(&mut self, report: ErrorReport) {
+        let name = report.file.as_deref()
+            .and_then(Path::file_name)
+            .map(|n| format!(" ({})", n.to_string_lossy()))
+            .unwrap_or_default();
+        self.toasts.push(Toast {
+            text: format!("{} failed{}: {}", report.action, name, report.cause),
+            is_error: true,
+            shown_at: None,
+        });
+        self.errors.push(report);
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context) {
+        let now = ctx.input(|i| i.time);
+        self.toasts.retain(|t| t.shown_at.is_none_or(|shown| now - shown < TOAST_SECONDS));
+
+        egui::Area::new(egui::Id::new("toasts"))
+            .anchor(egui::Align2::RIGHT_BOTTOM, egui::vec2(-12.0, -12.0))
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                ui.set_max_width(360.0);
+                let mut dismissed = None;
+                for (i, toast) in self.toasts.iter_mut().enumerate() {
+                    toast.shown_at.get_or_insert(now);
+                    let color = if toast.is_error { ERROR_COLOR } else { SUCCESS_COLOR };
+                    egui::Frame::popup(ui.style()).stroke(egui::Stroke::new(1.0, color)).show(ui, |ui| {
+                        ui.horizontal(|ui| {
+                            let icon = if toast.is_error { "❌" } else { "✔" };
+                            ui.label(egui::RichText::new(icon).color(color));
+                            ui.add(egui::Label::new(&toast.text).wrap());
+                            if toast.is_error && ui.small_button("Details").clicked() {
+                                self.show_panel = true;
+                                dismissed = Some(i);
+                            }
+                            if ui.small_button("✖").clicked() {
+                                dismissed = Some(i);
+                            }
+                        });
+                    });
+                }
+                if let Some(i) = dismissed {
+                    self.toasts.remove(i);
+                }
+
+                // Stays after the toasts fade so errors can still be looked up
+                if !self.errors.is_empty() {
+                    let label = egui::RichText::new(format!("⚠ {} error(s)", self.errors.len())).color(ERROR_COLOR);
+                    if ui.button(label).clicked() {
+                        self.show_panel = !self.show_panel;
+                    }
+                }
+            });
+
+        if !self.toasts.is_empty() {
+            ctx.request_repaint_after(std::time::Duration::from_millis(500));
+        }
+        if self.show_panel {
+            self.show_error_panel(ctx);
+        }
+    }
+
+    fn show_error_panel(&mut self, ctx: &egui::Context) {
+        let mut open = true;
+        let mut clear = false;
+        egui::Window::new("Errors")
+            .open(&mut open)
+            .resizable(true)
+            .default_width(480.0)
+            .show(ctx, |ui| {
+                if self.errors.is_empty() {
+                    ui.label(egui::RichText::new("No errors.").italics().color(egui::Color32::GRAY));
+                }
+                egui::ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+                    // Newest first
+                    for (i, report) in self.errors.iter().enumerate().rev() {
+                        ui.group(|ui| {
+                            ui.horizontal(|ui| {
+                                ui.label(egui::RichText::new(format!("{} failed", report.action)).strong().color(ERROR_COLOR));
+                                ui.label(egui::RichText::new(report.at.format("%H:%M:%S").to_string()).small().color(egui::Color32::GRAY));
+                                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                                    if ui.small_button("📋 Copy").clicked() {
+                                        ui.ctx().copy_text(report.to_text());
+                                    }
+                                });
+                            });
+                            egui::Grid::new(("error_report", i))
+                                .num_columns(2)
+                                .show(ui, |ui| {
+                                    if let Some(file) = &report.file {
+                                        ui.label("File:");
+                                        ui.label(file.display().to_string());
+                                        ui.end_row();
+                                    }
+                                    if let Some(location) = &report.location {
+                                        ui.label("Location:");
+                                        ui.label(location);
+                                        ui.end_row();
+                                    }
+                                    ui.label("Cause:");
+                                    ui.add(egui::Label::new(&report.cause).wrap());
+                                    ui.end_row();
+                                });
+                        });
+                    }
+                });
+                ui.separator();
+                if ui.button("Clear").clicked() {
+                    clear = true;
+                }
+            });
+        if clear {
+            self.errors.clear();
+            self.show_panel = false;
+        }
+        if !open {
+            self.show_panel = false;
+        }
+    }
+}
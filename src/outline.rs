@@ -0,0 +1,275 @@
+use chrono::NaiveDateTime;
+use std::collections::HashMap;
+use crate::duration::{Duration, DurationUnit};
+use crate::task::{working_minutes_between, Task};
+
+// The hierarchy is implied by list order and outline levels, as in MS
+// Project: a task's children are the deeper tasks directly after it.
+
+/// End (exclusive) of the subtree starting at `index`.
+pub fn subtree_end(tasks: &[Task], index: usize) -> usize {
+    let level = tasks[index].outline_level();
+    (index + 1..tasks.len())
+        .find(|&i| tasks[i].outline_level() <= level)
+        .unwrap_or(tasks.len())
+}
+
+pub fn is_summary(tasks: &[Task], index: usize) -> bool {
+    subtree_end(tasks, index) > index + 1
+}
+
+/// Summary values derived from a task's children.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rollup {
+    pub start: NaiveDateTime,
+    pub finish: NaiveDateTime,
+    pub percent_complete: u32,
+    /// Working minutes already spent on the leaves.
+    pub actual_minutes: u32,
+}
+
+/// Earliest start and latest finish of the leaf tasks under `index`, with
+/// percent complete weighted by duration and actual time summed. `None`
+/// for leaf tasks.
+pub fn rollup(tasks: &[Task], index: usize) -> Option<Rollup> {
+    let end = subtree_end(tasks, index);
+    let leaves: Vec<&Task> = (index + 1..end)
+        .filter(|&i| !is_summary(tasks, i))
+        .map(|i| &tasks[i])
+        .collect();
+    let start = leaves.iter().map(|t| t.start).min()?;
+    let finish = leaves.iter().map(|t| t.finish).max()?;
+
+    // Milestones and zero-length tasks still count, just lightly
+    let weight = |t: &Task| t.duration.minutes.max(1) as u64;
+    let total: u64 = leaves.iter().map(|t| weight(t)).sum();
+    let done: u64 = leaves.iter().map(|t| weight(t) * t.percent_complete as u64).sum();
+    Some(Rollup {
+        start,
+        finish,
+        percent_complete: (done as f64 / total as f64).round() as u32,
+        actual_minutes: leaves.iter().map(|t| t.actual_duration.minutes).sum(),
+    })
+}
+
+/// Overwrites every summary task's dates, durations and percent complete
+/// with the values rolled up from its children. Status follows the new
+/// percent unless it was set to On Hold or Cancelled.
+pub fn apply_rollups(tasks: &mut [Task]) {
+    for index in 0..tasks.len() {
+        if let Some(rollup) = rollup(tasks, index) {
+            let task = &mut tasks[index];
+            task.start = rollup.start;
+            task.finish = rollup.finish;
+            task.duration = Duration::from_minutes(working_minutes_between(rollup.start, rollup.finish), DurationUnit::Days);
+            task.actual_duration = task.duration.with_minutes(rollup.actual_minutes);
+            task.remaining_duration = task.duration.with_minutes(task.duration.minutes.saturating_sub(rollup.actual_minutes));
+            task.percent_complete = rollup.percent_complete;
+            task.milestone = false;
+            if !crate::status::is_explicit(task.status) {
+                task.status = crate::status::from_percent(rollup.percent_complete);
+            }
+        }
+    }
+}
+
+/// The task `index` sits directly under, if any.
+pub fn parent(tasks: &[Task], index: usize) -> Option<usize> {
+    let level = tasks[index].outline_level();
+    (0..index).rev().find(|&i| tasks[i].outline_level() < level)
+}
+
+/// A UID no task uses yet.
+pub fn next_uid(tasks: &[Task]) -> i32 {
+    tasks.iter().map(|t| t.uid).max().unwrap_or(0).max(0) + 1
+}
+
+/// Where a new task goes and its WBS code: after the last child of the
+/// anchor (`child`) or of the anchor's parent, or at the end of the top
+/// level without an anchor. Plans without WBS codes just get the task after
+/// the anchor. `None` if a child is asked for under a task with no WBS.
+pub fn new_task_slot(tasks: &[Task], anchor: Option<usize>, child: bool) -> Option<(usize, String)> {
+    let parent = match (anchor, child) {
+        (Some(a), true) if tasks[a].wbs.is_empty() => return None,
+        (Some(a), true) => Some(a),
+        (Some(a), false) if tasks[a].wbs.is_empty() => return Some((subtree_end(tasks, a), String::new())),
+        (Some(a), false) => parent(tasks, a),
+        (None, true) => return None,
+        (None, false) if !tasks.is_empty() && tasks.iter().all(|t| t.wbs.is_empty()) => {
+            return Some((tasks.len(), String::new()));
+        }
+        (None, false) => None,
+    };
+
+    let (first, end, prefix, level) = match parent {
+        Some(p) => (p + 1, subtree_end(tasks, p), format!("{}.", tasks[p].wbs), tasks[p].outline_level() + 1),
+        None => (0, tasks.len(), String::new(), 1),
+    };
+    let last = tasks[first..end].iter()
+        .filter(|t| !t.wbs.is_empty() && t.outline_level() == level)
+        .filter_map(|t| t.wbs.rsplit('.').next()?.parse::<u32>().ok())
+        .max()
+        .unwrap_or(0);
+    Some((end, format!("{}{}", prefix, last + 1)))
+}
+
+/// Copies the subtree at `index` with the root renumbered to `wbs` and
+/// fresh UIDs from `first_uid`. Links inside the subtree follow the copies.
+pub fn duplicate_subtree(tasks: &[Task], index: usize, wbs: &str, first_uid: i32) -> Vec<Task> {
+    let root_wbs = &tasks[index].wbs;
+    let mut copies: Vec<Task> = tasks[index..subtree_end(tasks, index)].to_vec();
+    let uids: HashMap<i32, i32> = copies.iter()
+        .zip(first_uid..)
+        .filter(|(t, _)| t.uid > 0)
+        .map(|(t, uid)| (t.uid, uid))
+        .collect();
+    for (copy, uid) in copies.iter_mut().zip(first_uid..) {
+        copy.uid = uid;
+        if !root_wbs.is_empty() {
+            copy.wbs = format!("{}{}", wbs, &copy.wbs[root_wbs.len()..]);
+        }
+        for pred in &mut copy.predecessors {
+            if let Some(&new) = uids.get(&pred.predecessor_uid) {
+                pred.predecessor_uid = new;
+            }
+        }
+    }
+    copies[0].name = format!("{} (copy)", copies[0].name);
+    copies
+}
+
+/// Moves the subtree at position `pos` of `order` past its previous (`up`)
+/// or next sibling's subtree. `order` lists task indices as displayed.
+/// Returns the subtree's new position, or `None` if it has no such sibling.
+pub fn move_subtree(tasks: &[Task], order: &mut [usize], pos: usize, up: bool) -> Option<usize> {
+    let level = |order: &[usize], p: usize| tasks[order[p]].outline_level();
+    let end = |order: &[usize], start: usize| {
+        (start + 1..order.len())
+            .find(|&p| level(order, p) <= level(order, start))
+            .unwrap_or(order.len())
+    };
+
+    let this_level = level(order, pos);
+    let this_end = end(order, pos);
+    if up {
+        let prev = (0..pos).rev().find(|&p| level(order, p) <= this_level)?;
+        if level(order, prev) != this_level {
+            return None; // First child: the task above is its parent
+        }
+        order[prev..this_end].rotate_left(pos - prev);
+        Some(prev)
+    } else {
+        if this_end >= order.len() || level(order, this_end) != this_level {
+            return None;
+        }
+        let next_end = end(order, this_end);
+        order[pos..next_end].rotate_left(this_end - pos);
+        Some(pos + next_end - this_end)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::{at_finish, at_start};
+    use chrono::NaiveDate;
+
+    fn task(wbs: &str, start: u32, end: u32, duration: u32, percent: u32) -> Task {
+        let mut t = Task::new(format!("Task {}", wbs));
+        t.wbs = wbs.to_string();
+        t.start = at_start(NaiveDate::from_ymd_opt(2024, 1, start).unwrap());
+        t.finish = at_finish(NaiveDate::from_ymd_opt(2024, 1, end).unwrap());
+        t.duration = Duration::from_days(duration);
+        t.percent_complete = percent;
+        t
+    }
+
+    #[test]
+    fn test_rollup_and_move_subtree() {
+        let tasks = vec![
+            task("1", 1, 1, 0, 0),
+            task("1.1", 2, 5, 4, 100),
+            task("1.2", 8, 19, 12, 0),
+            task("2", 1, 1, 0, 0),
+            task("2.1", 3, 4, 2, 50),
+        ];
+        assert!(is_summary(&tasks, 0));
+        assert_eq!(subtree_end(&tasks, 0), 3);
+        assert_eq!(rollup(&tasks, 0), Some(Rollup {
+            start: at_start(NaiveDate::from_ymd_opt(2024, 1, 2).unwrap()),
+            finish: at_finish(NaiveDate::from_ymd_opt(2024, 1, 19).unwrap()),
+            percent_complete: 25,
+            actual_minutes: 0,
+        }));
+        assert_eq!(rollup(&tasks, 1), None);
+
+        // Moving "2" up carries its child along
+        let mut order: Vec<usize> = (0..tasks.len()).collect();
+        assert_eq!(move_subtree(&tasks, &mut order, 3, true), Some(0));
+        assert_eq!(order, vec![3, 4, 0, 1, 2]);
+
+        // A first child can't leave its parent
+        let mut order: Vec<usize> = (0..tasks.len()).collect();
+        assert_eq!(move_subtree(&tasks, &mut order, 1, true), None);
+        assert_eq!(move_subtree(&tasks, &mut order, 1, false), Some(2));
+        assert_eq!(order, vec![0, 2, 1, 3, 4]);
+    }
+
+    #[test]
+    fn test_apply_rollups_nested() {
+        let mut tasks = vec![
+            task("1", 1, 1, 0, 0),
+            task("1.1", 1, 1, 0, 0),
+            task("1.1.1", 8, 9, 2, 100),
+            task("1.1.2", 10, 12, 3, 0),
+            task("1.2", 15, 16, 2, 50),
+        ];
+        apply_rollups(&mut tasks);
+
+        assert_eq!(tasks[1].start, at_start(NaiveDate::from_ymd_opt(2024, 1, 8).unwrap()));
+        assert_eq!(tasks[1].finish, at_finish(NaiveDate::from_ymd_opt(2024, 1, 12).unwrap()));
+        assert_eq!(tasks[1].duration, Duration::from_days(5));
+        assert_eq!(tasks[1].percent_complete, 40);
+        // The top level weighs the leaves, not the intermediate summary
+        assert_eq!(tasks[0].finish, at_finish(NaiveDate::from_ymd_opt(2024, 1, 16).unwrap()));
+        assert_eq!(tasks[0].duration, Duration::from_days(7));
+        assert_eq!(tasks[0].percent_complete, 43);
+
+        // Remaining time follows the children's actual time
+        tasks[2].actual_duration = Duration::from_days(2);
+        apply_rollups(&mut tasks);
+        assert_eq!(tasks[1].remaining_duration, Duration::from_days(3));
+        tasks[3].actual_duration = Duration::from_days(1);
+        apply_rollups(&mut tasks);
+        assert_eq!(tasks[1].actual_duration, Duration::from_days(3));
+        assert_eq!(tasks[1].remaining_duration, Duration::from_days(2));
+        assert_eq!(tasks[0].remaining_duration, Duration::from_days(4));
+    }
+
+    #[test]
+    fn test_new_task_slots() {
+        let mut tasks = vec![
+            task("1", 1, 1, 0, 0),
+            task("1.1", 1, 1, 0, 0),
+            task("1.2", 1, 1, 0, 0),
+            task("2", 1, 1, 0, 0),
+        ];
+        for (i, t) in tasks.iter_mut().enumerate() {
+            t.uid = i as i32 + 1;
+        }
+        tasks[2].predecessors = vec![crate::task::Predecessor { predecessor_uid: 2, link_type: 1, link_lag: 0, lag_format: 7 }];
+
+        assert_eq!(new_task_slot(&tasks, Some(1), false), Some((3, "1.3".to_string())));
+        assert_eq!(new_task_slot(&tasks, Some(0), true), Some((3, "1.3".to_string())));
+        assert_eq!(new_task_slot(&tasks, Some(3), true), Some((4, "2.1".to_string())));
+        assert_eq!(new_task_slot(&tasks, Some(0), false), Some((4, "3".to_string())));
+        assert_eq!(new_task_slot(&tasks, None, false), Some((4, "3".to_string())));
+        assert_eq!(next_uid(&tasks), 5);
+
+        let copies = duplicate_subtree(&tasks, 0, "3", 5);
+        let wbs: Vec<&str> = copies.iter().map(|t| t.wbs.as_str()).collect();
+        assert_eq!(wbs, ["3", "3.1", "3.2"]);
+        assert_eq!(copies[0].name, "Task 1 (copy)");
+        assert_eq!(copies[2].predecessors[0].predecessor_uid, 6);
+    }
+}
@@ -0,0 +1,91 @@
+// Tasks keep MS Project's priority scale: 0–1000, 500 by default, with
+// 1000 meaning "do not level". Spreadsheets tend to use 1–10 or words, so
+// those are converted on the way in.
+
+pub const DEFAULT: u32 = 500;
+pub const MAX: u32 = 1000;
+
+/// MS Project's named priority levels.
+pub const LEVELS: [(u32, &str); 10] = [
+    (100, "Lowest"),
+    (200, "Very Low"),
+    (300, "Lower"),
+    (400, "Low"),
+    (500, "Medium"),
+    (600, "High"),
+    (700, "Higher"),
+    (800, "Very High"),
+    (900, "Highest"),
+    (1000, "Do Not Level"),
+];
+
+/// Header of the priority column in our own Excel exports. Naming the
+/// scale tells the importer that 5 means 5, not a rating of 5.
+pub const COLUMN: &str = "Priority (0-1000)";
+
+/// Reads a spreadsheet priority: a level name, a 1–10 rating (scaled by
+/// 100), or an MSPDI value up to 1000.
+pub fn parse(text: &str) -> Option<u32> {
+    parse_on_scale(text, false)
+}
+
+/// Like `parse`, for columns that say they use the 0–1000 scale, so small
+/// numbers are taken as they are.
+pub fn parse_exact(text: &str) -> Option<u32> {
+    parse_on_scale(text, true)
+}
+
+fn parse_on_scale(text: &str, exact: bool) -> Option<u32> {
+    let text = text.trim();
+    if let Ok(value) = text.parse::<f64>() {
+        return match value.round() as i64 {
+            n @ 1..=10 if !exact => Some(n as u32 * 100),
+            n @ 0..=1000 => Some(n as u32),
+            _ => None,
+        };
+    }
+    let lower = text.to_lowercase();
+    match lower.as_str() {
+        "normal" | "med" => Some(DEFAULT),
+        "critical" | "urgent" => Some(900),
+        _ => LEVELS.iter()
+            .find(|(_, name)| name.eq_ignore_ascii_case(&lower))
+            .map(|(value, _)| *value),
+    }
+}
+
+/// Clamps an MSPDI `Priority` into range.
+pub fn from_mspdi(value: i32) -> u32 {
+    value.clamp(0, MAX as i32) as u32
+}
+
+/// Name of the closest level, e.g. "Medium" for 520.
+pub fn label(priority: u32) -> &'static str {
+    LEVELS.iter()
+        .min_by_key(|(value, _)| value.abs_diff(priority))
+        .map_or("", |(_, name)| name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_priority_scales() {
+        assert_eq!(parse("5"), Some(500));
+        assert_eq!(parse("10"), Some(1000));
+        assert_eq!(parse("750"), Some(750));
+        assert_eq!(parse("0"), Some(0));
+        assert_eq!(parse("high"), Some(600));
+        assert_eq!(parse("Medium"), Some(500));
+        assert_eq!(parse("LOW"), Some(400));
+        assert_eq!(parse("1500"), None);
+        assert_eq!(parse("soon"), None);
+        assert_eq!(from_mspdi(-3), 0);
+        assert_eq!(from_mspdi(4000), 1000);
+        assert_eq!(label(520), "Medium");
+
+        assert_eq!(parse_exact("5"), Some(5));
+        assert_eq!(parse_exact("Low"), Some(400));
+    }
+}
@@ -0,0 +1,349 @@
+use quick_xml::events::Event;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use crate::diagnostics::Diagnostic;
+use crate::error::FileError;
+use crate::merge::LoadedFile;
+use crate::task::Task;
+
+/// What a format can carry beyond the flat task list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Capabilities {
+    pub hierarchy: bool,
+    pub dependencies: bool,
+    pub resources: bool,
+}
+
+impl Capabilities {
+    pub const fn new(hierarchy: bool, dependencies: bool, resources: bool) -> Self {
+        Self { hierarchy, dependencies, resources }
+    }
+
+    /// Human readable list of what `self` can't carry, e.g. for export warnings.
+    pub fn missing(&self) -> Vec<&'static str> {
+        let mut missing = Vec::new();
+        if !self.hierarchy {
+            missing.push("hierarchy");
+        }
+        if !self.dependencies {
+            missing.push("dependencies");
+        }
+        if !self.resources {
+            missing.push("resources");
+        }
+        missing
+    }
+
+    pub fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if self.hierarchy {
+            parts.push("hierarchy");
+        }
+        if self.dependencies {
+            parts.push("dependencies");
+        }
+        if self.resources {
+            parts.push("resources");
+        }
+        if parts.is_empty() {
+            "tasks only".to_string()
+        } else {
+            parts.join(", ")
+        }
+    }
+}
+
+/// How sure an importer is that it can read a file. Content matches beat extension matches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Confidence {
+    None,
+    Extension,
+    Content,
+}
+
+/// Name and attribute names of the first element in an XML document.
+pub struct XmlRoot {
+    pub name: String,
+    pub attributes: Vec<String>,
+}
+
+impl XmlRoot {
+    pub fn has_attribute(&self, name: &str) -> bool {
+        self.attributes.iter().any(|a| a == name)
+    }
+}
+
+/// Everything detection needs to know about a file, computed once.
+pub struct Probe<'a> {
+    pub extension: Option<String>,
+    pub content: &'a [u8],
+    pub xml_root: Option<XmlRoot>,
+}
+
+impl<'a> Probe<'a> {
+    pub fn new(path: &Path, content: &'a [u8]) -> Self {
+        Self {
+            extension: path.extension().and_then(|e| e.to_str()).map(|e| e.to_lowercase()),
+            content,
+            xml_root: sniff_xml_root(content),
+        }
+    }
+
+    pub fn has_extension(&self, extensions: &[&str]) -> bool {
+        self.extension.as_deref().is_some_and(|e| extensions.contains(&e))
+    }
+
+    pub fn xml_root_is(&self, name: &str) -> bool {
+        self.xml_root.as_ref().is_some_and(|r| r.name == name)
+    }
+}
+
+/// A source format that can turn the raw bytes of a file into tasks.
+pub trait Importer {
+    fn name(&self) -> &'static str;
+    fn extensions(&self) -> &'static [&'static str];
+    fn capabilities(&self) -> Capabilities;
+
+    fn detect(&self, probe: &Probe) -> Confidence {
+        if probe.has_extension(self.extensions()) {
+            Confidence::Extension
+        } else {
+            Confidence::None
+        }
+    }
+
+    fn import(&self, content: &[u8], options: &ImportOptions) -> Result<Vec<Task>, Box<dyn std::error::Error>>;
+
+    /// Like `import`, also reporting every value that had to be defaulted.
+    /// Formats that never substitute anything can keep this default.
+    fn import_with_diagnostics(&self, content: &[u8], options: &ImportOptions) -> Result<(Vec<Task>, Vec<Diagnostic>), Box<dyn std::error::Error>> {
+        Ok((self.import(content, options)?, Vec::new()))
+    }
+}
+
+/// How a file was read. Kept with each loaded file so it can be read again
+/// the same way, or differently.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ImportOptions {
+    /// How spreadsheet dates typed as text are read.
+    pub date_locale: crate::dates::DateLocale,
+    /// MSPDI custom field holding On Hold or Cancelled, Text1 when unset.
+    /// `Registry::import_file` takes it from the status rules.
+    #[serde(skip)]
+    pub status_field: Option<u32>,
+}
+
+/// Format-specific export settings. Each exporter reads the group it cares
+/// about and ignores the rest.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExportOptions {
+    pub calendar: crate::file_export_ics::IcsOptions,
+    pub chart: crate::chart::ChartOptions,
+    /// Earned value date; `Registry::export_file` falls back to the status rules.
+    #[serde(skip)]
+    pub status_date: Option<chrono::NaiveDate>,
+}
+
+/// A target format that can write a task list to disk.
+pub trait Exporter {
+    fn name(&self) -> &'static str;
+    fn extension(&self) -> &'static str;
+    fn capabilities(&self) -> Capabilities;
+    fn export(&self, tasks: &[Task], path: &Path, options: &ExportOptions) -> Result<(), Box<dyn std::error::Error>>;
+}
+
+/// The set of formats the GUI and CLI know about.
+pub struct Registry {
+    importers: Vec<Box<dyn Importer>>,
+    exporters: Vec<Box<dyn Exporter>>,
+    /// Checked against every imported task's status.
+    pub status_rules: crate::status::StatusRules,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Self {
+            importers: Vec::new(),
+            exporters: Vec::new(),
+            status_rules: crate::status::StatusRules::default(),
+        }
+    }
+
+    pub fn with_builtin_formats() -> Self {
+        let mut registry = Self::new();
+        registry.register_importer(Box::new(crate::file_import::MspdiImporter));
+        registry.register_importer(Box::new(crate::file_import::XlsxImporter));
+        registry.register_importer(Box::new(crate::file_import_p6::P6Importer));
+        registry.register_importer(Box::new(crate::file_import_gan::GanImporter));
+        registry.register_importer(Box::new(crate::file_import_planner::PlannerImporter));
+
+        // The first exporter is the default when a path has no extension
+        registry.register_exporter(Box::new(crate::file_export_xml::MspdiExporter));
+        registry.register_exporter(Box::new(crate::file_export::XlsxExporter));
+        registry.register_exporter(Box::new(crate::file_export_ics::IcsExporter));
+        registry.register_exporter(Box::new(crate::file_export_svg::SvgExporter));
+        registry.register_exporter(Box::new(crate::file_export_png::PngExporter));
+        registry.register_exporter(Box::new(crate::file_export_pdf::PdfExporter));
+        registry
+    }
+
+    pub fn register_importer(&mut self, importer: Box<dyn Importer>) {
+        self.importers.push(importer);
+    }
+
+    pub fn register_exporter(&mut self, exporter: Box<dyn Exporter>) {
+        self.exporters.push(exporter);
+    }
+
+    pub fn importers(&self) -> impl Iterator<Item = &dyn Importer> {
+        self.importers.iter().map(|i| i.as_ref())
+    }
+
+    pub fn exporters(&self) -> impl Iterator<Item = &dyn Exporter> {
+        self.exporters.iter().map(|e| e.as_ref())
+    }
+
+    /// All extensions any importer claims, for file dialogs.
+    pub fn import_extensions(&self) -> Vec<&'static str> {
+        let mut extensions: Vec<&'static str> = self.importers.iter()
+            .flat_map(|i| i.extensions().iter().copied())
+            .collect();
+        extensions.sort_unstable();
+        extensions.dedup();
+        extensions
+    }
+
+    pub fn export_extensions(&self) -> Vec<&'static str> {
+        self.exporters.iter().map(|e| e.extension()).collect()
+    }
+
+    /// Picks the importer most confident it can read the file. Ties go to
+    /// whichever was registered first.
+    pub fn detect_importer(&self, path: &Path, content: &[u8]) -> Result<&dyn Importer, String> {
+        let probe = Probe::new(path, content);
+        let mut best: Option<(&dyn Importer, Confidence)> = None;
+        for importer in self.importers() {
+            let confidence = importer.detect(&probe);
+            if confidence > best.map_or(Confidence::None, |(_, c)| c) {
+                best = Some((importer, confidence));
+            }
+        }
+        if let Some((importer, _)) = best {
+            return Ok(importer);
+        }
+
+        // OLE compound document: a native binary .mpp file
+        if content.starts_with(&[0xD0, 0xCF, 0x11, 0xE0]) || probe.has_extension(&["mpp"]) {
+            return Err("Binary MPP files are not directly supported. Please export your MPP file to XLSX or XML format first.".to_string());
+        }
+        match probe.xml_root {
+            Some(root) => Err(format!("Unsupported XML document with root element <{}>", root.name)),
+            None => Err("Unrecognised file format".to_string()),
+        }
+    }
+
+    /// Reads a file and imports it with whichever importer recognises it.
+    /// The result records the detected format and any defaulted values,
+    /// including statuses that contradict percent complete.
+    pub fn import_file(&self, path: &Path, options: &ImportOptions) -> Result<LoadedFile, FileError> {
+        let content = std::fs::read(path)
+            .map_err(|source| FileError::Io { path: path.to_path_buf(), source })?;
+        let importer = self.detect_importer(path, &content)
+            .map_err(|reason| FileError::UnknownFormat { path: path.to_path_buf(), reason })?;
+        let with_rules = ImportOptions { status_field: Some(self.status_rules.status_field), ..*options };
+        let (mut tasks, mut diagnostics) = importer.import_with_diagnostics(&content, &with_rules)
+            .map_err(|e| FileError::from_boxed(e).in_file(path))?;
+        diagnostics.extend(crate::status::apply_rules(&mut tasks, &self.status_rules));
+        let mut file = LoadedFile::new(path.to_path_buf(), importer.name(), tasks);
+        file.diagnostics = diagnostics;
+        file.import_options = *options;
+        Ok(file)
+    }
+
+    /// Writes `tasks` with the exporter for `path`'s extension and returns
+    /// the format name. Summary tasks are rolled up first, so edits to
+    /// their children are reflected.
+    pub fn export_file(&self, tasks: &[Task], path: &Path, options: &ExportOptions) -> Result<&'static str, FileError> {
+        let exporter = self.exporter_for_path(path)
+            .ok_or_else(|| FileError::NoExporter { path: path.to_path_buf(), supported: self.export_extensions() })?;
+        let mut tasks = tasks.to_vec();
+        crate::outline::apply_rollups(&mut tasks);
+        let status_date = options.status_date.unwrap_or_else(|| self.status_rules.status_date());
+        let options = ExportOptions { status_date: Some(status_date), ..options.clone() };
+        exporter.export(&tasks, path, &options)
+            .map_err(|e| FileError::from_boxed(e).in_file(path))?;
+        Ok(exporter.name())
+    }
+
+    /// Picks an exporter from the output path's extension, falling back to the default.
+    pub fn exporter_for_path(&self, path: &Path) -> Option<&dyn Exporter> {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) => self.exporters()
+                .find(|e| e.extension().eq_ignore_ascii_case(ext)),
+            None => self.exporters().next(),
+        }
+    }
+}
+
+pub fn sniff_xml_root(content: &[u8]) -> Option<XmlRoot> {
+    let content = content.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(content);
+    let mut reader = quick_xml::Reader::from_reader(content);
+    let mut buf = Vec::new();
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) | Ok(Event::Empty(e)) => {
+                let name = String::from_utf8_lossy(e.local_name().as_ref()).into_owned();
+                let attributes = e
+                    .attributes()
+                    .flatten()
+                    .map(|a| String::from_utf8_lossy(a.key.local_name().as_ref()).into_owned())
+                    .collect();
+                return Some(XmlRoot { name, attributes });
+            }
+            Ok(Event::Eof) | Err(_) => return None,
+            _ => {}
+        }
+        buf.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_importer_by_root_element() {
+        let registry = Registry::with_builtin_formats();
+        // Extension deliberately misleading: content wins
+        let detect = |xml: &str| registry
+            .detect_importer(Path::new("schedule.xlsx"), xml.as_bytes())
+            .map(|i| i.name());
+
+        assert_eq!(detect("<?xml version=\"1.0\"?><Project xmlns=\"http://schemas.microsoft.com/project\"/>"), Ok("Microsoft Project XML (MSPDI)"));
+        assert_eq!(detect("<APIBusinessObjects><Project/></APIBusinessObjects>"), Ok("Primavera P6 (PMXML)"));
+        assert_eq!(detect("<project name=\"x\" mrproject-version=\"2\"/>"), Ok("GNOME Planner"));
+        assert_eq!(detect("<project name=\"x\" version=\"3.2\"/>"), Ok("GanttProject"));
+    }
+
+    #[test]
+    fn test_detect_importer_falls_back_to_extension() {
+        let registry = Registry::with_builtin_formats();
+
+        let importer = registry.detect_importer(Path::new("plan.gan"), b"not xml at all").unwrap();
+        assert_eq!(importer.name(), "GanttProject");
+
+        assert!(registry.detect_importer(Path::new("page.html"), b"<html/>").is_err());
+        assert!(registry.detect_importer(Path::new("plan.mpp"), &[0xD0, 0xCF, 0x11, 0xE0]).is_err());
+    }
+
+    #[test]
+    fn test_exporter_for_path() {
+        let registry = Registry::with_builtin_formats();
+        assert_eq!(registry.exporter_for_path(Path::new("out.XLSX")).unwrap().extension(), "xlsx");
+        assert_eq!(registry.exporter_for_path(Path::new("out")).unwrap().extension(), "xml");
+        assert!(registry.exporter_for_path(Path::new("out.doc")).is_none());
+    }
+}
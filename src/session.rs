@@ -0,0 +1,155 @@
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use crate::filter::Filter;
+use crate::history::History;
+use crate::merge::{LoadedFile, MergeOptions};
+use crate::registry::{ExportOptions, ImportOptions};
+use crate::status::StatusRules;
+use crate::task::Task;
+use crate::WizardStep;
+
+pub const EXTENSION: &str = "mpmsession";
+const FORMAT_VERSION: u32 = 1;
+
+/// Everything needed to pick a merge up where it was left: the loaded files,
+/// the current tasks with their edit history, and the wizard's state.
+#[derive(Serialize, Deserialize)]
+pub struct Session {
+    pub version: u32,
+    pub saved_at: DateTime<Local>,
+    /// Imported files in merge order; the first enabled one is the base.
+    pub files: Vec<LoadedFile>,
+    #[serde(default)]
+    pub merge_stale: bool,
+    pub tasks: Vec<Task>,
+    #[serde(default)]
+    pub history: History,
+    pub step: WizardStep,
+    #[serde(default)]
+    pub filter_text: String,
+    #[serde(default)]
+    pub filter: Filter,
+    #[serde(default)]
+    pub sort_column: Option<usize>,
+    #[serde(default = "default_true")]
+    pub sort_ascending: bool,
+    #[serde(default)]
+    pub export_options: ExportOptions,
+    #[serde(default)]
+    pub status_rules: StatusRules,
+    #[serde(default)]
+    pub merge_options: MergeOptions,
+    /// For files added later; loaded files keep their own.
+    #[serde(default)]
+    pub import_options: ImportOptions,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Session {
+    pub fn new(step: WizardStep) -> Self {
+        Self {
+            version: FORMAT_VERSION,
+            saved_at: Local::now(),
+            files: Vec::new(),
+            merge_stale: false,
+            tasks: Vec::new(),
+            history: History::default(),
+            step,
+            filter_text: String::new(),
+            filter: Filter::default(),
+            sort_column: None,
+            sort_ascending: true,
+            export_options: ExportOptions::default(),
+            status_rules: StatusRules::default(),
+            merge_options: MergeOptions::default(),
+            import_options: ImportOptions::default(),
+        }
+    }
+
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Writes via a temporary file so a crash mid-save never leaves a
+    /// truncated session behind.
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        write_atomic(path, &self.to_json()?)
+    }
+
+    pub fn load(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        let content = fs::read_to_string(path)?;
+        let session: Session = serde_json::from_str(&content)
+            .map_err(|e| format!("Not a valid session file: {}", e))?;
+        if session.version > FORMAT_VERSION {
+            return Err(format!(
+                "Session was saved by a newer version (format {}, this build reads up to {})",
+                session.version, FORMAT_VERSION
+            ).into());
+        }
+        Ok(session)
+    }
+}
+
+pub fn write_atomic(path: &Path, content: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let temp = path.with_extension("tmp");
+    fs::write(&temp, content)?;
+    fs::rename(&temp, path)?;
+    Ok(())
+}
+
+/// Where the running session is periodically saved for crash recovery.
+pub fn autosave_path() -> Option<PathBuf> {
+    app_data_path(&format!("autosave.{}", EXTENSION))
+}
+
+/// A file in the app's own data directory, which is created if needed.
+pub fn app_data_path(file_name: &str) -> Option<PathBuf> {
+    let dir = data_dir()?.join("msproject-merge");
+    fs::create_dir_all(&dir).ok()?;
+    Some(dir.join(file_name))
+}
+
+fn data_dir() -> Option<PathBuf> {
+    let env_path = |name: &str| std::env::var_os(name).filter(|v| !v.is_empty()).map(PathBuf::from);
+    if cfg!(windows) {
+        env_path("APPDATA")
+    } else if cfg!(target_os = "macos") {
+        env_path("HOME").map(|home| home.join("Library/Application Support"))
+    } else {
+        env_path("XDG_DATA_HOME").or_else(|| env_path("HOME").map(|home| home.join(".local/share")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::Command;
+
+    #[test]
+    fn test_session_round_trip() {
+        let mut session = Session::new(WizardStep::Review);
+        session.files = vec![
+            LoadedFile::new(PathBuf::from("base.xml"), "Microsoft Project XML (MSPDI)", vec![Task::new("Design".to_string())]),
+            LoadedFile::new(PathBuf::from("overlay.xlsx"), "Excel", Vec::new()),
+        ];
+        let mut tasks = vec![Task::new("Design".to_string())];
+        session.history.execute(&mut tasks, Command::Reorder { order: vec![0] });
+        session.tasks = tasks;
+
+        let path = std::env::temp_dir().join(format!("session_round_trip_{}.{}", std::process::id(), EXTENSION));
+        session.save(&path).unwrap();
+        let loaded = Session::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.files[1].path, PathBuf::from("overlay.xlsx"));
+        assert_eq!(loaded.files[0].tasks.len(), 1);
+        assert_eq!(loaded.tasks[0].name, "Design");
+        assert!(loaded.history.can_undo());
+        assert!(loaded.step == WizardStep::Review);
+    }
+}
@@ -0,0 +1,155 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use crate::diagnostics::Diagnostic;
+use crate::task::{Task, TaskStatus};
+
+// Percent complete decides between Not Started, In Progress and Completed.
+// On Hold and Cancelled can't be told from progress, so they only come from
+// the file: a status column, MSPDI's Active flag or a custom text field.
+
+/// How stated statuses are checked against progress.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StatusRules {
+    /// When a stated status contradicts percent complete, replace it with
+    /// the derived one. Otherwise keep it and just flag the conflict.
+    pub percent_wins: bool,
+    /// The date "late" is judged against; today when unset.
+    pub status_date: Option<NaiveDate>,
+    /// `FieldID` of the MSPDI custom field read for On Hold and Cancelled.
+    pub status_field: u32,
+}
+
+impl Default for StatusRules {
+    fn default() -> Self {
+        Self { percent_wins: true, status_date: None, status_field: crate::mspdi::TEXT1_FIELD_ID }
+    }
+}
+
+impl StatusRules {
+    pub fn status_date(&self) -> NaiveDate {
+        self.status_date.unwrap_or_else(|| chrono::Local::now().date_naive())
+    }
+}
+
+pub fn from_percent(percent_complete: u32) -> TaskStatus {
+    match percent_complete {
+        0 => TaskStatus::NotStarted,
+        100.. => TaskStatus::Completed,
+        _ => TaskStatus::InProgress,
+    }
+}
+
+/// Whether `status` is set by hand rather than following progress.
+pub fn is_explicit(status: TaskStatus) -> bool {
+    matches!(status, TaskStatus::OnHold | TaskStatus::Cancelled)
+}
+
+/// On Hold or Cancelled from a custom field or similar free text.
+pub fn parse_explicit(text: &str) -> Option<TaskStatus> {
+    match text.trim().to_lowercase().as_str() {
+        "on hold" | "onhold" | "hold" | "paused" => Some(TaskStatus::OnHold),
+        "cancelled" | "canceled" | "dropped" => Some(TaskStatus::Cancelled),
+        _ => None,
+    }
+}
+
+/// Why the task's status disagrees with its percent complete, if it does.
+pub fn conflict(task: &Task) -> Option<String> {
+    if is_explicit(task.status) || from_percent(task.percent_complete) == task.status {
+        return None;
+    }
+    Some(format!("{:?} at {}% complete", task.status, task.percent_complete))
+}
+
+/// Past its finish on the status date and neither done nor cancelled.
+pub fn is_late(task: &Task, status_date: NaiveDate) -> bool {
+    task.finish.date() < status_date
+        && task.percent_complete < 100
+        && !matches!(task.status, TaskStatus::Completed | TaskStatus::Cancelled)
+}
+
+/// Checks imported statuses against progress. Conflicts become diagnostics,
+/// and the status is replaced when `rules.percent_wins`.
+pub fn apply_rules(tasks: &mut [Task], rules: &StatusRules) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    for task in tasks.iter_mut() {
+        let Some(reason) = conflict(task) else { continue };
+        let derived = from_percent(task.percent_complete);
+        let substituted = if rules.percent_wins {
+            format!("{:?}", derived)
+        } else {
+            format!("kept ({})", reason)
+        };
+        diagnostics.push(Diagnostic::new(format!("UID {}", task.uid), "Status", &format!("{:?}", task.status), substituted));
+        if rules.percent_wins {
+            task.status = derived;
+        }
+    }
+    diagnostics
+}
+
+/// Keeps an edited task consistent: a new percent complete moves the
+/// status along, marking it Not Started or Completed sets the percent, and
+/// entering an actual finish completes it.
+pub fn reconcile(after: &mut Task, before: &Task) {
+    if after.actual_finish.is_some() && before.actual_finish.is_none() {
+        after.percent_complete = 100;
+        after.status = TaskStatus::Completed;
+    } else if after.status != before.status {
+        match after.status {
+            TaskStatus::NotStarted => after.percent_complete = 0,
+            TaskStatus::Completed => after.percent_complete = 100,
+            _ => {}
+        }
+    } else if after.percent_complete != before.percent_complete && !is_explicit(after.status) {
+        after.status = from_percent(after.percent_complete);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(status: TaskStatus, percent: u32) -> Task {
+        let mut t = Task::new("Task".to_string());
+        t.status = status;
+        t.percent_complete = percent;
+        t
+    }
+
+    #[test]
+    fn test_status_rules() {
+        let mut tasks = vec![
+            task(TaskStatus::Completed, 30),
+            task(TaskStatus::OnHold, 30),
+            task(TaskStatus::InProgress, 30),
+        ];
+        let mut kept = tasks.clone();
+
+        let diagnostics = apply_rules(&mut tasks, &StatusRules::default());
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].substituted, "InProgress");
+        assert_eq!(tasks[0].status, TaskStatus::InProgress);
+        assert_eq!(tasks[1].status, TaskStatus::OnHold);
+
+        let rules = StatusRules { percent_wins: false, ..StatusRules::default() };
+        assert_eq!(apply_rules(&mut kept, &rules).len(), 1);
+        assert_eq!(kept[0].status, TaskStatus::Completed);
+        assert!(conflict(&kept[0]).is_some());
+
+        let before = task(TaskStatus::InProgress, 30);
+        let mut after = before.clone();
+        after.status = TaskStatus::Completed;
+        reconcile(&mut after, &before);
+        assert_eq!(after.percent_complete, 100);
+        let mut after = before.clone();
+        after.percent_complete = 0;
+        reconcile(&mut after, &before);
+        assert_eq!(after.status, TaskStatus::NotStarted);
+        let mut after = before.clone();
+        after.actual_finish = Some(crate::task::at_finish(NaiveDate::from_ymd_opt(2024, 1, 5).unwrap()));
+        reconcile(&mut after, &before);
+        assert_eq!((after.status, after.percent_complete), (TaskStatus::Completed, 100));
+    }
+}
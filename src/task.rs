@@ -1,28 +1,109 @@
-use chrono::NaiveDate;
+use chrono::{Datelike, NaiveDate, NaiveDateTime, NaiveTime, Timelike, Weekday};
 use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use crate::duration::Duration;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Task {
+    pub uid: i32,
     pub name: String,
     pub description: String,
-    pub start_date: NaiveDate,
-    pub end_date: NaiveDate,
+    #[serde(alias = "start_date", deserialize_with = "stored::start")]
+    pub start: NaiveDateTime,
+    #[serde(alias = "end_date", deserialize_with = "stored::finish")]
+    pub finish: NaiveDateTime,
     pub status: TaskStatus,
     pub priority: u32,
     pub assignee: String,
-    pub duration_days: u32,
+    #[serde(alias = "duration_days")]
+    pub duration: Duration,
     pub percent_complete: u32,
+    pub milestone: bool,
     pub source_file: usize,
     pub wbs: String,
     pub predecessors: Vec<Predecessor>,
+    /// Where tracked fields got their current value, keyed by field name.
+    /// Fields without an entry still hold what `source_file` imported.
+    #[serde(default)]
+    pub provenance: BTreeMap<String, FieldSource>,
+    /// Budgeted cost; 0 when the source has no costs.
+    #[serde(default)]
+    pub cost: f64,
+    #[serde(default)]
+    pub actual_cost: f64,
+    /// The plan as baselined, if it was.
+    #[serde(default)]
+    pub baseline: Option<Baseline>,
+    /// When work really started and finished, as reported.
+    #[serde(default, deserialize_with = "stored::optional_start")]
+    pub actual_start: Option<NaiveDateTime>,
+    #[serde(default, deserialize_with = "stored::optional_finish")]
+    pub actual_finish: Option<NaiveDateTime>,
+    /// Time spent so far.
+    #[serde(default, alias = "actual_duration_days")]
+    pub actual_duration: Duration,
+    /// Time still to go.
+    #[serde(default, alias = "remaining_duration_days")]
+    pub remaining_duration: Duration,
+}
+
+/// Baseline dates, duration and cost (MSPDI baseline 0).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Baseline {
+    #[serde(alias = "start_date", deserialize_with = "stored::start")]
+    pub start: NaiveDateTime,
+    #[serde(alias = "end_date", deserialize_with = "stored::finish")]
+    pub finish: NaiveDateTime,
+    #[serde(alias = "duration_days")]
+    pub duration: Duration,
+    pub cost: f64,
+}
+
+/// Fields whose origin is tracked through merges and edits, with display names.
+pub const TRACKED_FIELDS: [(&str, &str); 13] = [
+    ("name", "Name"),
+    ("description", "Description"),
+    ("start", "Start"),
+    ("finish", "Finish"),
+    ("duration", "Duration"),
+    ("percent_complete", "% Complete"),
+    ("status", "Status"),
+    ("priority", "Priority"),
+    ("assignee", "Assignee"),
+    ("actual_start", "Actual Start"),
+    ("actual_finish", "Actual Finish"),
+    ("actual_duration", "Actual Duration"),
+    ("remaining_duration", "Remaining Duration"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Origin {
+    /// Index into the loaded files.
+    File(usize),
+    /// Changed by hand in the review step.
+    Manual,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FieldSource {
+    pub origin: Origin,
+    pub at: NaiveDateTime,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct Predecessor {
     pub predecessor_uid: i32,
     pub link_type: i32,
+    /// Tenths of a minute, or tenths of a percent for percent lag formats.
     pub link_lag: i32,
+    /// MSPDI `LagFormat`: the unit the lag is entered and shown in.
+    #[serde(default = "default_lag_format")]
+    pub lag_format: i32,
+}
+
+fn default_lag_format() -> i32 {
+    crate::dependency::LAG_FORMAT_DAYS
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -58,20 +139,307 @@ impl Task {
     pub fn new(name: String) -> Self {
         let today = chrono::Local::now().date_naive();
         Self {
+            uid: 0,
             name,
             description: String::new(),
-            start_date: today,
-            end_date: today,
+            start: at_start(today),
+            finish: at_finish(today),
             status: TaskStatus::NotStarted,
-            priority: 0,
+            priority: crate::priority::DEFAULT,
             assignee: String::new(),
-            duration_days: 0,
+            duration: Duration::default(),
             percent_complete: 0,
+            milestone: false,
             source_file: 0,
             wbs: String::new(),
             predecessors: Vec::new(),
+            provenance: BTreeMap::new(),
+            cost: 0.0,
+            actual_cost: 0.0,
+            baseline: None,
+            actual_start: None,
+            actual_finish: None,
+            actual_duration: Duration::default(),
+            remaining_duration: Duration::default(),
+        }
+    }
+
+    /// Where `field`'s current value came from.
+    pub fn field_origin(&self, field: &str) -> Origin {
+        self.provenance.get(field).map_or(Origin::File(self.source_file), |s| s.origin)
+    }
+
+    pub fn set_field_origin(&mut self, field: &str, origin: Origin, at: NaiveDateTime) {
+        self.provenance.insert(field.to_string(), FieldSource { origin, at });
+    }
+
+    /// Attributes every tracked field that differs from `before` to `origin`.
+    pub fn record_changes(&mut self, before: &Task, origin: Origin, at: NaiveDateTime) {
+        let changed = [
+            self.name != before.name,
+            self.description != before.description,
+            self.start != before.start,
+            self.finish != before.finish,
+            self.duration != before.duration,
+            self.percent_complete != before.percent_complete,
+            self.status != before.status,
+            self.priority != before.priority,
+            self.assignee != before.assignee,
+            self.actual_start != before.actual_start,
+            self.actual_finish != before.actual_finish,
+            self.actual_duration != before.actual_duration,
+            self.remaining_duration != before.remaining_duration,
+        ];
+        for ((field, _), changed) in TRACKED_FIELDS.iter().zip(changed) {
+            if changed {
+                self.set_field_origin(field, origin, at);
+            }
+        }
+    }
+
+    /// Whether any field of this task came from file `index`.
+    pub fn touched_by(&self, index: usize) -> bool {
+        self.source_file == index
+            || self.provenance.values().any(|s| s.origin == Origin::File(index))
+    }
+
+    /// Moves the task to new dates. Duration follows the new span in working
+    /// time, keeping the unit it's shown in.
+    pub fn reschedule(&mut self, start: NaiveDateTime, finish: NaiveDateTime) {
+        let finish = finish.max(start);
+        self.start = start;
+        self.finish = finish;
+        if !self.milestone {
+            self.duration = Duration::from_minutes(working_minutes_between(start, finish), self.duration.unit);
+        }
+    }
+
+    /// Moves the task by `days` working days, keeping its times of day and
+    /// its duration.
+    pub fn shift(&mut self, days: i32) {
+        self.start = shift_working_days(self.start.date(), days).and_time(self.start.time());
+        self.finish = shift_working_days(self.finish.date(), days).and_time(self.finish.time());
+    }
+
+    /// Outline depth from the WBS code: "1" is level 1, "1.2.3" level 3.
+    pub fn outline_level(&self) -> usize {
+        if self.wbs.is_empty() {
+            1
+        } else {
+            self.wbs.split('.').count()
+        }
+    }
+}
+
+// Date-only values get MS Project's default working hours: starts at 8:00
+// and finishes at 17:00, with an hour off at noon.
+
+pub fn default_start_time() -> NaiveTime {
+    NaiveTime::from_hms_opt(8, 0, 0).unwrap()
+}
+
+pub fn default_finish_time() -> NaiveTime {
+    NaiveTime::from_hms_opt(17, 0, 0).unwrap()
+}
+
+pub fn at_start(date: NaiveDate) -> NaiveDateTime {
+    date.and_time(default_start_time())
+}
+
+pub fn at_finish(date: NaiveDate) -> NaiveDateTime {
+    date.and_time(default_finish_time())
+}
+
+/// "2024-03-04", or "2024-03-04 12:00" when the time isn't `default`.
+pub fn format_moment(moment: NaiveDateTime, default: NaiveTime) -> String {
+    if moment.time() == default {
+        moment.format("%Y-%m-%d").to_string()
+    } else {
+        moment.format("%Y-%m-%d %H:%M").to_string()
+    }
+}
+
+/// Working minutes of its day before `moment`: 0 at 8:00, 240 from noon to
+/// 13:00, 480 from 17:00.
+fn minutes_worked(moment: NaiveDateTime) -> u32 {
+    let minute = moment.hour() * 60 + moment.minute();
+    minute.clamp(8 * 60, 12 * 60) - 8 * 60 + minute.clamp(13 * 60, 17 * 60) - 13 * 60
+}
+
+/// How far through its working day `moment` is, from 0 to 1.
+pub fn day_fraction(moment: NaiveDateTime) -> f32 {
+    minutes_worked(moment) as f32 / crate::duration::MINUTES_PER_DAY as f32
+}
+
+/// Working minutes from `start` to `finish`.
+pub fn working_minutes_between(start: NaiveDateTime, finish: NaiveDateTime) -> u32 {
+    let days = working_days_between(start.date(), finish.date());
+    if days == 0 {
+        return 0;
+    }
+    let before_start = if is_working_day(start.date()) { minutes_worked(start) } else { 0 };
+    let until_finish = if is_working_day(finish.date()) { minutes_worked(finish) } else { crate::duration::MINUTES_PER_DAY };
+    ((days - 1) * crate::duration::MINUTES_PER_DAY + until_finish).saturating_sub(before_start)
+}
+
+/// The moment `minutes` of working time after `start`. Work that ends
+/// exactly at a day's end finishes at 17:00 rather than the next 8:00.
+pub fn add_working_minutes(start: NaiveDateTime, minutes: u32) -> NaiveDateTime {
+    let per_day = crate::duration::MINUTES_PER_DAY;
+    let (first_day, offset) = if is_working_day(start.date()) {
+        (start.date(), minutes_worked(start))
+    } else {
+        (add_working_days(start.date(), 1), 0)
+    };
+    let total = offset + minutes;
+    let (days, rest) = match (total / per_day, total % per_day) {
+        (days, 0) if days > 0 => (days - 1, per_day),
+        split => split,
+    };
+    let clock = if rest <= per_day / 2 { 8 * 60 + rest } else { 13 * 60 + rest - per_day / 2 };
+    add_working_days(first_day, days).and_time(NaiveTime::from_hms_opt(clock / 60, clock % 60, 0).unwrap())
+}
+
+/// Sessions saved before tasks had times stored plain dates.
+mod stored {
+    use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+    use serde::{Deserialize, Deserializer};
+
+    fn parse<'de, D: Deserializer<'de>>(text: &str, default: NaiveTime) -> Result<NaiveDateTime, D::Error> {
+        text.parse::<NaiveDateTime>()
+            .or_else(|_| text.parse::<NaiveDate>().map(|d| d.and_time(default)))
+            .map_err(serde::de::Error::custom)
+    }
+
+    pub fn start<'de, D: Deserializer<'de>>(d: D) -> Result<NaiveDateTime, D::Error> {
+        parse::<D>(&String::deserialize(d)?, super::default_start_time())
+    }
+
+    pub fn finish<'de, D: Deserializer<'de>>(d: D) -> Result<NaiveDateTime, D::Error> {
+        parse::<D>(&String::deserialize(d)?, super::default_finish_time())
+    }
+
+    pub fn optional_start<'de, D: Deserializer<'de>>(d: D) -> Result<Option<NaiveDateTime>, D::Error> {
+        Option::<String>::deserialize(d)?.map(|t| parse::<D>(&t, super::default_start_time())).transpose()
+    }
+
+    pub fn optional_finish<'de, D: Deserializer<'de>>(d: D) -> Result<Option<NaiveDateTime>, D::Error> {
+        Option::<String>::deserialize(d)?.map(|t| parse::<D>(&t, super::default_finish_time())).transpose()
+    }
+}
+
+pub fn is_working_day(date: NaiveDate) -> bool {
+    !matches!(date.weekday(), Weekday::Sat | Weekday::Sun)
+}
+
+/// Steps `days` working days forward from `start`, skipping weekends.
+pub fn add_working_days(start: NaiveDate, days: u32) -> NaiveDate {
+    let mut date = start;
+    let mut remaining = days;
+    while remaining > 0 {
+        date = date.succ_opt().unwrap_or(date);
+        if is_working_day(date) {
+            remaining -= 1;
         }
     }
+    date
 }
 
+/// Moves `date` by `days` working days, backwards when negative.
+pub fn shift_working_days(date: NaiveDate, days: i32) -> NaiveDate {
+    let mut date = date;
+    for _ in 0..days.unsigned_abs() {
+        loop {
+            date = if days < 0 { date.pred_opt() } else { date.succ_opt() }.unwrap_or(date);
+            if is_working_day(date) {
+                break;
+            }
+        }
+    }
+    date
+}
+
+/// Working days between `from` and the day `calendar_days` later (or
+/// earlier), counting the days passed over. Landing on a weekend counts as
+/// the working day before it when moving later, after it when earlier.
+pub fn working_day_offset(from: NaiveDate, calendar_days: i64) -> i32 {
+    let to = from + chrono::Duration::days(calendar_days);
+    match calendar_days.cmp(&0) {
+        Ordering::Greater => working_days_between(from.succ_opt().unwrap_or(from), to) as i32,
+        Ordering::Less => -(working_days_between(to, from.pred_opt().unwrap_or(from)) as i32),
+        Ordering::Equal => 0,
+    }
+}
+
+/// Working days from `start` to `end`, both inclusive.
+pub fn working_days_between(start: NaiveDate, end: NaiveDate) -> u32 {
+    start.iter_days()
+        .take_while(|d| *d <= end)
+        .filter(|d| is_working_day(*d))
+        .count() as u32
+}
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_changes_provenance() {
+        let at = at_start(NaiveDate::from_ymd_opt(2024, 1, 8).unwrap());
+        let mut task = Task::new("Design".to_string());
+        task.set_field_origin("percent_complete", Origin::File(1), at);
+        assert!(task.touched_by(0) && task.touched_by(1) && !task.touched_by(2));
+
+        // Only the fields that changed become manual
+        let before = task.clone();
+        task.assignee = "Alice".to_string();
+        task.record_changes(&before, Origin::Manual, at);
+        assert_eq!(task.field_origin("assignee"), Origin::Manual);
+        assert_eq!(task.field_origin("percent_complete"), Origin::File(1));
+        assert_eq!(task.field_origin("name"), Origin::File(0));
+
+        // Overwriting the overlay's only field by hand means it no longer touches the task
+        let before = task.clone();
+        task.percent_complete = 80;
+        task.record_changes(&before, Origin::Manual, at);
+        assert_eq!(task.field_origin("percent_complete"), Origin::Manual);
+        assert!(!task.touched_by(1));
+    }
+
+    #[test]
+    fn test_working_days() {
+        let fri = NaiveDate::from_ymd_opt(2024, 1, 5).unwrap();
+        let tue = NaiveDate::from_ymd_opt(2024, 1, 9).unwrap();
+        assert_eq!(add_working_days(fri, 2), tue);
+        assert_eq!(working_days_between(fri, tue), 3);
+        assert_eq!(shift_working_days(tue, -2), fri);
+        assert_eq!(shift_working_days(fri, 1), NaiveDate::from_ymd_opt(2024, 1, 8).unwrap());
+
+        let mut task = Task::new("Task".to_string());
+        task.reschedule(at_start(tue), at_finish(fri));
+        assert_eq!(task.finish, at_start(tue));
+        assert_eq!(task.duration.minutes, 0);
+
+        // Half days: the morning ends at noon, the afternoon starts at 13:00
+        let noon = tue.and_hms_opt(12, 0, 0).unwrap();
+        task.reschedule(at_start(tue), noon);
+        assert_eq!(task.duration.minutes, 240);
+        assert_eq!(working_minutes_between(tue.and_hms_opt(13, 0, 0).unwrap(), at_finish(tue)), 240);
+        assert_eq!(working_minutes_between(at_start(fri), at_finish(tue)), 3 * 480);
+        assert_eq!(day_fraction(noon), 0.5);
+        assert_eq!(add_working_minutes(at_start(tue), 240), noon);
+        assert_eq!(add_working_minutes(at_start(fri), 480), at_finish(fri));
+        assert_eq!(add_working_minutes(noon, 480), tue.succ_opt().unwrap().and_hms_opt(12, 0, 0).unwrap());
+        assert_eq!(format_moment(noon, default_finish_time()), "2024-01-09 12:00");
+
+        // Dragging Mon-Fri two days later is Wed-Tue, still five days of work
+        let mon = NaiveDate::from_ymd_opt(2024, 1, 8).unwrap();
+        task.reschedule(at_start(mon), at_finish(add_working_days(mon, 4)));
+        task.shift(working_day_offset(mon, 2));
+        assert_eq!((task.start, task.finish), (at_start(mon + chrono::Duration::days(2)), at_finish(tue + chrono::Duration::days(7))));
+        assert_eq!(working_minutes_between(task.start, task.finish), 5 * 480);
+        // Dropped on Saturday it stays on Friday
+        assert_eq!(working_day_offset(mon, 5), 4);
+        assert_eq!(working_day_offset(tue, -3), -1);
+    }
+}